@@ -1,22 +1,90 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    fs::{self, File},
     path::{Path, PathBuf},
-    sync::mpsc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
     {mem, thread},
 };
 
 use anyhow::{Context, Error as AnyhowError, Result as AnyhowResult, anyhow};
-use directories::UserDirs;
 use eframe::egui;
 use pathdiff::diff_paths;
 
 use crate::{
-    config::AppConfig,
-    scanner::{Conflicts, ScanError, scan_for_conflicts},
-    utils::{delete, open_in_explorer},
+    backups::{BackupSession, back_up, list_sessions},
+    compare::{InstallDiff, compare_installs},
+    config::{AppConfig, WindowGeometry},
+    content::{Divergence, KnownIdentical, classify_conflicts, divergent_keys, read_source},
+    decisions::{Decision, DecisionAction, render_log},
+    diff::{DiffLine, MAX_BYTE_RANGES, MAX_DIFF_LINES, ResourceDiff, diff_sources},
+    display::DisplayRules,
+    duplicates::{DuplicateInstall, find_duplicate_installs},
+    erf::{ErfFile, ErfIssue, ErfVersion},
+    loadorder::{
+        WinReason, explain_winner, resolve_winner, shadows_core, unexpected_winners,
+        winner_location,
+    },
+    pairing::unpaired_scripts,
+    palette::rank_matches,
+    plan::{PLAN_FILE, PlanAction, ResolutionPlan},
+    preview::{MAX_PREVIEW_BYTES, PreviewContent, ResourcePreview, load_preview},
+    profiles::exclude_foreign_generated,
+    queue::{QueueEvent, run_queue},
+    restype::ResourceType,
+    safety::{DeleteRules, DeleteSafety},
+    scanner::{
+        Conflicts, Grouping, ScanError, ScanOptions, ScanProgress, ScanReport, ScanWarning,
+        SourceInfo, SourceKind, addin_root, matches_pattern, override_package, resource_name,
+        retry_archives, scan_for_conflicts, summarize_conflicts,
+    },
+    snapshots::{ProviderChange, Snapshot, load_snapshots, provider_timeline, record_snapshot},
+    summary::{ScanSummary, resource_type, summarize},
+    tlk::{MAX_TLK_ENTRIES, StringDiff, TalkTable, diff_tables},
+    utils::{
+        copy_with_metadata, delete, disable, enable, format_date, format_size, is_in_use, open_url,
+        reveal_in_file_manager, search_url, unix_timestamp,
+    },
+    verify::{VerifyReport, verify_archives},
+    view::{
+        ConflictEntry, ConflictSection, ListView, ResolutionCounts, ResultsFilter, SectionKind,
+        SortMode, SourceRow, ViewState, collapse_sources, first_match, is_actionable, render_csv,
+        render_json, render_report, sort_entries,
+    },
 };
 
 const BUTTON_RADIUS: f32 = 3.0;
+/// Most differing strings listed per source in the talk table preview.
+const MAX_SHOWN_STRING_DIFFS: usize = 500;
+
+const SCAN_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F5);
+const RESCAN_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::R);
+const EXPAND_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::E);
+const COLLAPSE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::W);
+const SEARCH_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::F);
+const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+const FIND_RESOURCE_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::F,
+);
+/// Most matches listed by the resource finder.
+const MAX_FIND_RESULTS: usize = 200;
+/// Applied commands that can be undone, oldest dropped first.
+const MAX_UNDO_STEPS: usize = 50;
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Most toasts stacked at once, older ones are dropped first.
+const MAX_TOASTS: usize = 5;
 
 fn setup_theme(ctx: &egui::Context) {
     ctx.set_theme(egui::Theme::Dark);
@@ -27,50 +95,555 @@ fn setup_theme(ctx: &egui::Context) {
     });
 }
 
+fn show_modal_overlay(ctx: &egui::Context) {
+    egui::Area::new(egui::Id::new("modal_overlay"))
+        .order(egui::Order::Background)
+        .show(ctx, |ui| {
+            ui.painter().rect_filled(
+                ui.ctx().screen_rect(),
+                egui::CornerRadius::ZERO,
+                egui::Color32::from_black_alpha(150),
+            );
+        });
+}
+
+/// A short confirmation of a finished action, dismissed after [`TOAST_DURATION`].
+struct Toast {
+    message: String,
+    shown_at: Instant,
+}
+
+type PaletteAction = Box<dyn Fn(&mut App, &Path)>;
+
+struct PaletteCommand {
+    name: String,
+    enabled: bool,
+    action: PaletteAction,
+}
+
+impl PaletteCommand {
+    fn new(
+        name: impl Into<String>,
+        enabled: bool,
+        action: impl Fn(&mut App, &Path) + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            enabled,
+            action: Box::new(action),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CompareState {
+    first: String,
+    second: String,
+    result: Option<InstallDiff>,
+}
+
+/// A delete waiting for the user to type the confirmation word.
+struct DeleteConfirmation {
+    key: String,
+    path: PathBuf,
+    typed: String,
+}
+
+/// Keeping one source of a conflict, waiting for the user to confirm deleting
+/// the other loose files.
+struct KeepOnlyConfirmation {
+    key: String,
+    kept: PathBuf,
+    deletions: Vec<PathBuf>,
+    /// Loose files the delete rules don't allow deleting, left in place.
+    blocked: Vec<PathBuf>,
+    /// A deletion is a high-risk type, so the confirmation word must be typed.
+    needs_typed: bool,
+    typed: String,
+}
+
+const DELETE_CONFIRMATION_WORD: &str = "DELETE";
+
+#[derive(Default)]
+struct PaletteState {
+    query: String,
+    selected: usize,
+}
+
 pub struct App {
     config: AppConfig,
     conflicts: Conflicts,
     status: String,
     error: Option<AnyhowError>,
     pending_commands: Vec<Command>,
-    expanded_conflicts: HashSet<String>,
+    /// Actions held back for review while staging is on.
+    staged_commands: Vec<Command>,
+    /// Collapsed source rows opened by the user, by conflict key and folder.
+    expanded_layers: HashSet<(String, PathBuf)>,
     scan_thread: Option<thread::JoinHandle<()>>,
-    receiver: Option<mpsc::Receiver<Result<Conflicts, ScanError>>>,
+    /// Set to stop the running scan early.
+    scan_cancel: Arc<AtomicBool>,
+    receiver: Option<mpsc::Receiver<Result<ScanReport, ScanError>>>,
+    progress_receiver: Option<mpsc::Receiver<ScanProgress>>,
+    scan_progress: ScanProgress,
     has_scanned: bool,
+    archives: Vec<PathBuf>,
+    /// Archives the last scan couldn't read, offered for a retry.
+    failed_archives: Vec<PathBuf>,
+    scan_warnings: Vec<ScanWarning>,
+    /// The last scan skipped archives.
+    override_only: bool,
+    single_providers: HashMap<String, PathBuf>,
+    /// Every resource the last scan found, conflicting or not.
+    resource_index: Conflicts,
+    /// Query of the open resource finder.
+    resource_finder: Option<String>,
+    source_info: HashMap<(String, PathBuf), SourceInfo>,
+    pattern_input: String,
+    ignored_name_input: String,
+    /// Files disabled by the app, with the conflict key each was disabled from.
+    disabled_files: Vec<(String, PathBuf)>,
+    addins: HashMap<PathBuf, String>,
+    verify_thread: Option<thread::JoinHandle<()>>,
+    verify_receiver: Option<mpsc::Receiver<VerifyReport>>,
+    preview: Option<ResourcePreview>,
+    /// Inverses of the latest applied commands, most recent last.
+    undo_stack: Vec<UndoAction>,
+    /// Confirmations of finished actions, oldest first.
+    toasts: Vec<Toast>,
+    /// A folder outside the BioWare folder scanned in its place, such as a
+    /// modpack staging folder. Its resolutions are never saved.
+    scanned_folder: Option<PathBuf>,
+    forget_all_confirmation: bool,
+    /// Name typed into the profile selector for a new profile.
+    new_profile_name: String,
+    /// Timestamp naming this session's backup folder.
+    backup_session: u64,
+    /// Past backup sessions listed for a restore.
+    backup_sessions: Option<Vec<BackupSession>>,
+    /// Sources picked for a diff, by conflict key, compared once two are picked.
+    diff_picks: Option<(String, Vec<PathBuf>)>,
+    diff_view: Option<DiffView>,
+    diff_receiver: Option<mpsc::Receiver<AnyhowResult<DiffView>>>,
+    preview_receiver: Option<mpsc::Receiver<AnyhowResult<ResourcePreview>>>,
+    verify_report: Option<VerifyReport>,
+    inspected_archive: Option<(PathBuf, ErfFile, Vec<ErfIssue>)>,
+    /// Size and compression of archive sources, read when first hovered.
+    archive_details: HashMap<(PathBuf, String), Option<String>>,
+    list_view: ListView,
+    palette: Option<PaletteState>,
+    only_actionable: bool,
+    unexpected_winners: HashSet<String>,
+    summary: Option<ScanSummary>,
+    show_summary: bool,
+    display_rules: DisplayRules,
+    divergence: HashMap<String, Divergence>,
+    compare: Option<CompareState>,
+    compare_thread: Option<thread::JoinHandle<()>>,
+    compare_receiver: Option<mpsc::Receiver<Result<InstallDiff, ScanError>>>,
+    scan_queue: Option<ScanQueue>,
+    queue_thread: Option<thread::JoinHandle<()>>,
+    queue_receiver: Option<mpsc::Receiver<QueueEvent>>,
+    snapshots: Vec<Snapshot>,
+    history_key: Option<String>,
+    tlk_preview: Option<TlkPreview>,
+    tlk_receiver: Option<mpsc::Receiver<AnyhowResult<TlkPreview>>>,
+    /// An imported plan awaiting confirmation before it's applied.
+    plan_review: Option<ResolutionPlan>,
+    results_filter: ResultsFilter,
+    scan_on_start: bool,
+    /// Inspection only, every command that changes files or resolutions is refused.
+    read_only: bool,
+    /// A command that failed because a file was in use, offered again from the error dialog.
+    retry_command: Option<Command>,
+    delete_confirmation: Option<DeleteConfirmation>,
+    keep_only: Option<KeepOnlyConfirmation>,
+    /// The first conflict matching the search, highlighted in the list.
+    search_focus: Option<String>,
+    /// Move keyboard focus to the search box on the next frame.
+    focus_search: bool,
+    scroll_to_focus: bool,
+}
+
+/// Folders to scan one after another, with the result of each.
+#[derive(Default)]
+struct ScanQueue {
+    input: String,
+    targets: Vec<PathBuf>,
+    /// One slot per target, filled in as the queue runs.
+    results: Vec<Option<Result<Box<ScanReport>, String>>>,
+    /// The target being scanned, zero-based.
+    current: Option<usize>,
+}
+
+/// The inverse of an applied command.
+#[derive(Debug)]
+enum UndoAction {
+    Unignore(String),
+    Reignore {
+        key: String,
+        paths: Vec<PathBuf>,
+        ignored_at: Option<u64>,
+    },
+    /// Ignores again every conflict forgotten at once, with when each was ignored.
+    ReignoreAll(Vec<(String, Vec<PathBuf>, Option<u64>)>),
+    /// Re-enables a file disabled from a conflict.
+    Enable {
+        key: String,
+        disabled: PathBuf,
+    },
+    /// Re-enables the files of a duplicate install and puts their sources back
+    /// into the conflicts they were removed from.
+    EnableInstall {
+        disabled: Vec<PathBuf>,
+        sources: Vec<(String, PathBuf)>,
+    },
+    /// Copies a deleted file or rewritten archive back from its backup.
+    Restore {
+        key: String,
+        original: PathBuf,
+        backup: PathBuf,
+    },
+}
+
+impl UndoAction {
+    /// Undoes ignoring `key`, bringing back its earlier resolution if it had one.
+    fn before_ignoring(config: &AppConfig, key: &str) -> Self {
+        match config.ignored.get(key) {
+            Some(paths) => UndoAction::Reignore {
+                key: key.to_string(),
+                paths: paths.clone(),
+                ignored_at: config.ignored_at.get(key).copied(),
+            },
+            None => UndoAction::Unignore(key.to_string()),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name()
+                .map_or_else(String::new, |n| n.to_string_lossy().into_owned())
+        };
+
+        match self {
+            UndoAction::Unignore(key) => format!("ignoring {key}"),
+            UndoAction::Reignore { key, .. } => format!("forgetting the resolution of {key}"),
+            UndoAction::ReignoreAll(forgotten) => {
+                format!("forgetting {} resolved conflicts", forgotten.len())
+            }
+            UndoAction::Enable { disabled, .. } => {
+                format!("disabling {}", name(disabled.with_extension("").as_path()))
+            }
+            UndoAction::EnableInstall { disabled, .. } => {
+                format!("disabling {} duplicate files", disabled.len())
+            }
+            UndoAction::Restore { key, original, .. } => {
+                format!("removing {key} from {}", name(original))
+            }
+        }
+    }
+}
+
+/// Two sources of one conflict compared with each other.
+struct DiffView {
+    key: String,
+    first: PathBuf,
+    second: PathBuf,
+    diff: ResourceDiff,
+}
+
+/// Talk table strings that differ between a conflict's winner and its other sources.
+struct TlkPreview {
+    key: String,
+    winner: PathBuf,
+    /// Strings differing from the winner, per other source.
+    comparisons: Vec<(PathBuf, Result<Vec<StringDiff>, String>)>,
+    /// Some table held more strings than were read.
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExportFormat {
+    Csv,
+    Json,
 }
 
 #[derive(Debug)]
 enum Command {
     IgnoreConflict(String, Vec<PathBuf>),
     UnignoreConflict(String),
+    /// Forgets every resolved conflict.
+    UnignoreAll,
     DeleteConflictFile(String, PathBuf),
+    /// Rewrites an archive without the conflicting resource.
+    RemoveFromArchive(String, PathBuf),
+    DisableConflictFile(String, PathBuf),
+    /// Disables a whole redundant install, one entry per conflict source.
+    DisableDuplicateInstall(Vec<(String, PathBuf)>),
+    /// Restores a disabled file, by the conflict key it was disabled from.
+    EnableFile(String, PathBuf),
+    SetPreferredProvider(String, PathBuf),
+    ClearPreferredProvider(String),
+}
+
+impl Command {
+    /// Whether the command changes the saved ignore list or preferred sources.
+    fn changes_resolutions(&self) -> bool {
+        matches!(
+            self,
+            Command::IgnoreConflict(..)
+                | Command::UnignoreConflict(..)
+                | Command::UnignoreAll
+                | Command::SetPreferredProvider(..)
+                | Command::ClearPreferredProvider(..)
+        )
+    }
+
+    /// Whether the command changes files on disk or the recorded resolutions.
+    fn is_mutating(&self) -> bool {
+        match self {
+            Command::IgnoreConflict(..)
+            | Command::UnignoreConflict(..)
+            | Command::UnignoreAll
+            | Command::DeleteConflictFile(..)
+            | Command::RemoveFromArchive(..)
+            | Command::DisableConflictFile(..)
+            | Command::DisableDuplicateInstall(..)
+            | Command::EnableFile(..)
+            | Command::SetPreferredProvider(..)
+            | Command::ClearPreferredProvider(..) => true,
+        }
+    }
+
+    /// The commands carrying out a plan's operations, leaving out deletions the
+    /// delete rules block. Returns how many were left out.
+    fn from_plan(plan: ResolutionPlan, rules: &DeleteRules) -> (Vec<Self>, usize) {
+        let mut commands = Vec::new();
+        let mut blocked = 0;
+
+        for operation in plan.operations {
+            match operation.action {
+                PlanAction::Disable => {
+                    commands.push(Command::DisableConflictFile(operation.key, operation.path))
+                }
+                PlanAction::Delete if rules.check(&operation.path) == DeleteSafety::Blocked => {
+                    blocked += 1;
+                }
+                PlanAction::Delete => {
+                    commands.push(Command::DeleteConflictFile(operation.key, operation.path))
+                }
+            }
+        }
+
+        (commands, blocked)
+    }
+
+    /// Refuses the command in read-only mode, and refuses resolution changes
+    /// while a folder other than the BioWare folder is open.
+    fn check_allowed(&self, read_only: bool, scanned_folder: bool) -> AnyhowResult<()> {
+        if read_only && self.is_mutating() {
+            return Err(anyhow!(
+                "The scanner is in read-only mode, files and resolutions can't be changed"
+            ));
+        }
+        if scanned_folder && self.changes_resolutions() {
+            return Err(anyhow!(
+                "Ignores and preferred sources are only kept for the BioWare folder, \
+                 go back to it to resolve conflicts this way"
+            ));
+        }
+        Ok(())
+    }
+
+    /// One line describing the command for the staged actions list.
+    fn describe(&self, bioware_dir: &Path) -> String {
+        let show = |path: &Path| {
+            diff_paths(path, bioware_dir)
+                .unwrap_or_else(|| path.to_path_buf())
+                .display()
+                .to_string()
+        };
+
+        match self {
+            Command::IgnoreConflict(key, _) => format!("Ignore {key}"),
+            Command::UnignoreConflict(key) => format!("Restore {key}"),
+            Command::UnignoreAll => "Forget every resolved conflict".to_string(),
+            Command::DeleteConflictFile(key, path) => format!("Delete {} ({key})", show(path)),
+            Command::RemoveFromArchive(key, path) => {
+                format!("Remove {key} from {}", show(path))
+            }
+            Command::DisableConflictFile(key, path) => format!("Disable {} ({key})", show(path)),
+            Command::DisableDuplicateInstall(files) => {
+                format!("Disable a duplicate install ({} sources)", files.len())
+            }
+            Command::EnableFile(key, path) => format!("Enable {} ({key})", show(path)),
+            Command::SetPreferredProvider(key, path) => {
+                format!("Prefer {} for {key}", show(path))
+            }
+            Command::ClearPreferredProvider(key) => format!("Clear the preferred source of {key}"),
+        }
+    }
+
+    /// What the command did once applied, for a toast.
+    fn outcome(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            )
+        };
+
+        match self {
+            Command::IgnoreConflict(key, _) => format!("Ignored {key}"),
+            Command::UnignoreConflict(key) => format!("Restored {key}"),
+            Command::UnignoreAll => "Forgot every resolved conflict".to_string(),
+            Command::DeleteConflictFile(_, path) => format!("Deleted {}", name(path)),
+            Command::RemoveFromArchive(key, path) => {
+                format!("Removed {key} from {}", name(path))
+            }
+            Command::DisableConflictFile(_, path) => format!("Disabled {}", name(path)),
+            Command::DisableDuplicateInstall(files) => {
+                format!("Disabled a duplicate install ({} sources)", files.len())
+            }
+            Command::EnableFile(_, path) => format!("Enabled {}", name(path)),
+            Command::SetPreferredProvider(key, path) => {
+                format!("Preferred {} for {key}", name(path))
+            }
+            Command::ClearPreferredProvider(key) => {
+                format!("Cleared the preferred source of {key}")
+            }
+        }
+    }
 }
 
 impl App {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        results_filter: ResultsFilter,
+        read_only: bool,
+    ) -> Self {
         setup_theme(&cc.egui_ctx);
 
+        let (config, load_error) = AppConfig::load();
+        let (display_rules, rule_errors) = DisplayRules::compile(&config.display_rules);
+        let error = match load_error {
+            Some(err) => Some(err.context(
+                "Your saved settings and ignore list couldn't be read, so defaults are in use. \
+                 The unreadable file was kept as config.toml.bak.",
+            )),
+            None => (!rule_errors.is_empty()).then(|| anyhow!(rule_errors.join("\n"))),
+        };
+        let (snapshots, error) = match load_snapshots() {
+            Ok(snapshots) => (snapshots, error),
+            Err(err) => (
+                Vec::new(),
+                error.or_else(|| {
+                    Some(err.context("Your scan history couldn't be read, so it starts empty."))
+                }),
+            ),
+        };
+
+        let scan_on_start = !results_filter.is_empty();
+        let read_only = read_only || config.read_only;
+        let mut view = if config.remember_view {
+            config.view_state.clone()
+        } else {
+            ViewState::default()
+        };
+        // A filter given on the command line replaces the remembered one
+        if scan_on_start {
+            view.filter = results_filter;
+        }
+
         Self {
-            config: AppConfig::load(),
+            config,
             conflicts: Conflicts::new(),
             status: "Waiting for a scan...".into(),
-            error: None,
+            error,
             scan_thread: None,
+            scan_cancel: Arc::new(AtomicBool::new(false)),
             receiver: None,
+            progress_receiver: None,
+            scan_progress: ScanProgress::default(),
             pending_commands: Vec::new(),
-            expanded_conflicts: HashSet::new(),
+            staged_commands: Vec::new(),
+            expanded_layers: HashSet::new(),
             has_scanned: false,
+            archives: Vec::new(),
+            failed_archives: Vec::new(),
+            scan_warnings: Vec::new(),
+            override_only: false,
+            single_providers: HashMap::new(),
+            resource_index: Conflicts::new(),
+            resource_finder: None,
+            source_info: HashMap::new(),
+            pattern_input: String::new(),
+            ignored_name_input: String::new(),
+            disabled_files: Vec::new(),
+            addins: HashMap::new(),
+            verify_thread: None,
+            verify_receiver: None,
+            preview: None,
+            undo_stack: Vec::new(),
+            toasts: Vec::new(),
+            scanned_folder: None,
+            forget_all_confirmation: false,
+            new_profile_name: String::new(),
+            backup_session: unix_timestamp(),
+            backup_sessions: None,
+            diff_picks: None,
+            diff_view: None,
+            diff_receiver: None,
+            preview_receiver: None,
+            verify_report: None,
+            inspected_archive: None,
+            archive_details: HashMap::new(),
+            list_view: view.list_view,
+            palette: None,
+            only_actionable: view.only_actionable,
+            unexpected_winners: HashSet::new(),
+            summary: None,
+            show_summary: false,
+            display_rules,
+            divergence: HashMap::new(),
+            compare: None,
+            compare_thread: None,
+            compare_receiver: None,
+            scan_queue: None,
+            queue_thread: None,
+            queue_receiver: None,
+            snapshots,
+            history_key: None,
+            tlk_preview: None,
+            tlk_receiver: None,
+            plan_review: None,
+            scan_on_start,
+            read_only,
+            results_filter: view.filter,
+            retry_command: None,
+            delete_confirmation: None,
+            keep_only: None,
+            search_focus: None,
+            focus_search: false,
+            scroll_to_focus: false,
         }
     }
 
     fn start_scan(&mut self, bioware_dir: &Path) {
         self.has_scanned = true;
+        self.archive_details.clear();
         let (tx, rx) = mpsc::channel();
         self.receiver = Some(rx);
+        let (progress_tx, progress_rx) = mpsc::channel();
+        self.progress_receiver = Some(progress_rx);
+        self.scan_progress = ScanProgress::default();
 
         let game_dir = bioware_dir.to_path_buf();
+        let options = self.scan_options();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.scan_cancel = Arc::clone(&cancel);
         self.scan_thread = Some(thread::spawn(move || {
-            let result = scan_for_conflicts(&game_dir);
+            let result = scan_for_conflicts(&game_dir, &options, Some(&progress_tx), Some(&cancel));
             let _ = tx.send(result);
         }));
 
@@ -78,312 +651,4072 @@ impl App {
         self.conflicts.clear();
     }
 
+    /// The configured scan options, adjusted when a folder other than the
+    /// BioWare folder is open.
+    fn scan_options(&self) -> ScanOptions {
+        let mut options = self.config.scan_options();
+        if self.scanned_folder.is_some() {
+            options.loose_anywhere = true;
+            options.override_only = false;
+            // The cache and known identical groups describe the BioWare folder
+            options.toc_cache = None;
+            options.known_identical.clear();
+        }
+        options
+    }
+
+    /// The folder being scanned, the BioWare folder unless another was opened.
+    fn scan_root(&self) -> Option<PathBuf> {
+        self.scanned_folder
+            .clone()
+            .or_else(|| self.config.bioware_dir())
+    }
+
+    /// Asks for a folder to scan instead of the BioWare folder, without
+    /// changing which BioWare folder is configured.
+    fn scan_folder(&mut self) {
+        let Some(dir) = rfd::FileDialog::new()
+            .set_title("Choose a folder to scan")
+            .pick_folder()
+        else {
+            return;
+        };
+
+        self.scanned_folder = Some(dir.canonicalize().unwrap_or(dir));
+        self.conflicts.clear();
+        self.has_scanned = false;
+        self.undo_stack.clear();
+        self.scan_on_start = true;
+    }
+
+    fn close_scanned_folder(&mut self) {
+        self.scanned_folder = None;
+        self.conflicts.clear();
+        self.has_scanned = false;
+        self.undo_stack.clear();
+        self.scan_on_start = true;
+    }
+
     fn process_scan_results(&mut self) {
-        if let Some(receiver) = &self.receiver {
-            if let Ok(result) = receiver.try_recv() {
-                match result {
-                    Ok(conflicts) => {
-                        self.conflicts = conflicts;
+        if let Some(progress) = &self.progress_receiver
+            && let Some(latest) = progress.try_iter().last()
+        {
+            self.scan_progress = latest;
+        }
+        if self.scan_thread.is_none() {
+            self.progress_receiver = None;
+        }
+
+        if let Some(receiver) = &self.receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(mut report) => {
+                    let excluded_generated = exclude_foreign_generated(
+                        &mut report.conflicts,
+                        &self.config.generated_files,
+                        &self.config.active_profile,
+                    );
+                    self.conflicts = report.conflicts;
+                    self.archives = report.archives;
+                    self.failed_archives = report.failed_archives;
+                    self.scan_warnings = report.warnings;
+                    self.override_only = report.override_only;
+                    self.single_providers = report.single_providers;
+                    self.resource_index = report.resource_index;
+                    self.source_info = report.source_info;
+                    self.disabled_files = report.disabled;
+                    self.addins = report.addins;
+                    self.refresh_unexpected_winners();
+                    // Resolutions belong to the BioWare folder, another folder leaves them be
+                    let keep_config = self.scanned_folder.is_none();
 
-                        // Remove old conflicts when new ones are found
+                    // Remove old conflicts when new ones are found
+                    if keep_config {
                         self.config.ignored.retain(|key, ignored_paths| {
                             self.conflicts
                                 .get(key)
-                                .map_or(false, |paths| paths == ignored_paths)
+                                .is_some_and(|paths| paths == ignored_paths)
                         });
-                        self.expanded_conflicts
+                        let ignored = &self.config.ignored;
+                        self.config
+                            .ignored_at
+                            .retain(|key, _| ignored.contains_key(key));
+                        self.config
+                            .expanded_conflicts
                             .retain(|k| self.conflicts.contains_key(k));
+                        if self.config.auto_expand_divergent {
+                            self.config
+                                .expanded_conflicts
+                                .extend(divergent_keys(&report.divergence));
+                        }
+                    }
 
-                        self.status = format!("Found {} conflicts!", self.conflicts.len());
-
-                        let _ = self.config.save();
+                    self.summary = Some(summarize(
+                        &report.root,
+                        &self.conflicts,
+                        |key, paths| self.is_unresolved(key, paths),
+                        &report.divergence,
+                        &self.unexpected_winners,
+                        &report.stats,
+                        |path| fs::metadata(path).map(|m| m.len()),
+                    ));
+                    if report.compared_contents && keep_config {
+                        self.refresh_known_identical(&report.divergence, &report.trusted_identical);
                     }
-                    Err(e) => {
-                        self.status = "Scan failed!".into();
-                        self.error = Some(e.into());
+                    self.divergence = report.divergence;
+
+                    if keep_config {
+                        let snapshot = Snapshot {
+                            timestamp: unix_timestamp(),
+                            conflicts: self.conflicts.clone(),
+                        };
+                        if let Err(err) = record_snapshot(&mut self.snapshots, snapshot) {
+                            self.notify(format!("Could not save the scan history: {err:#}"));
+                        }
                     }
-                }
+                    // A filtered launch goes straight to the matching conflicts
+                    self.show_summary = self.results_filter.is_empty();
 
-                self.receiver = None;
-                self.scan_thread = None;
-            }
-        }
-    }
+                    let summary = summarize_conflicts(&self.conflicts);
+                    self.status = if summary.conflicts == 0 {
+                        summary.describe()
+                    } else {
+                        format!(
+                            "{} {}",
+                            self.resolution_counts().describe(),
+                            summary.sources()
+                        )
+                    };
+                    if self.override_only {
+                        self.status.push_str(" (override only)");
+                    }
+                    if excluded_generated > 0 {
+                        self.status.push_str(&format!(
+                            " (excluded {excluded_generated} files generated by other profiles)"
+                        ));
+                    }
+                    if !report.trusted_identical.is_empty() {
+                        self.status.push_str(&format!(
+                            " (trusted {} known identical groups)",
+                            report.trusted_identical.len()
+                        ));
+                    }
+                    if report.stats.collapsed_links > 0 {
+                        self.status.push_str(&format!(
+                            " (merged {} hardlinked files)",
+                            report.stats.collapsed_links
+                        ));
+                    }
+                    if report.stats.skipped_empty > 0 {
+                        self.status.push_str(&format!(
+                            " (skipped {} empty files)",
+                            report.stats.skipped_empty
+                        ));
+                    }
+                    if report.stats.hidden_identical > 0 {
+                        self.status.push_str(&format!(
+                            " (hid {} identical duplicates)",
+                            report.stats.hidden_identical
+                        ));
+                    }
 
-    fn handle_commands(&mut self) -> AnyhowResult<()> {
-        let commands = mem::take(&mut self.pending_commands);
-        for command in commands {
-            match command {
-                Command::IgnoreConflict(key, paths) => {
-                    self.config.ignored.insert(key, paths);
+                    if keep_config {
+                        let _ = self.config.save();
+                    }
                 }
-                Command::UnignoreConflict(key) => {
-                    self.config.ignored.remove(&key);
+                Err(ScanError::Cancelled) => {
+                    self.status = "Scan cancelled".into();
+                    self.has_scanned = false;
                 }
-                Command::DeleteConflictFile(key, path) => {
-                    delete(&path).context(format!("Failed to delete {}", path.display()))?;
-
-                    if let Some(paths) = self.conflicts.get_mut(&key) {
-                        paths.retain(|p| p != &path);
-                        if paths.is_empty() {
-                            self.conflicts.remove(&key);
-                        }
-                    }
+                Err(e) => {
+                    self.status = "Scan failed!".into();
+                    self.error = Some(e.into());
                 }
             }
-        }
 
-        self.config.save().context("Failed to save config")?;
-        Ok(())
+            self.receiver = None;
+            self.scan_thread = None;
+        }
     }
 
-    fn expand_all(&mut self) {
-        self.expanded_conflicts = self.conflicts.keys().cloned().collect();
-    }
+    /// Stores the window geometry so the next launch reopens it in place.
+    fn remember_window(&mut self, ctx: &egui::Context) {
+        let (inner, outer) = ctx.input(|i| (i.viewport().inner_rect, i.viewport().outer_rect));
+        let Some(inner) = inner else {
+            return;
+        };
 
-    fn collapse_all(&mut self) {
-        self.expanded_conflicts.clear();
+        self.config.window = Some(WindowGeometry {
+            size: inner.size().into(),
+            position: outer.map(|rect| rect.min.into()),
+        });
+        let _ = self.config.save();
     }
-}
 
-impl App {
-    fn show_error_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(err) = &self.error {
-            let mut open = true;
-            let mut should_clear_error = false;
+    fn retry_failed_archives(&mut self, bioware_dir: &Path) {
+        let options = self.scan_options();
+        let outcome = retry_archives(
+            &self.failed_archives,
+            bioware_dir,
+            &options,
+            &mut self.conflicts,
+            &mut self.single_providers,
+            &mut self.resource_index,
+        );
+        let recovered = self.failed_archives.len() - outcome.still_failing.len();
 
-            egui::Area::new(egui::Id::new("modal_overlay"))
-                .order(egui::Order::Background)
-                .show(ctx, |ui| {
-                    ui.painter().rect_filled(
-                        ui.ctx().screen_rect(),
-                        egui::CornerRadius::ZERO,
-                        egui::Color32::from_black_alpha(150),
-                    );
-                });
+        let mut compare_warnings = Vec::new();
+        if options.detect_divergence {
+            let mut changed: Conflicts = outcome
+                .changed
+                .iter()
+                .filter_map(|key| Some((key.clone(), self.conflicts.get(key)?.clone())))
+                .collect();
+            changed.keep_entry_names(&self.conflicts);
+            let classification = classify_conflicts(&changed);
+            self.divergence.extend(classification.divergence);
+            compare_warnings = classification.warnings;
+        }
+        self.refresh_unexpected_winners();
 
-            egui::Window::new("Error")
-                .open(&mut open)
-                .collapsible(false)
-                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                .show(ctx, |ui| {
-                    egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
-                        let errors: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+        let retried = mem::take(&mut self.failed_archives);
+        self.scan_warnings.retain(|w| !retried.contains(&w.path));
+        self.failed_archives = outcome
+            .still_failing
+            .iter()
+            .map(|warning| warning.path.clone())
+            .collect();
+        self.scan_warnings.extend(outcome.still_failing);
+        self.scan_warnings.extend(compare_warnings);
+        self.scan_warnings.sort_by(|a, b| a.path.cmp(&b.path));
+        if let Some(summary) = &mut self.summary {
+            summary.failed_archives = self.failed_archives.len();
+        }
+        self.status = format!(
+            "Recovered {recovered} archives, {} conflicts changed",
+            outcome.changed.len()
+        );
+    }
 
-                        let message = errors.join("\n\n").replace(r"\\?\", ""); // Clean Windows extended path prefix
+    fn start_verify(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.verify_receiver = Some(rx);
 
-                        egui::ScrollArea::vertical()
-                            .max_height(300.0)
-                            .show(ui, |ui| {
-                                ui.label(egui::RichText::new(&message).size(14.0));
-                            });
+        let archives = self.archives.clone();
+        self.verify_thread = Some(thread::spawn(move || {
+            let _ = tx.send(verify_archives(&archives));
+        }));
 
-                        ui.add_space(7.0);
-                        ui.separator();
-                        ui.add_space(8.0);
+        self.status = format!("Verifying {} archives...", self.archives.len());
+    }
 
-                        ui.with_layout(
-                            egui::Layout::top_down_justified(egui::Align::Center),
-                            |ui| {
-                                ui.spacing_mut().button_padding = egui::vec2(6.0, 6.0);
+    fn process_verify_results(&mut self) {
+        if let Some(receiver) = &self.verify_receiver
+            && let Ok(report) = receiver.try_recv()
+        {
+            self.status = format!(
+                "Verified {} archives, {} with issues",
+                report.checked,
+                report.affected_archives()
+            );
+            self.verify_report = Some(report);
 
-                                if ui
-                                    .add(
-                                        egui::Button::new(egui::RichText::new("Copy").size(14.0))
-                                            .corner_radius(BUTTON_RADIUS),
-                                    )
-                                    .clicked()
-                                {
-                                    ui.ctx().copy_text(message);
-                                }
+            self.verify_receiver = None;
+            self.verify_thread = None;
+        }
+    }
 
-                                ui.add_space(6.0);
+    /// Loads the start of a source on a worker so large files don't stall the UI.
+    fn start_preview(&mut self, key: &str, path: &Path) {
+        let (tx, rx) = mpsc::channel();
+        self.preview_receiver = Some(rx);
 
-                                if ui
-                                    .add(
-                                        egui::Button::new(egui::RichText::new("Close").size(14.0))
-                                            .corner_radius(BUTTON_RADIUS),
-                                    )
-                                    .clicked()
-                                {
-                                    should_clear_error = true;
-                                }
-                            },
-                        );
-                    });
-                });
+        let name = self.conflicts.entry_name(key, path).to_string();
+        let (key, path) = (key.to_string(), path.to_path_buf());
+        thread::spawn(move || {
+            let _ = tx.send(load_preview(&path, &key, &name));
+        });
+        self.status = "Loading preview...".into();
+    }
 
-            if !open || should_clear_error {
-                self.error = None;
+    fn process_preview_results(&mut self) {
+        if let Some(receiver) = &self.preview_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(preview) => {
+                    self.status = format!("Previewing {}", preview.key);
+                    self.preview = Some(preview);
+                }
+                Err(err) => self.error = Some(err.context("Failed to load the preview")),
             }
+            self.preview_receiver = None;
         }
     }
 
-    fn main_ui(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
-        egui::TopBottomPanel::top("controls").show_inside(ui, |ui| {
-            self.scan_controls(ui, bioware_dir);
-            ui.add_space(8.0);
-        });
+    fn toggle_diff_pick(&mut self, key: &str, path: &Path) {
+        let picks = match &mut self.diff_picks {
+            Some((picked_key, picks)) if picked_key == key => picks,
+            _ => &mut self.diff_picks.insert((key.to_string(), Vec::new())).1,
+        };
 
-        egui::TopBottomPanel::bottom("ignored").show_inside(ui, |ui| {
-            ui.add_space(8.0);
-            self.ignored_panel(ui, bioware_dir);
-        });
+        if let Some(index) = picks.iter().position(|p| p == path) {
+            picks.remove(index);
+            return;
+        }
+        picks.push(path.to_path_buf());
 
-        egui::CentralPanel::default().show_inside(ui, |ui| {
-            egui::ScrollArea::vertical()
-                .id_salt("main_results")
-                .show(ui, |ui| {
-                    self.results_panel(ui, bioware_dir);
-                });
-        });
+        if let [first, second] = picks.as_slice() {
+            let (first, second) = (first.clone(), second.clone());
+            self.diff_picks = None;
+            self.start_diff(key, first, second);
+        }
     }
 
-    fn scan_controls(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
-        ui.horizontal(|ui| {
-            ui.spacing_mut().button_padding = egui::vec2(24.0, 6.0);
+    /// Compares two sources on a worker, unpacking both and diffing large
+    /// tables takes long enough to freeze the window.
+    fn start_diff(&mut self, key: &str, first: PathBuf, second: PathBuf) {
+        let (tx, rx) = mpsc::channel();
+        self.diff_receiver = Some(rx);
 
-            // Scan button
-            if ui
-                .add_enabled(
-                    self.scan_thread.is_none(),
-                    egui::Button::new(egui::RichText::new("🔍").size(24.0))
-                        .corner_radius(BUTTON_RADIUS),
-                )
-                .on_hover_text("Start new scan")
-                .clicked()
-            {
-                self.start_scan(bioware_dir);
+        let names = (
+            self.conflicts.entry_name(key, &first).to_string(),
+            self.conflicts.entry_name(key, &second).to_string(),
+        );
+        let key = key.to_string();
+        thread::spawn(move || {
+            let result = diff_sources((&first, &names.0), (&second, &names.1))
+                .map(|diff| DiffView {
+                    key: key.clone(),
+                    first,
+                    second,
+                    diff,
+                })
+                .with_context(|| format!("Failed to compare {key}"));
+            let _ = tx.send(result);
+        });
+        self.status = "Comparing sources...".into();
+    }
+
+    fn process_diff_results(&mut self) {
+        if let Some(receiver) = &self.diff_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(view) => {
+                    self.status = format!("Comparing two sources of {}", view.key);
+                    self.diff_view = Some(view);
+                }
+                Err(err) => self.error = Some(err),
             }
+            self.diff_receiver = None;
+        }
+    }
 
-            ui.add_space(4.0);
-            ui.label(egui::RichText::new(&self.status).size(14.0));
+    fn is_diff_pick(&self, key: &str, path: &Path) -> bool {
+        self.diff_picks
+            .as_ref()
+            .is_some_and(|(picked_key, picks)| picked_key == key && picks.iter().any(|p| p == path))
+    }
 
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                ui.spacing_mut().button_padding = egui::vec2(4.0, 2.0);
+    fn start_compare(&mut self) {
+        let Some(compare) = &mut self.compare else {
+            return;
+        };
+        compare.result = None;
 
-                ui.with_layout(egui::Layout::top_down(egui::Align::RIGHT), |ui| {
-                    if ui
-                        .add(
-                            egui::Button::new(egui::RichText::new("⏷").size(12.0))
-                                .corner_radius(BUTTON_RADIUS),
-                        )
-                        .on_hover_text("Expand all conflicts")
-                        .clicked()
-                    {
+        let (tx, rx) = mpsc::channel();
+        self.compare_receiver = Some(rx);
+
+        let first = PathBuf::from(compare.first.trim());
+        let second = PathBuf::from(compare.second.trim());
+        let options = self.config.scan_options();
+        self.compare_thread = Some(thread::spawn(move || {
+            let _ = tx.send(compare_installs(&first, &second, &options));
+        }));
+
+        self.status = "Comparing installs...".into();
+    }
+
+    fn process_compare_results(&mut self) {
+        if let Some(receiver) = &self.compare_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(diff) => {
+                    self.status = format!(
+                        "Compared installs: {} only in the first, {} only in the second",
+                        diff.only_first.len(),
+                        diff.only_second.len()
+                    );
+                    if let Some(compare) = &mut self.compare {
+                        compare.result = Some(diff);
+                    }
+                }
+                Err(e) => {
+                    self.status = "Comparison failed!".into();
+                    self.error = Some(e.into());
+                }
+            }
+
+            self.compare_receiver = None;
+            self.compare_thread = None;
+        }
+    }
+
+    fn start_queue(&mut self) {
+        let Some(queue) = &mut self.scan_queue else {
+            return;
+        };
+        queue.results = queue.targets.iter().map(|_| None).collect();
+
+        let (tx, rx) = mpsc::channel();
+        self.queue_receiver = Some(rx);
+
+        let targets = queue.targets.clone();
+        let options = self.config.scan_options();
+        self.queue_thread = Some(thread::spawn(move || {
+            run_queue(
+                &targets,
+                |target| scan_for_conflicts(target, &options, None, None),
+                |event| {
+                    let _ = tx.send(event);
+                },
+            );
+        }));
+    }
+
+    fn process_queue_results(&mut self) {
+        let Some(receiver) = &self.queue_receiver else {
+            return;
+        };
+
+        loop {
+            match receiver.try_recv() {
+                Ok(QueueEvent::Started { index, total }) => {
+                    self.status = format!("Scanning {} of {total} targets...", index + 1);
+                    if let Some(queue) = &mut self.scan_queue {
+                        queue.current = Some(index);
+                    }
+                }
+                Ok(QueueEvent::Finished { index, result }) => {
+                    if let Some(slot) = self
+                        .scan_queue
+                        .as_mut()
+                        .and_then(|queue| queue.results.get_mut(index))
+                    {
+                        *slot = Some(result.map_err(|err| format!("{err:#}")));
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    if let Some(queue) = &mut self.scan_queue {
+                        queue.current = None;
+                        self.status = format!("Scanned {} targets", queue.targets.len());
+                    }
+                    self.queue_receiver = None;
+                    self.queue_thread = None;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Moves new actions that change files or resolutions into the staged list.
+    fn stage_commands(&mut self) {
+        let (mutating, immediate): (Vec<_>, Vec<_>) = mem::take(&mut self.pending_commands)
+            .into_iter()
+            .partition(Command::is_mutating);
+        self.staged_commands.extend(mutating);
+        self.pending_commands = immediate;
+    }
+
+    fn apply_staged_commands(&mut self) {
+        self.pending_commands.append(&mut self.staged_commands);
+        if let Err(err) = self.handle_commands() {
+            self.error = Some(err);
+        }
+    }
+
+    /// Runs every pending command, a failed one doesn't stop the rest. The
+    /// config is saved either way, so what did run isn't lost.
+    fn handle_commands(&mut self) -> AnyhowResult<()> {
+        let commands = mem::take(&mut self.pending_commands);
+        let applied = !commands.is_empty();
+        let mut failures = run_each(commands, |command| self.run_command(command));
+
+        if applied {
+            self.show_resolution_counts();
+        }
+        if let Err(err) = self.config.save().context("Failed to save config") {
+            failures.push(err);
+        }
+        combine_failures(failures)
+    }
+
+    fn run_command(&mut self, command: Command) -> AnyhowResult<()> {
+        command.check_allowed(self.read_only, self.scanned_folder.is_some())?;
+
+        let outcome = command.outcome();
+        match command {
+            Command::IgnoreConflict(key, paths) => {
+                self.push_undo(UndoAction::before_ignoring(&self.config, &key));
+                self.ignore_conflict(key, paths, unix_timestamp());
+            }
+            Command::UnignoreConflict(key) => {
+                if let Some((paths, ignored_at)) = self.unignore_conflict(&key) {
+                    self.push_undo(UndoAction::Reignore {
+                        key,
+                        paths,
+                        ignored_at,
+                    });
+                }
+            }
+            Command::UnignoreAll => {
+                let keys: Vec<_> = self.config.ignored.keys().cloned().collect();
+                let forgotten = keys
+                    .into_iter()
+                    .filter_map(|key| {
+                        let (paths, ignored_at) = self.unignore_conflict(&key)?;
+                        Some((key, paths, ignored_at))
+                    })
+                    .collect();
+                self.push_undo(UndoAction::ReignoreAll(forgotten));
+            }
+            Command::DeleteConflictFile(key, path) => {
+                let backup = if self.config.backup_before_delete {
+                    Some(self.back_up(&path)?)
+                } else {
+                    None
+                };
+                if let Err(err) = delete(&path) {
+                    if is_in_use(&err) {
+                        self.retry_command =
+                            Some(Command::DeleteConflictFile(key.clone(), path.clone()));
+                    }
+                    return Err(err).context(format!("Failed to delete {}", path.display()));
+                }
+                self.remove_conflict_path(&key, &path);
+                if let Some(backup) = backup {
+                    self.push_undo(UndoAction::Restore {
+                        key: key.clone(),
+                        original: path.clone(),
+                        backup,
+                    });
+                }
+                self.record_decision(&key, DecisionAction::Deleted, Some(path));
+            }
+            Command::RemoveFromArchive(key, path) => {
+                let backup = self.back_up(&path)?;
+                ErfFile::remove_resource(&path, self.conflicts.entry_name(&key, &path))?;
+                self.remove_conflict_path(&key, &path);
+                self.push_undo(UndoAction::Restore {
+                    key: key.clone(),
+                    original: path.clone(),
+                    backup,
+                });
+                self.record_decision(&key, DecisionAction::RemovedFromArchive, Some(path));
+            }
+            Command::DisableConflictFile(key, path) => {
+                let disabled =
+                    disable(&path).context(format!("Failed to disable {}", path.display()))?;
+                self.remove_conflict_path(&key, &path);
+                self.disabled_files.push((key.clone(), disabled.clone()));
+                self.push_undo(UndoAction::Enable {
+                    key: key.clone(),
+                    disabled,
+                });
+                self.record_decision(&key, DecisionAction::Disabled, Some(path));
+            }
+            Command::DisableDuplicateInstall(files) => {
+                let mut disabled_paths = HashSet::new();
+                let mut disabled = Vec::new();
+                let mut sources = Vec::new();
+
+                let outcome: AnyhowResult<()> = files.into_iter().try_for_each(|(key, path)| {
+                    // Archives are shared by many conflicts but only renamed once
+                    if disabled_paths.insert(path.clone()) {
+                        let renamed = disable(&path)
+                            .context(format!("Failed to disable {}", path.display()))?;
+                        self.disabled_files.push((key.clone(), renamed.clone()));
+                        disabled.push(renamed);
+                    }
+
+                    self.remove_conflict_path(&key, &path);
+                    sources.push((key.clone(), path.clone()));
+                    self.record_decision(&key, DecisionAction::Disabled, Some(path));
+                    Ok(())
+                });
+
+                // Whatever was disabled before a failure can still be undone
+                if !disabled.is_empty() {
+                    self.push_undo(UndoAction::EnableInstall { disabled, sources });
+                }
+                outcome?;
+            }
+            Command::EnableFile(key, path) => {
+                let enabled =
+                    enable(&path).context(format!("Failed to enable {}", path.display()))?;
+                self.disabled_files.retain(|(_, p)| p != &path);
+                self.record_decision(&key, DecisionAction::Enabled, Some(enabled));
+            }
+            Command::SetPreferredProvider(key, path) => {
+                self.config.preferred.insert(key, path);
+                self.refresh_unexpected_winners();
+            }
+            Command::ClearPreferredProvider(key) => {
+                self.config.preferred.remove(&key);
+                self.refresh_unexpected_winners();
+            }
+        }
+        self.notify(outcome);
+        Ok(())
+    }
+
+    fn notify(&mut self, message: impl Into<String>) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast {
+            message: message.into(),
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Stacks the live toasts in the bottom right corner. They take no input,
+    /// so they never steal focus or clicks from what's under them.
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION);
+        let Some(oldest) = self.toasts.first() else {
+            return;
+        };
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .order(egui::Order::Tooltip)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                for toast in &self.toasts {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(&toast.message);
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+
+        ctx.request_repaint_after(TOAST_DURATION.saturating_sub(oldest.shown_at.elapsed()));
+    }
+
+    fn ignore_conflict(&mut self, key: String, paths: Vec<PathBuf>, ignored_at: u64) {
+        self.config.ignored_at.insert(key.clone(), ignored_at);
+        self.record_decision(&key, DecisionAction::Ignored, None);
+        self.config.ignored.insert(key, paths);
+    }
+
+    /// Forgets the resolution of `key`, returning what it was.
+    fn unignore_conflict(&mut self, key: &str) -> Option<(Vec<PathBuf>, Option<u64>)> {
+        let paths = self.config.ignored.remove(key);
+        let ignored_at = self.config.ignored_at.remove(key);
+        self.record_decision(key, DecisionAction::Unignored, None);
+        paths.map(|paths| (paths, ignored_at))
+    }
+
+    fn push_undo(&mut self, action: UndoAction) {
+        if self.undo_stack.len() == MAX_UNDO_STEPS {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(action);
+    }
+
+    /// Reverses the most recent undoable action.
+    fn undo(&mut self) {
+        if self.read_only {
+            return;
+        }
+        let Some(action) = self.undo_stack.pop() else {
+            return;
+        };
+
+        let description = action.describe();
+        let undone = match action {
+            UndoAction::Unignore(key) => {
+                self.unignore_conflict(&key);
+                Ok(())
+            }
+            UndoAction::Reignore {
+                key,
+                paths,
+                ignored_at,
+            } => {
+                self.ignore_conflict(key, paths, ignored_at.unwrap_or_else(unix_timestamp));
+                Ok(())
+            }
+            UndoAction::ReignoreAll(forgotten) => {
+                for (key, paths, ignored_at) in forgotten {
+                    self.ignore_conflict(key, paths, ignored_at.unwrap_or_else(unix_timestamp));
+                }
+                Ok(())
+            }
+            UndoAction::EnableInstall { disabled, sources } => disabled
+                .iter()
+                .try_for_each(|path| {
+                    enable(path)
+                        .map(|_| self.disabled_files.retain(|(_, p)| p != path))
+                        .context(format!("Failed to enable {}", path.display()))
+                })
+                .map(|()| {
+                    for (key, path) in sources {
+                        self.restore_conflict_path(&key, path);
+                    }
+                }),
+            UndoAction::Enable { key, disabled } => enable(&disabled)
+                .map(|enabled| {
+                    self.disabled_files.retain(|(_, p)| p != &disabled);
+                    self.restore_conflict_path(&key, enabled);
+                })
+                .context(format!("Failed to enable {}", disabled.display())),
+            UndoAction::Restore {
+                key,
+                original,
+                backup,
+            } => copy_with_metadata(&backup, &original)
+                .map(|()| self.restore_conflict_path(&key, original.clone()))
+                .context(format!("Failed to restore {}", original.display())),
+        };
+
+        match undone {
+            Ok(()) => {
+                self.show_resolution_counts();
+                self.notify(format!("Undid {description}"));
+                let _ = self.config.save();
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn restore_conflict_path(&mut self, key: &str, path: PathBuf) {
+        let paths = self.conflicts.entry(key.to_string()).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+            paths.sort();
+        }
+    }
+
+    /// Re-records known identical groups that were hashed again and still
+    /// match, and forgets the ones that no longer do.
+    fn refresh_known_identical(
+        &mut self,
+        divergence: &HashMap<String, Divergence>,
+        trusted: &HashSet<String>,
+    ) {
+        let now = unix_timestamp();
+        let known = mem::take(&mut self.config.known_identical);
+
+        self.config.known_identical = known
+            .into_iter()
+            .filter_map(|(key, record)| {
+                if trusted.contains(&key) {
+                    return Some((key, record));
+                }
+                if divergence.get(&key) != Some(&Divergence::Identical) {
+                    return None;
+                }
+
+                let record = KnownIdentical::record(&key, &self.conflicts, now).ok()?;
+                Some((key, record))
+            })
+            .collect();
+    }
+
+    fn set_sort(&mut self, mode: SortMode) {
+        self.config.list_sort = mode;
+        let _ = self.config.save();
+    }
+
+    /// Swaps in another profile's ignore list, re-filtering the current
+    /// results against it.
+    fn switch_profile(&mut self, name: &str) {
+        if name == self.config.active_profile {
+            return;
+        }
+
+        self.config.switch_profile(name);
+        // Undoing would restore entries into the wrong profile's list
+        self.undo_stack.clear();
+        self.show_resolution_counts();
+        let _ = self.config.save();
+        self.notify(format!("Switched to profile \"{name}\""));
+    }
+
+    fn profile_selector(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to = None;
+        let mut delete = None;
+        egui::ComboBox::from_id_salt("active_profile")
+            .selected_text(format!("Profile: {}", self.config.active_profile))
+            .show_ui(ui, |ui| {
+                for name in self.config.profile_names() {
+                    ui.horizontal(|ui| {
+                        let active = name == self.config.active_profile;
+                        if ui.selectable_label(active, &name).clicked() {
+                            switch_to = Some(name.clone());
+                        }
+                        if !active
+                            && ui
+                                .small_button("🗑")
+                                .on_hover_text("Delete this profile and its ignore list")
+                                .clicked()
+                        {
+                            delete = Some(name);
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_profile_name)
+                            .hint_text("New profile")
+                            .desired_width(120.0),
+                    );
+                    let name = self.new_profile_name.trim();
+                    if ui
+                        .add_enabled(
+                            !name.is_empty() && !self.config.profile_names().contains(name),
+                            egui::Button::new("Add").corner_radius(BUTTON_RADIUS),
+                        )
+                        .clicked()
+                    {
+                        switch_to = Some(name.to_string());
+                        self.new_profile_name.clear();
+                    }
+                });
+            })
+            .response
+            .on_hover_text("Each profile keeps its own list of ignored conflicts");
+
+        if let Some(name) = delete {
+            self.config.delete_profile(&name);
+            let _ = self.config.save();
+            self.notify(format!("Deleted profile \"{name}\""));
+        }
+        if let Some(name) = switch_to {
+            self.switch_profile(&name);
+        }
+    }
+
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            filter: self.results_filter.clone(),
+            list_view: self.list_view,
+            only_actionable: self.only_actionable,
+        }
+    }
+
+    /// Saves the list view to the config when it changed and should be remembered.
+    fn sync_view_state(&mut self) {
+        if !self.config.remember_view {
+            return;
+        }
+
+        let view = self.view_state();
+        if view != self.config.view_state {
+            self.config.view_state = view;
+            let _ = self.config.save();
+        }
+    }
+
+    fn refresh_unexpected_winners(&mut self) {
+        self.unexpected_winners = unexpected_winners(&self.conflicts, &self.config.preferred);
+    }
+
+    /// Copies a file into this session's backup folder, returning the copy.
+    fn back_up(&self, path: &Path) -> AnyhowResult<PathBuf> {
+        let root = self.config.bioware_dir().unwrap_or_default();
+        back_up(path, &root, &AppConfig::backup_dir()?, self.backup_session)
+    }
+
+    fn restore_backup(&mut self, session: &BackupSession) {
+        match session.restore() {
+            Ok(outcome) => {
+                self.status = format!(
+                    "Restored {} files from the backup of {}, rescan to see them",
+                    outcome.restored,
+                    format_date(session.timestamp)
+                );
+                if !outcome.skipped.is_empty() {
+                    self.status.push_str(&format!(
+                        " ({} skipped since they exist again)",
+                        outcome.skipped.len()
+                    ));
+                }
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn record_decision(&mut self, key: &str, action: DecisionAction, path: Option<PathBuf>) {
+        self.config.decisions.push(Decision {
+            timestamp: unix_timestamp(),
+            key: key.to_string(),
+            action,
+            path,
+            note: self.config.notes.get(key).cloned(),
+        });
+    }
+
+    fn export_decision_log(&mut self, bioware_dir: &Path) {
+        let log = render_log(&self.config.decisions, bioware_dir);
+        self.write_export("decision_log.txt", "decision log", &log);
+    }
+
+    /// Asks for the BioWare folder, remembers it and scans it.
+    fn choose_bioware_dir(&mut self) {
+        let mut dialog = rfd::FileDialog::new().set_title("Choose the BioWare folder");
+        if let Some(current) = self.config.bioware_dir() {
+            dialog = dialog.set_directory(current);
+        }
+        let Some(dir) = dialog.pick_folder() else {
+            return;
+        };
+
+        self.config.bioware_dir = Some(dir);
+        if let Err(err) = self.config.save() {
+            self.error = Some(err);
+        }
+        self.scan_on_start = true;
+    }
+
+    /// The sections as currently shown, resolved conflicts included when listed.
+    fn shown_sections(&self, bioware_dir: &Path) -> Vec<ConflictSection> {
+        let mut sections = self.visible_results(bioware_dir);
+        sections.push(ConflictSection {
+            kind: SectionKind::Resolved,
+            entries: self.visible_ignored(),
+        });
+        sections
+    }
+
+    fn export_list(&mut self, bioware_dir: &Path) {
+        let report = render_report(&self.shown_sections(bioware_dir), bioware_dir);
+        self.write_export("conflict_report.txt", "conflict report", &report);
+    }
+
+    /// Asks where to save the shown conflicts as CSV or JSON and writes them.
+    fn export_to_file(&mut self, bioware_dir: &Path, format: ExportFormat) {
+        let (name, extension) = match format {
+            ExportFormat::Csv => ("CSV", "csv"),
+            ExportFormat::Json => ("JSON", "json"),
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(name, &[extension])
+            .set_file_name(format!("conflicts.{extension}"))
+            .save_file()
+        else {
+            return;
+        };
+
+        let sections = self.shown_sections(bioware_dir);
+        let contents = match format {
+            ExportFormat::Csv => Ok(render_csv(&sections, bioware_dir)),
+            ExportFormat::Json => render_json(&sections, &self.conflicts, bioware_dir),
+        };
+
+        let result = contents.map_err(AnyhowError::from).and_then(|contents| {
+            fs::write(&path, contents).context(format!("Failed to write {}", path.display()))
+        });
+        match result {
+            Ok(()) => self.status = format!("Exported conflicts to {}", path.display()),
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn export_plan(&mut self, bioware_dir: &Path) {
+        let entries: Vec<ConflictEntry> = self
+            .visible_results(bioware_dir)
+            .into_iter()
+            .flat_map(|section| section.entries)
+            .collect();
+        let plan =
+            ResolutionPlan::from_conflicts(&entries, &self.config.preferred, unix_timestamp());
+
+        match plan.to_json() {
+            Ok(json) => self.write_export(PLAN_FILE, "resolution plan", &json),
+            Err(err) => self.error = Some(err.into()),
+        }
+    }
+
+    fn import_plan(&mut self) {
+        let result = AppConfig::config_dir().and_then(|dir| {
+            let json = fs::read_to_string(dir.join(PLAN_FILE))
+                .context("Failed to read the resolution plan, export one first")?;
+            ResolutionPlan::from_json(&json).context("Failed to parse the resolution plan")
+        });
+
+        match result {
+            Ok(plan) => self.plan_review = Some(plan),
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn apply_plan(&mut self, plan: ResolutionPlan) {
+        let (commands, blocked) = Command::from_plan(plan, &self.config.delete_rules);
+        self.pending_commands.extend(commands);
+
+        self.status = if blocked > 0 {
+            format!(
+                "Applying the resolution plan, skipped {blocked} deletions blocked by the delete rules"
+            )
+        } else {
+            "Applying the resolution plan".into()
+        };
+    }
+
+    fn show_plan_review(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(plan) = &self.plan_review else {
+            return;
+        };
+
+        let missing = plan.missing_files(Path::exists).len();
+        let mut open = true;
+        let mut apply = false;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Apply resolution plan")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(format!(
+                        "Planned on {}, {} operations",
+                        format_date(plan.created_at),
+                        plan.operations.len()
+                    ));
+                    if missing > 0 {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 140, 90),
+                            format!("⚠ {missing} files no longer exist and will fail"),
+                        );
+                    }
+                    ui.add_space(6.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("plan_operations")
+                                .num_columns(2)
+                                .spacing([12.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for operation in &plan.operations {
+                                        match operation.action {
+                                            PlanAction::Delete => ui.colored_label(
+                                                egui::Color32::from_rgb(255, 100, 100),
+                                                "delete",
+                                            ),
+                                            PlanAction::Disable => ui.label("disable"),
+                                        };
+                                        let display_path = diff_paths(&operation.path, bioware_dir)
+                                            .unwrap_or_else(|| operation.path.clone());
+                                        ui.monospace(display_path.display().to_string());
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+
+                    ui.add_space(6.0);
+                    apply = ui
+                        .add_enabled(
+                            !self.read_only && !plan.operations.is_empty(),
+                            egui::Button::new(format!(
+                                "Apply {} operations",
+                                plan.operations.len()
+                            ))
+                            .corner_radius(BUTTON_RADIUS),
+                        )
+                        .clicked();
+                });
+            });
+
+        if apply && let Some(plan) = self.plan_review.take() {
+            self.apply_plan(plan);
+        } else if !open {
+            self.plan_review = None;
+        }
+    }
+
+    /// Writes an export into the config directory and reveals it.
+    fn write_export(&mut self, file_name: &str, description: &str, contents: &str) {
+        let result = AppConfig::config_dir().and_then(|dir| {
+            let path = dir.join(file_name);
+            fs::create_dir_all(&dir).context("Failed to create config directory")?;
+            fs::write(&path, contents).context(format!("Failed to write {description}"))?;
+            Ok(path)
+        });
+
+        match result {
+            Ok(path) => {
+                self.status = format!("Exported {description} to {}", path.display());
+                self.notify(self.status.clone());
+                let _ = reveal_in_file_manager(&path);
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn inspect_archive(&mut self, path: &Path) {
+        match ErfFile::open(path) {
+            Ok(erf) => {
+                let warnings = File::open(path)
+                    .map(|mut file| erf.check_types(&mut file))
+                    .unwrap_or_default();
+                self.inspected_archive = Some((path.to_path_buf(), erf, warnings));
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn palette_commands(&self) -> Vec<PaletteCommand> {
+        let idle = self.scan_thread.is_none() && self.verify_thread.is_none();
+
+        let mut commands = vec![
+            PaletteCommand::new("Scan for conflicts", idle, |app, bioware_dir| {
+                app.start_scan(bioware_dir)
+            }),
+            PaletteCommand::new(
+                "Verify archives",
+                idle && !self.archives.is_empty(),
+                |app, _| app.start_verify(),
+            ),
+            PaletteCommand::new("Compare two installs", true, |app, bioware_dir| {
+                app.compare.get_or_insert_with(|| CompareState {
+                    first: bioware_dir.display().to_string(),
+                    ..Default::default()
+                });
+            }),
+            PaletteCommand::new("Scan queue", true, |app, bioware_dir| {
+                app.scan_queue.get_or_insert_with(|| ScanQueue {
+                    input: bioware_dir.display().to_string(),
+                    ..Default::default()
+                });
+            }),
+            PaletteCommand::new(
+                "Export resolution plan",
+                self.has_scanned,
+                |app, bioware_dir| app.export_plan(bioware_dir),
+            ),
+            PaletteCommand::new("Apply resolution plan", !self.read_only, |app, _| {
+                app.import_plan()
+            }),
+            PaletteCommand::new("Scan folder…", idle, |app, _| app.scan_folder()),
+            PaletteCommand::new("Find resource", self.has_scanned, |app, _| {
+                app.resource_finder = Some(String::new());
+            }),
+            PaletteCommand::new("Restore from backup", !self.read_only, |app, _| {
+                app.backup_sessions = Some(
+                    AppConfig::backup_dir()
+                        .map(|dir| list_sessions(&dir))
+                        .unwrap_or_default(),
+                );
+            }),
+            PaletteCommand::new("Expand all conflicts", true, |app, _| app.expand_all()),
+            PaletteCommand::new("Collapse all conflicts", true, |app, _| app.collapse_all()),
+            PaletteCommand::new(
+                "Export decision log",
+                !self.config.decisions.is_empty(),
+                |app, bioware_dir| app.export_decision_log(bioware_dir),
+            ),
+            PaletteCommand::new(
+                "Clear all preferred providers",
+                !self.config.preferred.is_empty()
+                    && !self.read_only
+                    && self.scanned_folder.is_none(),
+                |app, _| {
+                    app.config.preferred.clear();
+                    app.refresh_unexpected_winners();
+                    let _ = app.config.save();
+                },
+            ),
+            PaletteCommand::new("Toggle only actionable conflicts", true, |app, _| {
+                app.only_actionable = !app.only_actionable
+            }),
+            PaletteCommand::new(
+                "Clear conflicts search",
+                !self.results_filter.is_empty(),
+                |app, _| app.results_filter = ResultsFilter::default(),
+            ),
+        ];
+
+        for view in ListView::ALL {
+            commands.push(PaletteCommand::new(
+                format!("Show {} conflicts", view.label().to_lowercase()),
+                self.list_view != view,
+                move |app, _| app.list_view = view,
+            ));
+        }
+
+        for mode in SortMode::ALL {
+            commands.push(PaletteCommand::new(
+                format!("Sort conflicts: {}", mode.label()),
+                self.config.list_sort != mode,
+                move |app, _| app.set_sort(mode),
+            ));
+        }
+
+        commands
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let pressed = |shortcut| ctx.input_mut(|i| i.consume_shortcut(&shortcut));
+
+        if (pressed(SCAN_SHORTCUT) || pressed(RESCAN_SHORTCUT)) && self.scan_thread.is_none() {
+            self.start_scan(bioware_dir);
+        }
+        if pressed(EXPAND_SHORTCUT) {
+            self.expand_all();
+        }
+        if pressed(COLLAPSE_SHORTCUT) {
+            self.collapse_all();
+        }
+        // Before the plain search, which would also match with Shift held
+        if pressed(FIND_RESOURCE_SHORTCUT) && self.has_scanned {
+            self.resource_finder = Some(String::new());
+        }
+        if pressed(SEARCH_SHORTCUT) {
+            self.focus_search = true;
+        }
+        // Left to the focused text field, which has an undo of its own
+        if !ctx.wants_keyboard_input() && pressed(UNDO_SHORTCUT) {
+            self.undo();
+        }
+    }
+
+    fn toggle_palette(&mut self) {
+        self.palette = match self.palette {
+            Some(_) => None,
+            None => Some(PaletteState::default()),
+        };
+    }
+
+    /// Label of the add-in providing `path`, from its manifest or folder name.
+    fn addin_label(&self, path: &Path, bioware_dir: &Path) -> Option<String> {
+        let root = addin_root(path, bioware_dir)?;
+
+        self.addins.get(&root).cloned().or_else(|| {
+            root.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+    }
+
+    /// Queues a delete, unless the delete rules block it or ask for confirmation.
+    fn request_delete(&mut self, key: &str, path: &Path) {
+        match self.config.delete_rules.check(path) {
+            DeleteSafety::Allowed => self.pending_commands.push(Command::DeleteConflictFile(
+                key.to_string(),
+                path.to_path_buf(),
+            )),
+            DeleteSafety::Confirm => {
+                self.delete_confirmation = Some(DeleteConfirmation {
+                    key: key.to_string(),
+                    path: path.to_path_buf(),
+                    typed: String::new(),
+                })
+            }
+            DeleteSafety::Blocked => {
+                self.error = Some(anyhow!(
+                    "Deleting {} is blocked by the delete rules, disable it instead.",
+                    path.display()
+                ))
+            }
+        }
+    }
+
+    /// Asks to delete every loose source of a conflict except `kept`, packed
+    /// sources are left alone since they can't be deleted on their own.
+    fn request_keep_only(&mut self, key: &str, kept: &Path) {
+        let Some(paths) = self.conflicts.get(key) else {
+            return;
+        };
+
+        let (blocked, deletions): (Vec<PathBuf>, Vec<PathBuf>) = paths
+            .iter()
+            .filter(|path| *path != kept && SourceKind::of(path) == SourceKind::Loose)
+            .cloned()
+            .partition(|path| self.config.delete_rules.check(path) == DeleteSafety::Blocked);
+        let needs_typed = deletions
+            .iter()
+            .any(|path| self.config.delete_rules.check(path) == DeleteSafety::Confirm);
+
+        self.keep_only = Some(KeepOnlyConfirmation {
+            key: key.to_string(),
+            kept: kept.to_path_buf(),
+            deletions,
+            blocked,
+            needs_typed,
+            typed: String::new(),
+        });
+    }
+
+    fn remove_conflict_path(&mut self, key: &str, path: &Path) {
+        if let Some(paths) = self.conflicts.get_mut(key) {
+            paths.retain(|p| p != path);
+            if paths.is_empty() {
+                self.conflicts.remove(key);
+            }
+        }
+    }
+
+    fn expand_all(&mut self) {
+        self.config.expanded_conflicts = self.conflicts.keys().cloned().collect();
+        let _ = self.config.save();
+    }
+
+    fn collapse_all(&mut self) {
+        self.config.expanded_conflicts.clear();
+        let _ = self.config.save();
+    }
+}
+
+impl App {
+    fn show_error_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(err) = &self.error {
+            let mut open = true;
+            let mut should_clear_error = false;
+            let mut should_retry = false;
+
+            show_modal_overlay(ctx);
+
+            egui::Window::new("Error")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                        let errors: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+
+                        let message = errors.join("\n\n").replace(r"\\?\", ""); // Clean Windows extended path prefix
+
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(&message).size(14.0));
+                            });
+
+                        if self.retry_command.is_some() {
+                            ui.add_space(6.0);
+                            ui.label(
+                                egui::RichText::new(
+                                    "The file is in use by another program. Close the game and retry.",
+                                )
+                                .color(egui::Color32::from_rgb(255, 140, 90))
+                                .size(14.0),
+                            );
+                        }
+
+                        ui.add_space(7.0);
+                        ui.separator();
+                        ui.add_space(8.0);
+
+                        ui.with_layout(
+                            egui::Layout::top_down_justified(egui::Align::Center),
+                            |ui| {
+                                ui.spacing_mut().button_padding = egui::vec2(6.0, 6.0);
+
+                                if self.retry_command.is_some() {
+                                    if ui
+                                        .add(
+                                            egui::Button::new(
+                                                egui::RichText::new("Retry").size(14.0),
+                                            )
+                                            .corner_radius(BUTTON_RADIUS),
+                                        )
+                                        .clicked()
+                                    {
+                                        should_retry = true;
+                                    }
+
+                                    ui.add_space(6.0);
+                                }
+
+                                if ui
+                                    .add(
+                                        egui::Button::new(egui::RichText::new("Copy").size(14.0))
+                                            .corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .clicked()
+                                {
+                                    ui.ctx().copy_text(message);
+                                }
+
+                                ui.add_space(6.0);
+
+                                if ui
+                                    .add(
+                                        egui::Button::new(egui::RichText::new("Close").size(14.0))
+                                            .corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .clicked()
+                                {
+                                    should_clear_error = true;
+                                }
+                            },
+                        );
+                    });
+                });
+
+            if should_retry && let Some(command) = self.retry_command.take() {
+                self.pending_commands.push(command);
+                self.error = None;
+            } else if !open || should_clear_error {
+                self.error = None;
+                self.retry_command = None;
+            }
+        }
+    }
+
+    fn show_command_palette(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        if self.palette.is_none() {
+            return;
+        }
+
+        let commands = self.palette_commands();
+        let Some(palette) = &mut self.palette else {
+            return;
+        };
+
+        let matches = rank_matches(commands.iter().map(|c| c.name.as_str()), &palette.query);
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        if down && palette.selected + 1 < matches.len() {
+            palette.selected += 1;
+        }
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+
+        let mut chosen = enter
+            .then(|| matches.get(palette.selected).copied())
+            .flatten();
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size([420.0, 0.0])
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut palette.query)
+                            .hint_text("Type a command...")
+                            .desired_width(f32::INFINITY),
+                    );
+                    response.request_focus();
+                    if response.changed() {
+                        palette.selected = 0;
+                    }
+
+                    ui.add_space(4.0);
+                    ui.separator();
+
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            if matches.is_empty() {
+                                ui.label(
+                                    egui::RichText::new("No matching commands")
+                                        .color(egui::Color32::DARK_GRAY),
+                                );
+                            }
+
+                            for (row, &index) in matches.iter().enumerate() {
+                                let command = &commands[index];
+                                let response = ui.add_enabled(
+                                    command.enabled,
+                                    egui::SelectableLabel::new(
+                                        row == palette.selected,
+                                        egui::RichText::new(&command.name).size(14.0),
+                                    ),
+                                );
+
+                                if row == palette.selected {
+                                    response.scroll_to_me(None);
+                                }
+                                if response.clicked() {
+                                    chosen = Some(index);
+                                }
+                            }
+                        });
+                });
+            });
+
+        if escape {
+            self.palette = None;
+        } else if let Some(index) = chosen
+            && commands[index].enabled
+        {
+            self.palette = None;
+            (commands[index].action)(self, bioware_dir);
+        }
+    }
+
+    fn show_verify_report(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(report) = &self.verify_report else {
+            return;
+        };
+
+        let mut open = true;
+        let mut inspect = None;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Archive verification")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Checked {} archives, {} with issues.",
+                            report.checked,
+                            report.affected_archives()
+                        ))
+                        .size(14.0),
+                    );
+                    ui.add_space(6.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            if report.issues.is_empty() {
+                                ui.label(
+                                    egui::RichText::new("No issues found")
+                                        .color(egui::Color32::DARK_GRAY)
+                                        .size(14.0),
+                                );
+                            }
+
+                            ui.spacing_mut().button_padding = egui::vec2(2.0, 1.0);
+
+                            for (kind, entries) in &report.issues {
+                                egui::CollapsingHeader::new(
+                                    egui::RichText::new(format!("{kind} ({})", entries.len()))
+                                        .size(14.0),
+                                )
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    for (path, details) in entries {
+                                        ui.horizontal(|ui| {
+                                            if ui
+                                                .add(
+                                                    egui::Button::new(
+                                                        egui::RichText::new("📂").size(14.0),
+                                                    )
+                                                    .corner_radius(BUTTON_RADIUS),
+                                                )
+                                                .on_hover_text("Show in file manager")
+                                                .clicked()
+                                            {
+                                                let _ = reveal_in_file_manager(path);
+                                            }
+
+                                            if ui
+                                                .add(
+                                                    egui::Button::new(
+                                                        egui::RichText::new("ℹ").size(14.0),
+                                                    )
+                                                    .corner_radius(BUTTON_RADIUS),
+                                                )
+                                                .on_hover_text("Inspect archive")
+                                                .clicked()
+                                            {
+                                                inspect = Some(path.clone());
+                                            }
+
+                                            let display_path = diff_paths(path, bioware_dir)
+                                                .unwrap_or_else(|| path.clone());
+                                            ui.label(
+                                                egui::RichText::new(
+                                                    display_path.display().to_string(),
+                                                )
+                                                .size(13.0),
+                                            )
+                                            .on_hover_text(details);
+                                        });
+                                    }
+                                });
+                            }
+                        });
+                });
+            });
+
+        if !open {
+            self.verify_report = None;
+        }
+        if let Some(path) = inspect {
+            self.verify_report = None;
+            self.inspect_archive(&path);
+        }
+    }
+
+    fn show_archive_inspector(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some((path, erf, warnings)) = &self.inspected_archive else {
+            return;
+        };
+
+        let mut open = true;
+        let mut extract = false;
+
+        show_modal_overlay(ctx);
+
+        let title = path.file_name().map_or_else(
+            || "Archive".to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    let display_path =
+                        diff_paths(path, bioware_dir).unwrap_or_else(|| path.clone());
+                    ui.label(egui::RichText::new(display_path.display().to_string()).size(13.0));
+                    ui.add_space(6.0);
+
+                    egui::Grid::new("archive_header")
+                        .num_columns(2)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("Version");
+                            ui.label(erf.version.label());
+                            ui.end_row();
+
+                            if erf.version != ErfVersion::V30 {
+                                ui.label("Build date");
+                                ui.label(format!("year {}, day {}", erf.year + 1900, erf.day));
+                                ui.end_row();
+                            }
+
+                            ui.label("Module ID");
+                            ui.label(erf.module_id.to_string());
+                            ui.end_row();
+
+                            ui.label("Flags");
+                            ui.label(match erf.encryption() {
+                                0 => format!("{:#010x}", erf.flags),
+                                scheme => {
+                                    format!("{:#010x} (encrypted, scheme {scheme})", erf.flags)
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label("Description strref");
+                            ui.label(
+                                erf.description_strref
+                                    .map_or_else(|| "none".to_string(), |s| s.to_string()),
+                            );
+                            ui.end_row();
+
+                            ui.label("Resources");
+                            ui.label(erf.toc.len().to_string());
+                            ui.end_row();
+                        });
+
+                    if !warnings.is_empty() {
+                        ui.add_space(6.0);
+                        for warning in warnings {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 140, 90),
+                                format!("⚠ {}: {}", warning.kind(), warning.details()),
+                            );
+                        }
+                    }
+
+                    ui.add_space(6.0);
+                    if ui
+                        .add(egui::Button::new("Extract all…").corner_radius(BUTTON_RADIUS))
+                        .on_hover_text("Write every resource of the archive into a folder")
+                        .clicked()
+                    {
+                        extract = true;
+                    }
+
+                    ui.add_space(7.0);
+                    ui.separator();
+                    ui.add_space(4.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("archive_toc")
+                                .num_columns(6)
+                                .striped(true)
+                                .spacing([16.0, 2.0])
+                                .show(ui, |ui| {
+                                    ui.strong("Name");
+                                    ui.strong("Type");
+                                    ui.strong("Offset");
+                                    ui.strong("Packed");
+                                    ui.strong("Length");
+                                    ui.strong("Ratio");
+                                    ui.end_row();
+
+                                    for entry in &erf.toc {
+                                        ui.label(&entry.name);
+                                        ui.label(entry.restype.label());
+                                        ui.label(entry.offset.to_string());
+                                        ui.label(entry.packed_length.to_string());
+                                        ui.label(entry.length.to_string());
+                                        ui.label(
+                                            entry
+                                                .compression_ratio()
+                                                .map_or_else(|| "-".to_string(), format_ratio),
+                                        );
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                });
+            });
+
+        if extract {
+            self.extract_inspected_archive();
+        }
+        if !open {
+            self.inspected_archive = None;
+        }
+    }
+
+    fn extract_inspected_archive(&mut self) {
+        let Some((path, erf, _)) = &self.inspected_archive else {
+            return;
+        };
+        let Some(dir) = rfd::FileDialog::new()
+            .set_title("Extract the archive into")
+            .pick_folder()
+        else {
+            return;
+        };
+
+        let extracted = File::open(path)
+            .map_err(AnyhowError::from)
+            .and_then(|mut file| erf.extract_all(&mut file, &dir));
+        match extracted {
+            Ok(files) => {
+                self.status = format!("Extracted {} resources to {}", files.len(), dir.display());
+            }
+            Err(err) => {
+                self.error = Some(err.context(format!("Failed to extract {}", path.display())));
+            }
+        }
+    }
+
+    fn show_delete_confirmation(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(confirmation) = &mut self.delete_confirmation else {
+            return;
+        };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Confirm delete")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    let display_path = diff_paths(&confirmation.path, bioware_dir)
+                        .unwrap_or_else(|| confirmation.path.clone());
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} is a high-risk file type.",
+                            display_path.display()
+                        ))
+                        .size(14.0),
+                    );
+                    ui.label(format!("Type {DELETE_CONFIRMATION_WORD} to delete it."));
+                    ui.add_space(6.0);
+
+                    ui.add(
+                        egui::TextEdit::singleline(&mut confirmation.typed).desired_width(240.0),
+                    );
+                    ui.add_space(6.0);
+
+                    ui.horizontal(|ui| {
+                        confirmed = ui
+                            .add_enabled(
+                                confirmation.typed.trim() == DELETE_CONFIRMATION_WORD,
+                                egui::Button::new("Delete").corner_radius(BUTTON_RADIUS),
+                            )
+                            .clicked();
+                        cancelled = ui
+                            .add(egui::Button::new("Cancel").corner_radius(BUTTON_RADIUS))
+                            .clicked();
+                    });
+                });
+            });
+
+        if confirmed && let Some(confirmation) = self.delete_confirmation.take() {
+            self.pending_commands.push(Command::DeleteConflictFile(
+                confirmation.key,
+                confirmation.path,
+            ));
+        } else if cancelled || !open {
+            self.delete_confirmation = None;
+        }
+    }
+
+    fn show_keep_only_confirmation(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(confirmation) = &mut self.keep_only else {
+            return;
+        };
+
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let display = |path: &Path| {
+            diff_paths(path, bioware_dir)
+                .unwrap_or_else(|| path.to_path_buf())
+                .display()
+                .to_string()
+        };
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Keep one file")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(format!("Keep {}", display(&confirmation.kept))).size(14.0));
+                    ui.add_space(6.0);
+
+                    if confirmation.deletions.is_empty() {
+                        ui.label("There are no other loose files to delete.");
+                    } else {
+                        ui.label("Move these files to the recycle bin:");
+                        for path in &confirmation.deletions {
+                            ui.monospace(display(path));
+                        }
+                    }
+                    if !confirmation.blocked.is_empty() {
+                        ui.add_space(6.0);
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 140, 90),
+                            "Blocked by the delete rules, left in place:",
+                        );
+                        for path in &confirmation.blocked {
+                            ui.monospace(display(path));
+                        }
+                    }
+                    ui.add_space(6.0);
+
+                    if confirmation.needs_typed {
+                        ui.label(format!(
+                            "Some are high-risk file types. Type {DELETE_CONFIRMATION_WORD} to delete them."
+                        ));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut confirmation.typed)
+                                .desired_width(240.0),
+                        );
+                        ui.add_space(6.0);
+                    }
+
+                    ui.horizontal(|ui| {
+                        confirmed = ui
+                            .add_enabled(
+                                !confirmation.deletions.is_empty()
+                                    && (!confirmation.needs_typed
+                                        || confirmation.typed.trim() == DELETE_CONFIRMATION_WORD),
+                                egui::Button::new(format!(
+                                    "Delete {} files",
+                                    confirmation.deletions.len()
+                                ))
+                                .corner_radius(BUTTON_RADIUS),
+                            )
+                            .clicked();
+                        cancelled = ui
+                            .add(egui::Button::new("Cancel").corner_radius(BUTTON_RADIUS))
+                            .clicked();
+                    });
+                });
+            });
+
+        if confirmed && let Some(confirmation) = self.keep_only.take() {
+            self.pending_commands.extend(
+                confirmation
+                    .deletions
+                    .into_iter()
+                    .map(|path| Command::DeleteConflictFile(confirmation.key.clone(), path)),
+            );
+        } else if cancelled || !open {
+            self.keep_only = None;
+        }
+    }
+
+    fn show_compare_installs(&mut self, ctx: &egui::Context) {
+        let Some(compare) = &mut self.compare else {
+            return;
+        };
+
+        let mut open = true;
+        let mut start = false;
+        let running = self.compare_thread.is_some();
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Compare installs")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    egui::Grid::new("compare_roots")
+                        .num_columns(2)
+                        .spacing([8.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("First install");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut compare.first).desired_width(360.0),
+                            );
+                            ui.end_row();
+
+                            ui.label("Second install");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut compare.second)
+                                    .hint_text("Path to a Dragon Age user folder")
+                                    .desired_width(360.0),
+                            );
+                            ui.end_row();
+                        });
+
+                    ui.add_space(6.0);
+                    start = ui
+                        .add_enabled(
+                            !running
+                                && !compare.first.trim().is_empty()
+                                && !compare.second.trim().is_empty(),
+                            egui::Button::new("Compare").corner_radius(BUTTON_RADIUS),
+                        )
+                        .clicked();
+
+                    let Some(diff) = &compare.result else {
+                        if running {
+                            ui.spinner();
+                        }
+                        return;
+                    };
+
+                    ui.add_space(6.0);
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            for (title, keys, open) in [
+                                ("Only in the first install", &diff.only_first, false),
+                                ("Only in the second install", &diff.only_second, true),
+                                ("In both installs", &diff.both, false),
+                            ] {
+                                egui::CollapsingHeader::new(
+                                    egui::RichText::new(format!("{title} ({})", keys.len()))
+                                        .size(14.0),
+                                )
+                                .default_open(open)
+                                .show(ui, |ui| {
+                                    for key in keys {
+                                        ui.monospace(key);
+                                    }
+                                });
+                            }
+                        });
+                });
+            });
+
+        if start {
+            self.start_compare();
+        }
+        if !open {
+            self.compare = None;
+        }
+    }
+
+    fn show_scan_queue(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(queue) = &mut self.scan_queue else {
+            return;
+        };
+
+        let mut open = true;
+        let mut start = false;
+        let running = self.queue_thread.is_some();
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Scan queue")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut queue.input)
+                                .hint_text("Path to a folder to scan")
+                                .desired_width(360.0),
+                        );
+                        if ui
+                            .add_enabled(
+                                !running && !queue.input.trim().is_empty(),
+                                egui::Button::new("Add").corner_radius(BUTTON_RADIUS),
+                            )
+                            .clicked()
+                        {
+                            queue.targets.push(PathBuf::from(queue.input.trim()));
+                            queue.results.push(None);
+                            queue.input.clear();
+                        }
+                    });
+                    ui.add_space(6.0);
+
+                    let mut removed = None;
+                    egui::Grid::new("scan_queue")
+                        .num_columns(3)
+                        .spacing([12.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (index, target) in queue.targets.iter().enumerate() {
+                                let display_path = diff_paths(target, bioware_dir)
+                                    .filter(|p| !p.starts_with(".."))
+                                    .unwrap_or_else(|| target.clone());
+                                ui.monospace(display_path.display().to_string());
+
+                                match queue.results.get(index).and_then(Option::as_ref) {
+                                    Some(Ok(report)) => {
+                                        ui.label(format!("{} conflicts", report.conflicts.len()))
+                                    }
+                                    Some(Err(err)) => ui.colored_label(
+                                        egui::Color32::from_rgb(255, 100, 100),
+                                        err.as_str(),
+                                    ),
+                                    None if queue.current == Some(index) => ui.spinner(),
+                                    None => ui.label("queued"),
+                                };
+
+                                if ui
+                                    .add_enabled(
+                                        !running,
+                                        egui::Button::new("❌").corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .on_hover_text("Remove from the queue")
+                                    .clicked()
+                                {
+                                    removed = Some(index);
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                    if let Some(index) = removed {
+                        queue.targets.remove(index);
+                        queue.results.remove(index);
+                    }
+
+                    ui.add_space(6.0);
+                    start = ui
+                        .add_enabled(
+                            !running && !queue.targets.is_empty(),
+                            egui::Button::new("Run queue").corner_radius(BUTTON_RADIUS),
+                        )
+                        .clicked();
+                });
+            });
+
+        if start {
+            self.start_queue();
+        }
+        if !open && !running {
+            self.scan_queue = None;
+        }
+    }
+
+    /// Reads and compares the talk tables on a worker, they're often many MB.
+    fn preview_talk_table(&mut self, key: &str, paths: &[PathBuf]) {
+        let Some(winner) = resolve_winner(paths) else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        self.tlk_receiver = Some(rx);
+
+        let sources = paths
+            .iter()
+            .map(|path| {
+                (
+                    path.clone(),
+                    self.conflicts.entry_name(key, path).to_string(),
+                )
+            })
+            .collect();
+        let key = key.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(load_tlk_preview(key, sources, winner));
+        });
+        self.status = "Reading talk tables...".into();
+    }
+
+    fn process_tlk_results(&mut self) {
+        if let Some(receiver) = &self.tlk_receiver
+            && let Ok(result) = receiver.try_recv()
+        {
+            match result {
+                Ok(preview) => {
+                    self.status = format!("Comparing the strings of {}", preview.key);
+                    self.tlk_preview = Some(preview);
+                }
+                Err(err) => self.error = Some(err),
+            }
+            self.tlk_receiver = None;
+        }
+    }
+
+    /// Looks up which files and archives provide a resource, whether or not it
+    /// conflicts.
+    fn show_resource_finder(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(query) = &mut self.resource_finder else {
+            return;
+        };
+
+        let mut open = true;
+        let mut inspected = None;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Find resource")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    let input = ui.add(
+                        egui::TextEdit::singleline(query)
+                            .hint_text("Resource name, e.g. chargen.gda")
+                            .desired_width(420.0),
+                    );
+                    if ui.memory(|memory| memory.focused().is_none()) {
+                        input.request_focus();
+                    }
+
+                    let needle = query.trim().to_lowercase();
+                    if needle.is_empty() {
+                        ui.label(format!(
+                            "Search the {} resources found by the last scan.",
+                            self.resource_index.len()
+                        ));
+                        return;
+                    }
+
+                    let mut matches: Vec<_> = self
+                        .resource_index
+                        .iter()
+                        .filter(|(key, _)| key.contains(&needle))
+                        .collect();
+                    matches.sort_by_key(|&(key, _)| key);
+
+                    if matches.is_empty() {
+                        ui.label("No resource matches.");
+                        return;
+                    }
+                    if matches.len() > MAX_FIND_RESULTS {
+                        ui.weak(format!(
+                            "Showing the first {MAX_FIND_RESULTS} of {} matches",
+                            matches.len()
+                        ));
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            for (key, paths) in matches.into_iter().take(MAX_FIND_RESULTS) {
+                                let mut title = format!("{key} ({} sources)", paths.len());
+                                if self.conflicts.contains_key(key) {
+                                    title.push_str(", conflict");
+                                }
+
+                                egui::CollapsingHeader::new(title)
+                                    .id_salt(("find_resource", key))
+                                    .show(ui, |ui| {
+                                        for path in paths {
+                                            ui.horizontal(|ui| {
+                                                if SourceKind::of(path) == SourceKind::Archive
+                                                    && ui
+                                                        .small_button("🔎")
+                                                        .on_hover_text("Inspect archive")
+                                                        .clicked()
+                                                {
+                                                    inspected = Some(path.clone());
+                                                }
+                                                let display_path = diff_paths(path, bioware_dir)
+                                                    .unwrap_or_else(|| path.clone());
+                                                ui.label(display_path.display().to_string());
+                                            });
+                                        }
+                                    });
+                            }
+                        });
+                });
+            });
+
+        if let Some(path) = inspected {
+            self.resource_finder = None;
+            self.inspect_archive(&path);
+        }
+        if !open {
+            self.resource_finder = None;
+        }
+    }
+
+    fn show_resource_preview(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(preview) = &self.preview else {
+            return;
+        };
+
+        let mut open = true;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new(format!("Preview of {}", preview.key))
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    let display_path = diff_paths(&preview.path, bioware_dir)
+                        .unwrap_or_else(|| preview.path.clone());
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} ({})",
+                            display_path.display(),
+                            format_size(preview.total_len)
+                        ))
+                        .size(13.0),
+                    );
+                    if preview.truncated() {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 140, 90),
+                            format!(
+                                "⚠ Only the first {} are shown",
+                                format_size(MAX_PREVIEW_BYTES as u64)
+                            ),
+                        );
+                    }
+                    ui.add_space(6.0);
+
+                    let text = match &preview.content {
+                        PreviewContent::Text(text) => text,
+                        PreviewContent::Hex(dump) => {
+                            ui.label(egui::RichText::new("Binary content, shown as hex").weak());
+                            dump
+                        }
+                    };
+                    egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
+                        ui.add(egui::Label::new(egui::RichText::new(text).monospace()).extend());
+                    });
+                });
+            });
+
+        if !open {
+            self.preview = None;
+        }
+    }
+
+    fn show_diff_view(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(view) = &self.diff_view else {
+            return;
+        };
+
+        let display = |path: &Path| {
+            diff_paths(path, bioware_dir)
+                .unwrap_or_else(|| path.to_path_buf())
+                .display()
+                .to_string()
+        };
+        let removed_color = egui::Color32::from_rgb(255, 120, 120);
+        let added_color = egui::Color32::from_rgb(120, 200, 120);
+        let mut open = true;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new(format!("Differences in {}", view.key))
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.colored_label(removed_color, format!("− {}", display(&view.first)));
+                    ui.colored_label(added_color, format!("+ {}", display(&view.second)));
+                    ui.add_space(6.0);
+
+                    if view.diff.is_identical() {
+                        ui.label("The two sources are identical.");
+                        return;
+                    }
+
+                    match &view.diff {
+                        ResourceDiff::Text { lines, truncated } => {
+                            if *truncated {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(255, 140, 90),
+                                    format!("⚠ Only the first {MAX_DIFF_LINES} lines were compared"),
+                                );
+                            }
+                            egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
+                                for line in lines {
+                                    let (text, color) = match line {
+                                        DiffLine::Same(text) => (format!("  {text}"), None),
+                                        DiffLine::Removed(text) => {
+                                            (format!("− {text}"), Some(removed_color))
+                                        }
+                                        DiffLine::Added(text) => {
+                                            (format!("+ {text}"), Some(added_color))
+                                        }
+                                    };
+                                    let text = egui::RichText::new(text).monospace();
+                                    ui.add(
+                                        egui::Label::new(match color {
+                                            Some(color) => text.color(color),
+                                            None => text.weak(),
+                                        })
+                                        .extend(),
+                                    );
+                                }
+                            });
+                        }
+                        ResourceDiff::Binary {
+                            ranges,
+                            first_len,
+                            second_len,
+                        } => {
+                            ui.label(format!(
+                                "Binary content, {} against {}",
+                                format_size(*first_len as u64),
+                                format_size(*second_len as u64)
+                            ));
+                            if ranges.len() == MAX_BYTE_RANGES {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(255, 140, 90),
+                                    format!("⚠ Only the first {MAX_BYTE_RANGES} differing ranges are listed"),
+                                );
+                            }
+                            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                                for range in ranges {
+                                    ui.monospace(format!(
+                                        "{:08x}–{:08x}  {} bytes differ",
+                                        range.start,
+                                        range.end - 1,
+                                        range.len()
+                                    ));
+                                }
+                                if first_len != second_len {
+                                    ui.monospace(format!(
+                                        "{:08x}–  only in the {} source",
+                                        first_len.min(second_len),
+                                        if first_len > second_len { "first" } else { "second" }
+                                    ));
+                                }
+                            });
+                        }
+                    }
+                });
+            });
+
+        if !open {
+            self.diff_view = None;
+        }
+    }
+
+    fn show_backup_sessions(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(sessions) = &self.backup_sessions else {
+            return;
+        };
+
+        let mut open = true;
+        let mut restored = None;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Restore from backup")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    if sessions.is_empty() {
+                        ui.label("No backups yet, turn on \"Back up deletions\" to keep copies of deleted files.");
+                        return;
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            for (index, session) in sessions.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add(
+                                            egui::Button::new("Restore")
+                                                .corner_radius(BUTTON_RADIUS),
+                                        )
+                                        .on_hover_text(
+                                            "Copy these files back where they were, skipping any that exist again",
+                                        )
+                                        .clicked()
+                                    {
+                                        restored = Some(index);
+                                    }
+
+                                    egui::CollapsingHeader::new(format!(
+                                        "{} ({} files)",
+                                        format_date(session.timestamp),
+                                        session.entries.len()
+                                    ))
+                                    .id_salt(("backup_session", session.timestamp))
+                                    .show(ui, |ui| {
+                                        for entry in &session.entries {
+                                            let display_path =
+                                                diff_paths(&entry.original, bioware_dir)
+                                                    .unwrap_or_else(|| entry.original.clone());
+                                            ui.label(display_path.display().to_string());
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                });
+            });
+
+        if let Some(index) = restored
+            && let Some(session) = sessions.get(index).cloned()
+        {
+            self.restore_backup(&session);
+        }
+        if !open {
+            self.backup_sessions = None;
+        }
+    }
+
+    fn show_tlk_preview(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(preview) = &self.tlk_preview else {
+            return;
+        };
+
+        let display = |path: &Path| {
+            diff_paths(path, bioware_dir)
+                .unwrap_or_else(|| path.to_path_buf())
+                .display()
+                .to_string()
+        };
+        let mut open = true;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new(format!("String changes in {}", preview.key))
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Compared with {} ⭐",
+                            display(&preview.winner)
+                        ))
+                        .size(14.0),
+                    );
+                    if preview.truncated {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 140, 90),
+                            format!(
+                                "⚠ Only the first {MAX_TLK_ENTRIES} strings of each table were read"
+                            ),
+                        );
+                    }
+                    ui.add_space(6.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            for (index, (path, diff)) in preview.comparisons.iter().enumerate() {
+                                let diffs = match diff {
+                                    Ok(diffs) => diffs,
+                                    Err(err) => {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(255, 100, 100),
+                                            format!("{}: {err}", display(path)),
+                                        );
+                                        continue;
+                                    }
+                                };
+
+                                egui::CollapsingHeader::new(format!(
+                                    "{} ({} differing strings)",
+                                    display(path),
+                                    diffs.len()
+                                ))
+                                .id_salt(("tlk_preview", index))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    egui::Grid::new(("tlk_diff", index))
+                                        .num_columns(3)
+                                        .spacing([12.0, 4.0])
+                                        .striped(true)
+                                        .show(ui, |ui| {
+                                            ui.strong("ID");
+                                            ui.strong("This source");
+                                            ui.strong("Winner");
+                                            ui.end_row();
+
+                                            for diff in diffs.iter().take(MAX_SHOWN_STRING_DIFFS) {
+                                                ui.monospace(diff.id.to_string());
+                                                ui.label(
+                                                    diff.first.as_deref().unwrap_or("(missing)"),
+                                                );
+                                                ui.label(
+                                                    diff.second.as_deref().unwrap_or("(missing)"),
+                                                );
+                                                ui.end_row();
+                                            }
+                                        });
+
+                                    if diffs.len() > MAX_SHOWN_STRING_DIFFS {
+                                        ui.label(format!(
+                                            "… and {} more",
+                                            diffs.len() - MAX_SHOWN_STRING_DIFFS
+                                        ));
+                                    }
+                                });
+                            }
+                        });
+                });
+            });
+
+        if !open {
+            self.tlk_preview = None;
+        }
+    }
+
+    fn show_provider_history(&mut self, ctx: &egui::Context, bioware_dir: &Path) {
+        let Some(key) = &self.history_key else {
+            return;
+        };
+
+        let events = provider_timeline(&self.snapshots, key);
+        let mut open = true;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new(format!("Provider history of {key}"))
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Across the last {} scans",
+                            self.snapshots.len()
+                        ))
+                        .size(14.0),
+                    );
+                    ui.add_space(6.0);
+
+                    if events.is_empty() {
+                        ui.label(
+                            egui::RichText::new("No recorded changes")
+                                .color(egui::Color32::DARK_GRAY)
+                                .size(14.0),
+                        );
+                        return;
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("provider_history")
+                                .num_columns(3)
+                                .spacing([12.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for event in &events {
+                                        ui.label(format!(
+                                            "Scan {} ({})",
+                                            event.scan,
+                                            format_date(event.timestamp)
+                                        ));
+                                        match event.change {
+                                            ProviderChange::Added => ui.colored_label(
+                                                egui::Color32::from_rgb(120, 200, 120),
+                                                "added",
+                                            ),
+                                            ProviderChange::Removed => ui.colored_label(
+                                                egui::Color32::from_rgb(255, 100, 100),
+                                                "removed",
+                                            ),
+                                        };
+                                        let display_path = diff_paths(&event.path, bioware_dir)
+                                            .unwrap_or_else(|| event.path.clone());
+                                        ui.monospace(display_path.display().to_string());
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                });
+            });
+
+        if !open {
+            self.history_key = None;
+        }
+    }
+
+    fn main_ui(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        egui::TopBottomPanel::top("controls").show_inside(ui, |ui| {
+            self.scan_controls(ui, bioware_dir);
+            if !self.scan_warnings.is_empty() && self.scan_thread.is_none() {
+                ui.add_space(4.0);
+                self.scan_warnings_panel(ui, bioware_dir);
+            }
+            ui.add_space(8.0);
+        });
+
+        if !self.staged_commands.is_empty() {
+            egui::TopBottomPanel::bottom("staged").show_inside(ui, |ui| {
+                ui.add_space(8.0);
+                self.staged_panel(ui, bioware_dir);
+            });
+        }
+
+        if !self.disabled_files.is_empty() {
+            egui::TopBottomPanel::bottom("disabled").show_inside(ui, |ui| {
+                ui.add_space(8.0);
+                self.disabled_panel(ui, bioware_dir);
+            });
+        }
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("main_results")
+                .show(ui, |ui| {
+                    self.results_panel(ui, bioware_dir);
+                });
+        });
+    }
+
+    fn staged_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!("Staged actions ({})", self.staged_commands.len()))
+                    .size(16.0),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add(egui::Button::new("Discard all").corner_radius(BUTTON_RADIUS))
+                    .clicked()
+                {
+                    self.staged_commands.clear();
+                }
+                if ui
+                    .add_enabled(
+                        !self.read_only,
+                        egui::Button::new("Apply all").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text("Run every staged action in order")
+                    .clicked()
+                {
+                    self.apply_staged_commands();
+                }
+            });
+        });
+
+        let mut removed = None;
+        egui::ScrollArea::vertical()
+            .id_salt("staged_list")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for (index, command) in self.staged_commands.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add(egui::Button::new("✖").corner_radius(BUTTON_RADIUS))
+                            .on_hover_text("Drop this action")
+                            .clicked()
+                        {
+                            removed = Some(index);
+                        }
+                        ui.label(command.describe(bioware_dir));
+                    });
+                }
+            });
+
+        if let Some(index) = removed {
+            self.staged_commands.remove(index);
+        }
+    }
+
+    /// Shown instead of the scanner while there's no BioWare folder to scan.
+    fn missing_dir_state(&mut self, ui: &mut egui::Ui) {
+        let message = match &self.config.bioware_dir {
+            Some(dir) => format!(
+                "The chosen folder {} doesn't exist anymore.",
+                dir.display().to_string().replace(r"\\?\", "")
+            ),
+            None => "The 'Documents/BioWare/Dragon Age' folder wasn't found. \
+                     Start the game once to create it, or choose where it is."
+                .to_string(),
+        };
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.label(egui::RichText::new("📁").size(48.0));
+            ui.add_space(8.0);
+            ui.label(egui::RichText::new("No game folder to scan").size(20.0));
+            ui.add_space(4.0);
+            ui.label(message);
+            ui.add_space(12.0);
+            if ui
+                .add(
+                    egui::Button::new(egui::RichText::new("Choose game folder…").size(16.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Pick the BioWare/Dragon Age folder holding packages and AddIns")
+                .clicked()
+            {
+                self.choose_bioware_dir();
+            }
+        });
+    }
+
+    fn scan_warnings_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        egui::CollapsingHeader::new(
+            egui::RichText::new(format!("⚠ Scan warnings ({})", self.scan_warnings.len()))
+                .color(egui::Color32::from_rgb(255, 200, 90))
+                .size(14.0),
+        )
+        .id_salt("scan_warnings")
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("scan_warnings_list")
+                .max_height(160.0)
+                .show(ui, |ui| {
+                    for warning in &self.scan_warnings {
+                        let display_path = diff_paths(&warning.path, bioware_dir)
+                            .unwrap_or_else(|| warning.path.clone());
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .link(display_path.display().to_string())
+                                .on_hover_text("Show in file manager")
+                                .clicked()
+                            {
+                                let _ = reveal_in_file_manager(&warning.path);
+                            }
+                            ui.label(
+                                egui::RichText::new(&warning.message)
+                                    .color(egui::Color32::GRAY)
+                                    .size(12.0),
+                            );
+                        });
+                    }
+                });
+        });
+    }
+
+    fn scan_progress_bar(&self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        let progress = &self.scan_progress;
+        let current = diff_paths(&progress.current_path, bioware_dir)
+            .unwrap_or_else(|| progress.current_path.clone());
+
+        let bar = match (progress.groups_total, progress.erfs_total) {
+            (Some(total), _) => egui::ProgressBar::new(
+                progress.groups_hashed as f32 / total.max(1) as f32,
+            )
+            .text(format!(
+                "Compared {} of {total} conflicts, {}",
+                progress.groups_hashed,
+                current.display()
+            )),
+            (None, Some(total)) => egui::ProgressBar::new(
+                progress.erfs_parsed as f32 / total.max(1) as f32,
+            )
+            .text(format!(
+                "Parsed {} of {total} archives, {}",
+                progress.erfs_parsed,
+                current.display()
+            )),
+            (None, None) => egui::ProgressBar::new(0.0).animate(true).text(format!(
+                "Walked {} files, {}",
+                progress.files_seen,
+                current.display()
+            )),
+        };
+        ui.add(bar);
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
+    }
+
+    fn scan_controls(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().button_padding = egui::vec2(24.0, 6.0);
+
+            // Scan button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none(),
+                    egui::Button::new(egui::RichText::new("🔍").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text(format!(
+                    "Start new scan ({} or {})",
+                    ui.ctx().format_shortcut(&SCAN_SHORTCUT),
+                    ui.ctx().format_shortcut(&RESCAN_SHORTCUT)
+                ))
+                .clicked()
+            {
+                self.start_scan(bioware_dir);
+            }
+
+            // Cancel button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_some() && !self.scan_cancel.load(Ordering::Relaxed),
+                    egui::Button::new(egui::RichText::new("⏹").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Cancel the running scan")
+                .clicked()
+            {
+                self.scan_cancel.store(true, Ordering::Relaxed);
+                self.status = "Cancelling scan...".into();
+            }
+
+            // Folder button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none(),
+                    egui::Button::new(egui::RichText::new("📁").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text(format!(
+                    "Choose the BioWare folder to scan, currently {}",
+                    bioware_dir.display().to_string().replace(r"\\?\", "")
+                ))
+                .clicked()
+            {
+                self.choose_bioware_dir();
+            }
+
+            // Scan another folder button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none(),
+                    egui::Button::new(egui::RichText::new("📂").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text(
+                    "Scan another folder, such as a modpack staging folder, without changing the BioWare folder",
+                )
+                .clicked()
+            {
+                self.scan_folder();
+            }
+
+            // Verify button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none()
+                        && self.verify_thread.is_none()
+                        && !self.archives.is_empty(),
+                    egui::Button::new(egui::RichText::new("🛡").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Verify all ERF archives found by the last scan")
+                .clicked()
+            {
+                self.start_verify();
+            }
+
+            // Undo button
+            let undo_hint = self.undo_stack.last().map(UndoAction::describe);
+            if ui
+                .add_enabled(
+                    undo_hint.is_some() && !self.read_only,
+                    egui::Button::new(egui::RichText::new("↶").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text(format!(
+                    "Undo {} ({})",
+                    undo_hint.as_deref().unwrap_or("the last action"),
+                    ui.ctx().format_shortcut(&UNDO_SHORTCUT)
+                ))
+                .on_disabled_hover_text("Nothing to undo")
+                .clicked()
+            {
+                self.undo();
+            }
+
+            // Summary button
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none() && self.summary.is_some() && !self.show_summary,
+                    egui::Button::new(egui::RichText::new("📊").size(24.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Show the summary of the last scan")
+                .clicked()
+            {
+                self.show_summary = true;
+            }
+
+            // Export menu
+            ui.add_enabled_ui(self.scan_thread.is_none() && self.has_scanned, |ui| {
+                ui.menu_button(egui::RichText::new("📄").size(24.0), |ui| {
+                    if ui.button("Text report").clicked() {
+                        self.export_list(bioware_dir);
+                        ui.close_menu();
+                    }
+                    if ui.button("CSV…").clicked() {
+                        self.export_to_file(bioware_dir, ExportFormat::Csv);
+                        ui.close_menu();
+                    }
+                    if ui.button("JSON…").clicked() {
+                        self.export_to_file(bioware_dir, ExportFormat::Json);
+                        ui.close_menu();
+                    }
+                })
+                .response
+                .on_hover_text("Export the conflicts as currently shown");
+            });
+
+            ui.add_space(4.0);
+            if self.read_only {
+                ui.colored_label(egui::Color32::from_rgb(255, 200, 90), "🔒 Read-only")
+                    .on_hover_text("Files and resolutions can't be changed in this session");
+            }
+            ui.label(egui::RichText::new(&self.status).size(14.0));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.spacing_mut().button_padding = egui::vec2(4.0, 2.0);
+
+                ui.with_layout(egui::Layout::top_down(egui::Align::RIGHT), |ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(egui::RichText::new("⏷").size(12.0))
+                                .corner_radius(BUTTON_RADIUS),
+                        )
+                        .on_hover_text(format!(
+                            "Expand all conflicts ({})",
+                            ui.ctx().format_shortcut(&EXPAND_SHORTCUT)
+                        ))
+                        .clicked()
+                    {
                         self.expand_all();
                     }
                     if ui
-                        .add(
-                            egui::Button::new(egui::RichText::new("⏶").size(12.0))
-                                .corner_radius(BUTTON_RADIUS),
+                        .add(
+                            egui::Button::new(egui::RichText::new("⏶").size(12.0))
+                                .corner_radius(BUTTON_RADIUS),
+                        )
+                        .on_hover_text(format!(
+                            "Collapse all conflicts ({})",
+                            ui.ctx().format_shortcut(&COLLAPSE_SHORTCUT)
+                        ))
+                        .clicked()
+                    {
+                        self.collapse_all();
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.checkbox(&mut self.only_actionable, "Only actionable")
+                    .on_hover_text("Show only conflicts with at least one loose file");
+                ui.checkbox(&mut self.config.detect_divergence, "Compare contents")
+                    .on_hover_text(
+                        "Hash file contents during scans to tell identical copies from divergent ones, which makes scans slower",
+                    );
+                ui.add_enabled(
+                    self.config.detect_divergence,
+                    egui::Checkbox::new(
+                        &mut self.config.auto_expand_divergent,
+                        "Auto-expand divergent",
+                    ),
+                )
+                .on_hover_text("Expand conflicts whose files differ after each scan");
+                ui.checkbox(&mut self.config.skip_empty_files, "Skip empty files")
+                    .on_hover_text("Leave zero-byte files out of the next scan");
+                ui.checkbox(&mut self.config.hide_identical, "Hide identical duplicates")
+                    .on_hover_text(
+                        "Compare file contents during scans and leave out conflicts whose copies are byte-identical, which makes scans slower",
+                    );
+                ui.checkbox(&mut self.config.backup_before_delete, "Back up deletions")
+                    .on_hover_text(
+                        "Copy files into a timestamped backups folder before deleting them, restore them from the command palette",
+                    );
+                ui.checkbox(&mut self.config.collapse_layers, "Collapse folders")
+                    .on_hover_text(
+                        "Show sources from the same add-in or override folder as a single line",
+                    );
+                ui.checkbox(&mut self.config.stage_actions, "Stage actions")
+                    .on_hover_text(
+                        "Collect ignores, deletes and disables in a list to review and apply together",
+                    );
+                ui.checkbox(&mut self.config.remember_view, "Remember view")
+                    .on_hover_text("Restore the search and list view on launch");
+                ui.checkbox(&mut self.config.override_only_scan, "Override only")
+                    .on_hover_text(
+                        "Fast scan of loose override files that skips every ERF archive",
+                    );
+                ui.checkbox(&mut self.config.dedup_hardlinks, "Merge hardlinks")
+                    .on_hover_text("Treat hardlinked copies of a file as a single source");
+                let mut by_base_name = self.config.grouping == Grouping::BaseName;
+                if ui
+                    .checkbox(&mut by_base_name, "Group by base name")
+                    .on_hover_text(
+                        "Group files sharing a name across types (foo.utc with foo.uti) on the next scan",
+                    )
+                    .changed()
+                {
+                    self.config.grouping = if by_base_name {
+                        Grouping::BaseName
+                    } else {
+                        Grouping::ExactName
+                    };
+                }
+                if cfg!(windows) {
+                    ui.checkbox(&mut self.config.low_priority_scan, "Low priority scan")
+                        .on_hover_text(
+                            "Scan at background disk priority while the game is running",
+                        );
+                }
+            });
+        });
+
+        if self.scan_thread.is_some() {
+            ui.add_space(4.0);
+            self.scan_progress_bar(ui, bioware_dir);
+        }
+    }
+
+    fn summary_panel(&mut self, ui: &mut egui::Ui, summary: &ScanSummary, bioware_dir: &Path) {
+        ui.add_space(8.0);
+        ui.label(egui::RichText::new("Scan summary").size(24.0));
+        if self.override_only {
+            self.override_only_notice(ui);
+        }
+        ui.label(format!(
+            "{} resources from {} providers across {} archives and {} loose files.",
+            summary.resources, summary.providers, summary.archives, summary.loose_files
+        ));
+        ui.add_space(8.0);
+
+        egui::Grid::new("scan_summary")
+            .num_columns(2)
+            .spacing([24.0, 6.0])
+            .show(ui, |ui| {
+                ui.label("Conflicts found");
+                ui.label(summary.total.to_string());
+                ui.end_row();
+
+                ui.label("Unresolved");
+                ui.label(summary.unresolved.to_string());
+                ui.end_row();
+
+                ui.label("Actionable (with loose files)");
+                ui.label(summary.actionable.to_string());
+                ui.end_row();
+
+                if summary.core_shadowing > 0 {
+                    ui.label("Overrides shadowing core content");
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 100, 100),
+                        summary.core_shadowing.to_string(),
+                    );
+                    ui.end_row();
+                }
+
+                if summary.unexpected_winners > 0 {
+                    ui.label("Unexpected winners");
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 140, 90),
+                        summary.unexpected_winners.to_string(),
+                    );
+                    ui.end_row();
+                }
+
+                if summary.divergent + summary.identical > 0 {
+                    ui.label("Divergent / identical content");
+                    ui.label(format!("{} / {}", summary.divergent, summary.identical));
+                    ui.end_row();
+                }
+
+                ui.label("Reclaimable space")
+                    .on_hover_text("Size of the loose files that lose their conflict");
+                ui.label(format_size(summary.reclaimable_bytes));
+                ui.end_row();
+
+                ui.label("Unreadable archives");
+                if summary.failed_archives > 0 {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 100, 100),
+                        summary.failed_archives.to_string(),
+                    );
+                } else {
+                    ui.label("0");
+                }
+                ui.end_row();
+
+                if summary.skipped_empty > 0 {
+                    ui.label("Skipped empty files");
+                    ui.label(summary.skipped_empty.to_string());
+                    ui.end_row();
+                }
+            });
+
+        if !summary.by_type.is_empty() {
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new("By type").size(16.0));
+            egui::Grid::new("scan_summary_types")
+                .num_columns(2)
+                .spacing([24.0, 4.0])
+                .show(ui, |ui| {
+                    for (ext, count) in &summary.by_type {
+                        ui.monospace(format!(".{ext}"));
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.add_space(16.0);
+        ui.horizontal(|ui| {
+            ui.spacing_mut().button_padding = egui::vec2(12.0, 6.0);
+
+            if ui
+                .add(egui::Button::new("Show all conflicts").corner_radius(BUTTON_RADIUS))
+                .clicked()
+            {
+                self.only_actionable = false;
+                self.show_summary = false;
+            }
+            if ui
+                .add_enabled(
+                    summary.actionable > 0,
+                    egui::Button::new("Show actionable conflicts").corner_radius(BUTTON_RADIUS),
+                )
+                .clicked()
+            {
+                self.only_actionable = true;
+                self.show_summary = false;
+            }
+            if summary.failed_archives > 0
+                && ui
+                    .add_enabled(
+                        self.verify_thread.is_none(),
+                        egui::Button::new("Verify archives").corner_radius(BUTTON_RADIUS),
+                    )
+                    .clicked()
+            {
+                self.start_verify();
+            }
+            if !self.failed_archives.is_empty()
+                && ui
+                    .add(egui::Button::new("Retry failed archives").corner_radius(BUTTON_RADIUS))
+                    .on_hover_text("Read the unreadable archives again without a full rescan")
+                    .clicked()
+            {
+                self.retry_failed_archives(bioware_dir);
+            }
+        });
+    }
+
+    fn results_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        if self.scan_thread.is_some() {
+            return;
+        }
+
+        if self.show_summary
+            && let Some(summary) = self.summary.clone()
+        {
+            self.summary_panel(ui, &summary, bioware_dir);
+            return;
+        }
+
+        self.list_controls(ui, bioware_dir);
+        ui.add_space(4.0);
+
+        if self.override_only {
+            self.override_only_notice(ui);
+        }
+        if self.scanned_folder.is_some() {
+            self.scanned_folder_notice(ui);
+        }
+        if self.results_filter.resource_type.is_some() {
+            self.active_filter_bar(ui);
+        }
+        if self.list_view.shows_resolved() {
+            self.forget_all_button(ui);
+            self.ignored_names_panel(ui);
+            self.ignore_patterns_panel(ui);
+            self.excluded_dirs_panel(ui, bioware_dir);
+        }
+
+        // Unresolved conflicts are unknown until the first scan
+        if !self.has_scanned && self.list_view == ListView::Unresolved {
+            return;
+        }
+
+        let sections = self.visible_results(bioware_dir);
+        let resolved = self.visible_ignored();
+
+        if self.scroll_to_focus {
+            let entries = sections.iter().flat_map(|s| &s.entries).chain(&resolved);
+            self.search_focus = first_match(entries, &self.results_filter).map(str::to_string);
+            self.scroll_to_focus = self.search_focus.is_some();
+        }
+
+        if resolved.is_empty() && sections.iter().all(|s| s.entries.is_empty()) {
+            let has_unresolved = self
+                .conflicts
+                .iter()
+                .any(|(key, paths)| self.is_unresolved(key, paths));
+            let message = if !self.results_filter.is_empty() {
+                "No matching conflicts"
+            } else if self.list_view == ListView::Resolved {
+                "No resolved conflicts"
+            } else if has_unresolved {
+                "No actionable conflicts"
+            } else {
+                "All conflicts resolved!"
+            };
+
+            ui.centered_and_justified(|ui| {
+                ui.add(egui::Label::new(egui::RichText::new(message).size(24.0)).selectable(false));
+            });
+            return;
+        }
+
+        let unresolved: Conflicts = self
+            .conflicts
+            .iter()
+            .filter(|(key, paths)| self.is_unresolved(key, paths))
+            .map(|(key, paths)| (key.clone(), paths.clone()))
+            .collect();
+        let duplicates = if self.list_view.shows_unresolved() {
+            find_duplicate_installs(&unresolved, &self.divergence, bioware_dir)
+        } else {
+            Vec::new()
+        };
+
+        egui::ScrollArea::both()
+            .id_salt("results_panel")
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                if !duplicates.is_empty() {
+                    self.duplicate_installs_section(ui, &duplicates, &unresolved, bioware_dir);
+                }
+
+                for ConflictSection { kind, entries } in sections {
+                    let header = match kind {
+                        SectionKind::CoreShadowing => Some((
+                            "⛔",
+                            egui::Color32::from_rgb(255, 100, 100),
+                            "A loose override replaces a resource packed with the base game",
+                        )),
+                        SectionKind::UnexpectedWinner => Some((
+                            "⚠",
+                            egui::Color32::from_rgb(255, 140, 90),
+                            "Your preferred file is not the one the game will load",
+                        )),
+                        SectionKind::UnpairedScript => Some((
+                            "⚙",
+                            egui::Color32::from_rgb(230, 200, 90),
+                            "Only the source or only the compiled script is overridden, so they may not match",
+                        )),
+                        SectionKind::Other | SectionKind::Resolved => None,
+                    };
+
+                    if let Some((icon, color, hover)) = header {
+                        if entries.is_empty() {
+                            continue;
+                        }
+
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{icon} {} ({})",
+                                kind.title(),
+                                entries.len()
+                            ))
+                            .color(color)
+                            .size(16.0),
+                        )
+                        .on_hover_text(hover);
+                    }
+
+                    for (key, paths) in entries {
+                        self.render_result_conflict(ui, &key, &paths, bioware_dir);
+                    }
+
+                    if header.is_some() {
+                        ui.add_space(6.0);
+                        ui.separator();
+                        ui.add_space(6.0);
+                    }
+                }
+
+                if !resolved.is_empty() {
+                    if self.list_view == ListView::All {
+                        ui.add_space(6.0);
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "✔ {} ({})",
+                                SectionKind::Resolved.title(),
+                                resolved.len()
+                            ))
+                            .color(egui::Color32::from_rgb(120, 200, 120))
+                            .size(16.0),
+                        );
+                    }
+
+                    for (key, paths) in resolved {
+                        self.render_ignored_conflict(ui, &key, &paths, bioware_dir);
+                    }
+                }
+            });
+    }
+
+    fn forget_all_button(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .add_enabled(
+                !self.read_only && !self.config.ignored.is_empty(),
+                egui::Button::new(format!("Forget all ({})", self.config.ignored.len()))
+                    .corner_radius(BUTTON_RADIUS),
+            )
+            .on_hover_text("Forget every resolved conflict, so they're reported again")
+            .clicked()
+        {
+            self.forget_all_confirmation = true;
+        }
+    }
+
+    fn show_forget_all_confirmation(&mut self, ctx: &egui::Context) {
+        if !self.forget_all_confirmation {
+            return;
+        }
+
+        let count = self.config.ignored.len();
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        show_modal_overlay(ctx);
+
+        egui::Window::new("Forget all resolved conflicts")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Frame::new().inner_margin(6.0).show(ui, |ui| {
+                    ui.label(format!(
+                        "Forget {count} resolved conflicts? They'll be listed as unresolved again."
+                    ));
+                    ui.add_space(6.0);
+
+                    ui.horizontal(|ui| {
+                        confirmed = ui
+                            .add_enabled(
+                                count > 0,
+                                egui::Button::new(format!("Forget {count}"))
+                                    .corner_radius(BUTTON_RADIUS),
+                            )
+                            .clicked();
+                        cancelled = ui
+                            .add(egui::Button::new("Cancel").corner_radius(BUTTON_RADIUS))
+                            .clicked();
+                    });
+                });
+            });
+
+        if confirmed {
+            self.pending_commands.push(Command::UnignoreAll);
+        }
+        if confirmed || cancelled || !open {
+            self.forget_all_confirmation = false;
+        }
+    }
+
+    fn ignored_names_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(format!(
+            "Ignored file names ({})",
+            self.config.ignored_names.len()
+        ))
+        .id_salt("ignored_names")
+        .show(ui, |ui| {
+            let mut removed = None;
+            for name in &self.config.ignored_names {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !self.read_only,
+                            egui::Button::new("✖").corner_radius(BUTTON_RADIUS),
                         )
-                        .on_hover_text("Collapse all conflicts")
+                        .on_hover_text("Report this file again from the next scan")
                         .clicked()
                     {
-                        self.collapse_all();
+                        removed = Some(name.clone());
                     }
+                    ui.monospace(name);
                 });
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.ignored_name_input)
+                        .hint_text("e.g. chargenmorphcfg.xml")
+                        .desired_width(200.0),
+                );
+                let name = self.ignored_name_input.trim().to_ascii_lowercase();
+                if ui
+                    .add_enabled(
+                        !self.read_only && !name.is_empty(),
+                        egui::Button::new("Add").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text("Leave this file name out from the next scan")
+                    .clicked()
+                {
+                    self.ignored_name_input.clear();
+                    self.config.ignored_names.insert(name);
+                    let _ = self.config.save();
+                }
             });
+
+            if let Some(name) = removed {
+                self.config.ignored_names.remove(&name);
+                let _ = self.config.save();
+            }
         });
     }
 
-    fn results_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
-        if self.scan_thread.is_some() || !self.has_scanned {
-            return;
+    fn excluded_dirs_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        egui::CollapsingHeader::new(format!(
+            "Excluded folders ({})",
+            self.config.excluded_dirs.len()
+        ))
+        .id_salt("excluded_dirs")
+        .show(ui, |ui| {
+            let mut removed = None;
+            for (index, dir) in self.config.excluded_dirs.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !self.read_only,
+                            egui::Button::new("✖").corner_radius(BUTTON_RADIUS),
+                        )
+                        .on_hover_text("Scan this again from the next scan")
+                        .clicked()
+                    {
+                        removed = Some(index);
+                    }
+                    let display_path = diff_paths(dir, bioware_dir).unwrap_or_else(|| dir.clone());
+                    ui.monospace(display_path.display().to_string());
+                });
+            }
+
+            ui.horizontal(|ui| {
+                let mut picked = None;
+                if ui
+                    .add_enabled(
+                        !self.read_only,
+                        egui::Button::new("Add folder…").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text("Never scan anything inside a folder")
+                    .clicked()
+                {
+                    picked = rfd::FileDialog::new()
+                        .set_title("Choose a folder to exclude from scans")
+                        .set_directory(bioware_dir)
+                        .pick_folder();
+                }
+                if ui
+                    .add_enabled(
+                        !self.read_only,
+                        egui::Button::new("Add archive…").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text("Never scan a single archive")
+                    .clicked()
+                {
+                    picked = rfd::FileDialog::new()
+                        .set_title("Choose an archive to exclude from scans")
+                        .set_directory(bioware_dir)
+                        .add_filter("Archives", &["erf", "rim"])
+                        .pick_file();
+                }
+
+                // Canonical like the scanned paths, so the prefix check matches
+                if let Some(path) = picked {
+                    let path = path.canonicalize().unwrap_or(path);
+                    if !self.config.excluded_dirs.contains(&path) {
+                        self.config.excluded_dirs.push(path);
+                        let _ = self.config.save();
+                    }
+                }
+            });
+
+            if let Some(index) = removed {
+                self.config.excluded_dirs.remove(index);
+                let _ = self.config.save();
+            }
+        });
+    }
+
+    fn ignore_patterns_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(format!(
+            "Ignored name patterns ({})",
+            self.config.ignore_patterns.len()
+        ))
+        .id_salt("ignore_patterns")
+        .show(ui, |ui| {
+            let mut removed = None;
+            for (index, pattern) in self.config.ignore_patterns.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !self.read_only,
+                            egui::Button::new("✖").corner_radius(BUTTON_RADIUS),
+                        )
+                        .on_hover_text("Stop ignoring this pattern from the next scan")
+                        .clicked()
+                    {
+                        removed = Some(index);
+                    }
+                    ui.monospace(pattern);
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.pattern_input)
+                        .hint_text("e.g. *.gda or genmenu_*")
+                        .desired_width(200.0),
+                );
+                if ui
+                    .add_enabled(
+                        !self.read_only && !self.pattern_input.trim().is_empty(),
+                        egui::Button::new("Ignore by name pattern").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text(
+                        "Ignore every conflict whose resource name matches, * and ? are wildcards",
+                    )
+                    .clicked()
+                {
+                    let pattern = mem::take(&mut self.pattern_input).trim().to_string();
+                    self.ignore_pattern(pattern);
+                }
+            });
+
+            if let Some(index) = removed {
+                self.config.ignore_patterns.remove(index);
+                let _ = self.config.save();
+            }
+        });
+    }
+
+    /// Ignores conflicts matching `pattern` in this and every later scan.
+    fn ignore_pattern(&mut self, pattern: String) {
+        let before = self.conflicts.len();
+        self.conflicts
+            .retain(|key, _| !matches_pattern(&pattern, resource_name(key)));
+
+        self.status = format!(
+            "Ignoring conflicts matching {pattern}, {} hidden",
+            before - self.conflicts.len()
+        );
+        if !self.config.ignore_patterns.contains(&pattern) {
+            self.config.ignore_patterns.push(pattern);
+        }
+        let _ = self.config.save();
+    }
+
+    fn scanned_folder_notice(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 200, 90),
+                "⚠ Scanning a folder outside the BioWare folder, every loose file counts as an override and ignores aren't saved",
+            );
+            if ui
+                .add_enabled(
+                    self.scan_thread.is_none(),
+                    egui::Button::new("Back to the BioWare folder").corner_radius(BUTTON_RADIUS),
+                )
+                .clicked()
+            {
+                self.close_scanned_folder();
+            }
+        });
+    }
+
+    fn override_only_notice(&self, ui: &mut egui::Ui) {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 200, 90),
+            "⚠ Override only: archives were not scanned, so conflicts with ERF content are missing",
+        );
+    }
+
+    fn active_filter_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut parts = Vec::new();
+            if !self.results_filter.query.trim().is_empty() {
+                parts.push(format!("\"{}\"", self.results_filter.query.trim()));
+            }
+            if let Some(ext) = &self.results_filter.resource_type {
+                parts.push(format!(".{ext} files"));
+            }
+            if !self.results_filter.categories.is_empty() {
+                let labels: Vec<_> = self
+                    .results_filter
+                    .categories
+                    .iter()
+                    .map(|restype| restype.label())
+                    .collect();
+                parts.push(labels.join(" or "));
+            }
+
+            ui.label(
+                egui::RichText::new(format!("Filtered by {}", parts.join(", ")))
+                    .color(egui::Color32::LIGHT_BLUE),
+            );
+            if ui
+                .add(egui::Button::new("✖ Clear").corner_radius(BUTTON_RADIUS))
+                .clicked()
+            {
+                self.results_filter = ResultsFilter::default();
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    fn duplicate_installs_section(
+        &mut self,
+        ui: &mut egui::Ui,
+        duplicates: &[DuplicateInstall],
+        conflicts: &Conflicts,
+        bioware_dir: &Path,
+    ) {
+        ui.label(
+            egui::RichText::new(format!("⧉ Duplicate installs ({})", duplicates.len()))
+                .color(egui::Color32::from_rgb(120, 180, 255))
+                .size(16.0),
+        )
+        .on_hover_text("Folders holding identical copies of the same files");
+
+        for duplicate in duplicates {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().button_padding = egui::vec2(6.0, 4.0);
+
+                let [first, second] = duplicate.folders.each_ref().map(|folder| {
+                    diff_paths(folder, bioware_dir).unwrap_or_else(|| folder.clone())
+                });
+                ui.label(format!(
+                    "{} ↔ {} ({} identical conflicts)",
+                    first.display(),
+                    second.display(),
+                    duplicate.keys.len()
+                ));
+
+                for (folder, relative) in duplicate.folders.iter().zip([&first, &second]) {
+                    if ui
+                        .add_enabled(
+                            !self.read_only,
+                            egui::Button::new(format!("Remove copy at {}", relative.display()))
+                                .corner_radius(BUTTON_RADIUS),
+                        )
+                        .on_hover_text("Disable every duplicated file in this folder")
+                        .clicked()
+                    {
+                        self.pending_commands.push(Command::DisableDuplicateInstall(
+                            duplicate.files_in(folder, conflicts),
+                        ));
+                    }
+                }
+            });
+        }
+
+        ui.add_space(6.0);
+        ui.separator();
+        ui.add_space(6.0);
+    }
+
+    fn is_unresolved(&self, key: &str, paths: &[PathBuf]) -> bool {
+        let is_ignored = self.config.ignored.get(key).is_some_and(|p| p == paths);
+        // Conflicts won by the preferred provider stay quiet
+        let is_expected =
+            self.config.preferred.contains_key(key) && !self.unexpected_winners.contains(key);
+
+        !is_ignored && !is_expected
+    }
+
+    fn resolution_counts(&self) -> ResolutionCounts {
+        ResolutionCounts {
+            unresolved: self
+                .conflicts
+                .iter()
+                .filter(|(key, paths)| self.is_unresolved(key, paths))
+                .count(),
+            total: self.conflicts.len(),
+        }
+    }
+
+    /// Puts the up to date unresolved and resolved counts in the status line.
+    fn show_resolution_counts(&mut self) {
+        if self.has_scanned {
+            self.status = self.resolution_counts().describe();
+        }
+    }
+
+    /// The unresolved conflicts grouped, filtered and sorted as the results list shows them.
+    fn visible_results(&self, bioware_dir: &Path) -> Vec<ConflictSection> {
+        if !self.list_view.shows_unresolved() {
+            return Vec::new();
         }
 
         let mut filtered_conflicts: Vec<_> = self
             .conflicts
             .iter()
-            .filter_map(|(key, paths)| {
-                if self.config.ignored.get(key).map_or(false, |p| p == paths) {
-                    None
-                } else {
-                    Some((key.clone(), paths.clone()))
-                }
-            })
+            .filter(|(key, paths)| self.is_unresolved(key, paths))
+            .map(|(key, paths)| (key.clone(), paths.clone()))
             .collect();
 
-        if filtered_conflicts.is_empty() {
-            ui.centered_and_justified(|ui| {
-                ui.add(
-                    egui::Label::new(egui::RichText::new("All conflicts resolved!").size(24.0))
-                        .selectable(false),
-                );
-            });
-            return;
+        if self.only_actionable {
+            filtered_conflicts.retain(|(_, paths)| is_actionable(paths));
+        }
+        filtered_conflicts.retain(|(key, paths)| self.results_filter.matches(key, paths));
+
+        sort_entries(
+            &mut filtered_conflicts,
+            self.config.list_sort,
+            &self.config.ignored_at,
+        );
+
+        let (unexpected, others): (Vec<_>, Vec<_>) = filtered_conflicts
+            .into_iter()
+            .partition(|(key, _)| self.unexpected_winners.contains(key));
+
+        let (core_shadowing, others): (Vec<_>, Vec<_>) = others
+            .into_iter()
+            .partition(|(_, paths)| shadows_core(paths, bioware_dir));
+
+        let unpaired = unpaired_scripts(&self.conflicts, &self.config.script_pairs);
+        let (unpaired, others): (Vec<_>, Vec<_>) = others
+            .into_iter()
+            .partition(|(key, _)| unpaired.contains(key));
+
+        vec![
+            ConflictSection {
+                kind: SectionKind::CoreShadowing,
+                entries: core_shadowing,
+            },
+            ConflictSection {
+                kind: SectionKind::UnexpectedWinner,
+                entries: unexpected,
+            },
+            ConflictSection {
+                kind: SectionKind::UnpairedScript,
+                entries: unpaired,
+            },
+            ConflictSection {
+                kind: SectionKind::Other,
+                entries: others,
+            },
+        ]
+    }
+
+    /// The resolved conflicts filtered and sorted as the list shows them.
+    fn visible_ignored(&self) -> Vec<ConflictEntry> {
+        if !self.list_view.shows_resolved() {
+            return Vec::new();
+        }
+
+        let mut ignored_conflicts: Vec<_> = self
+            .config
+            .ignored
+            .iter()
+            .filter(|(key, paths)| self.results_filter.matches(key, paths))
+            .map(|(key, paths)| (key.clone(), paths.clone()))
+            .collect();
+        sort_entries(
+            &mut ignored_conflicts,
+            self.config.list_sort,
+            &self.config.ignored_at,
+        );
+
+        ignored_conflicts
+    }
+
+    fn conflict_title(&self, key: &str, paths: &[PathBuf]) -> egui::RichText {
+        let title = egui::RichText::new(format!(
+            "{} ({})",
+            self.display_rules.apply(key),
+            paths.len()
+        ))
+        .size(14.0);
+
+        if self.search_focus.as_deref() == Some(key) {
+            title.color(egui::Color32::from_rgb(255, 210, 90)).strong()
+        } else {
+            title
         }
+    }
+
+    /// Scrolls the first search match into view once after the search changes.
+    fn follow_search(&mut self, key: &str, header: &egui::Response) {
+        if self.scroll_to_focus && self.search_focus.as_deref() == Some(key) {
+            header.scroll_to_me(Some(egui::Align::TOP));
+            self.scroll_to_focus = false;
+        }
+    }
+
+    fn render_result_conflict(
+        &mut self,
+        ui: &mut egui::Ui,
+        key: &str,
+        paths: &[PathBuf],
+        bioware_dir: &Path,
+    ) {
+        let is_open = self.config.expanded_conflicts.contains(key);
+
+        let response = egui::CollapsingHeader::new(self.conflict_title(key, paths))
+            .id_salt(key)
+            .open(Some(is_open))
+            .show(ui, |ui| {
+                egui::Frame::new()
+                    .inner_margin(egui::Margin {
+                        left: 2,
+                        right: 16,
+                        top: 6,
+                        bottom: 8,
+                    })
+                    .show(ui, |ui| {
+                        // Ignore button
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().button_padding = egui::vec2(6.0, 4.0);
+
+                            let restype = ResourceType::of_name(resource_name(key));
+                            ui.label(egui::RichText::new(restype.label()).weak())
+                                .on_hover_text(format!(".{} resource", resource_type(key)));
+
+                            if ui
+                                .add_enabled(
+                                    !self.read_only,
+                                    egui::Button::new("Ignore").corner_radius(BUTTON_RADIUS),
+                                )
+                                .clicked()
+                            {
+                                self.pending_commands
+                                    .push(Command::IgnoreConflict(key.to_string(), paths.to_vec()));
+                            }
+
+                            if let Some(location) = winner_location(key, paths, &self.conflicts)
+                                && ui
+                                    .add(
+                                        egui::Button::new("Copy winner path")
+                                            .corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .on_hover_text(&location)
+                                    .clicked()
+                            {
+                                ui.ctx().copy_text(location);
+                                self.status = format!("Copied the winning path of {key}");
+                            }
+
+                            if ui
+                                .add(
+                                    egui::Button::new("Look up online")
+                                        .corner_radius(BUTTON_RADIUS),
+                                )
+                                .on_hover_text("Search for this resource in the browser")
+                                .clicked()
+                            {
+                                let url =
+                                    search_url(&self.config.lookup_url, resource_name(key));
+                                if let Err(err) = open_url(&url) {
+                                    self.error = Some(
+                                        AnyhowError::from(err)
+                                            .context(format!("Failed to open {url}")),
+                                    );
+                                }
+                            }
+
+                            if ui
+                                .add(egui::Button::new("History").corner_radius(BUTTON_RADIUS))
+                                .on_hover_text("Show how the providers changed across recent scans")
+                                .clicked()
+                            {
+                                self.history_key = Some(key.to_string());
+                            }
+
+                            if self.divergence.get(key) == Some(&Divergence::Identical) {
+                                let is_known = self.config.known_identical.contains_key(key);
+                                if ui
+                                    .add(
+                                        egui::Button::new("Trust as identical")
+                                            .selected(is_known)
+                                            .corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .on_hover_text(
+                                        "Skip hashing this conflict on later scans until a file changes size",
+                                    )
+                                    .clicked()
+                                {
+                                    if is_known {
+                                        self.config.known_identical.remove(key);
+                                    } else {
+                                        match KnownIdentical::record(
+                                            key,
+                                            &self.conflicts,
+                                            unix_timestamp(),
+                                        ) {
+                                            Ok(record) => {
+                                                self.config
+                                                    .known_identical
+                                                    .insert(key.to_string(), record);
+                                            }
+                                            Err(err) => self.error = Some(err),
+                                        }
+                                    }
+                                    let _ = self.config.save();
+                                }
+                            }
+
+                            if resource_type(key) == "tlk"
+                                && ui
+                                    .add_enabled(
+                                        self.tlk_receiver.is_none(),
+                                        egui::Button::new("Compare strings")
+                                            .corner_radius(BUTTON_RADIUS),
+                                    )
+                                    .on_hover_text("List the strings each source changes")
+                                    .clicked()
+                            {
+                                self.preview_talk_table(key, paths);
+                            }
+
+                            let mut note = self.config.notes.get(key).cloned().unwrap_or_default();
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut note)
+                                        .hint_text("Note (recorded in the decision log)")
+                                        .desired_width(280.0),
+                                )
+                                .changed()
+                            {
+                                if note.trim().is_empty() {
+                                    self.config.notes.remove(key);
+                                } else {
+                                    self.config.notes.insert(key.to_string(), note);
+                                }
+                            }
+                        });
+                        ui.add_space(4.0);
 
-        filtered_conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+                        ui.spacing_mut().item_spacing = egui::vec2(6.0, 8.0);
+                        ui.spacing_mut().button_padding = egui::vec2(2.0, 1.0);
 
-        egui::ScrollArea::both()
-            .id_salt("results_panel")
-            .auto_shrink(false)
-            .show(ui, |ui| {
-                for (key, paths) in filtered_conflicts {
-                    self.render_result_conflict(ui, &key, &paths, bioware_dir);
-                }
-            });
-    }
+                        let winner = explain_winner(paths);
+                        let rows = if self.config.collapse_layers {
+                            collapse_sources(paths, bioware_dir)
+                        } else {
+                            (0..paths.len()).map(SourceRow::Single).collect()
+                        };
 
-    fn render_result_conflict(
-        &mut self,
-        ui: &mut egui::Ui,
-        key: &str,
-        paths: &[PathBuf],
-        bioware_dir: &Path,
-    ) {
-        let is_open = self.expanded_conflicts.contains(key);
+                        for row in rows {
+                            let indices = match &row {
+                                SourceRow::Single(index) => vec![*index],
+                                SourceRow::Collapsed { folder, indices, .. } => {
+                                    let layer = (key.to_string(), folder.clone());
+                                    let expanded = self.expanded_layers.contains(&layer);
+                                    let has_winner = winner
+                                        .as_ref()
+                                        .is_some_and(|(winner, _)| indices.contains(winner));
 
-        let response = egui::CollapsingHeader::new(
-            egui::RichText::new(format!("{} ({})", key, paths.len())).size(14.0),
-        )
-        .open(Some(is_open))
-        .show(ui, |ui| {
-            egui::Frame::new()
-                .inner_margin(egui::Margin {
-                    left: 2,
-                    right: 16,
-                    top: 6,
-                    bottom: 8,
-                })
-                .show(ui, |ui| {
-                    // Ignore button
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().button_padding = egui::vec2(6.0, 4.0);
+                                    let mut text = row.summary(bioware_dir).unwrap_or_default();
+                                    if has_winner {
+                                        text.push_str(" ⭐");
+                                    }
+                                    if ui
+                                        .add(
+                                            egui::Button::new(
+                                                egui::RichText::new(format!(
+                                                    "{} {text}",
+                                                    if expanded { "⏷" } else { "⏵" }
+                                                ))
+                                                .size(13.0),
+                                            )
+                                            .frame(false),
+                                        )
+                                        .on_hover_text(if expanded {
+                                            "Collapse these sources"
+                                        } else {
+                                            "Show each source"
+                                        })
+                                        .clicked()
+                                        && !self.expanded_layers.remove(&layer)
+                                    {
+                                        self.expanded_layers.insert(layer);
+                                    }
 
-                        if ui
-                            .add(egui::Button::new("Ignore").corner_radius(BUTTON_RADIUS))
-                            .clicked()
-                        {
-                            self.pending_commands
-                                .push(Command::IgnoreConflict(key.to_string(), paths.to_vec()));
+                                    if !expanded {
+                                        continue;
+                                    }
+                                    indices.clone()
+                                }
+                            };
+
+                            for index in indices {
+                                let win_reason = winner
+                                    .as_ref()
+                                    .filter(|(winner, _)| *winner == index)
+                                    .map(|(_, reason)| reason);
+                                self.render_result_conflict_path(
+                                    ui,
+                                    &paths[index],
+                                    bioware_dir,
+                                    key,
+                                    win_reason,
+                                );
+                            }
                         }
                     });
-                    ui.add_space(4.0);
-
-                    ui.spacing_mut().item_spacing = egui::vec2(6.0, 8.0);
-                    ui.spacing_mut().button_padding = egui::vec2(2.0, 1.0);
+            });
 
-                    for path in paths {
-                        self.render_result_conflict_path(
-                            ui,
-                            path,
-                            bioware_dir,
-                            key,
-                            paths.last().is_some_and(|p| p == path),
-                        );
-                    }
-                });
-        });
+        self.follow_search(key, &response.header_response);
 
         if response.header_response.clicked() {
             if is_open {
-                self.expanded_conflicts.remove(key);
+                self.config.expanded_conflicts.remove(key);
             } else {
-                self.expanded_conflicts.insert(key.to_string());
+                self.config.expanded_conflicts.insert(key.to_string());
             }
+            let _ = self.config.save();
         }
     }
 
@@ -393,35 +4726,97 @@ impl App {
         path: &Path,
         bioware_dir: &Path,
         key: &str,
-        is_last: bool,
+        win_reason: Option<&WinReason>,
     ) {
         ui.horizontal(|ui| {
-            // Open in Explorer button
+            // Show in file manager button
             if ui
                 .add(
                     egui::Button::new(egui::RichText::new("📂").size(16.0))
                         .corner_radius(BUTTON_RADIUS),
                 )
-                .on_hover_text("Open location in Explorer")
+                .on_hover_text("Show in file manager")
                 .clicked()
             {
-                let _ = open_in_explorer(path);
+                let _ = reveal_in_file_manager(path);
             }
 
-            // Delete button (only for non-ERF files)
-            let is_erf = path
-                .extension()
-                .map_or(false, |ext| ext.eq_ignore_ascii_case("erf"));
+            // Delete button, removing just the resource for ERF files
+            let is_erf = SourceKind::of(path) == SourceKind::Archive;
             if ui
                 .add_enabled(
-                    !is_erf,
+                    !self.read_only,
                     egui::Button::new(egui::RichText::new("❌").size(16.0))
                         .corner_radius(BUTTON_RADIUS),
                 )
-                .on_hover_text("Delete file")
+                .on_hover_text(if is_erf {
+                    "Remove from archive, backing the archive up first"
+                } else {
+                    "Move file to the recycle bin"
+                })
+                .clicked()
+            {
+                if is_erf {
+                    self.pending_commands.push(Command::RemoveFromArchive(
+                        key.to_string(),
+                        path.to_path_buf(),
+                    ));
+                } else {
+                    self.request_delete(key, path);
+                }
+            }
+
+            // Inspect button (only for ERF files)
+            if ui
+                .add_enabled(
+                    is_erf,
+                    egui::Button::new(egui::RichText::new("ℹ").size(16.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Inspect archive")
+                .clicked()
+            {
+                self.inspect_archive(path);
+            }
+
+            // Preview button
+            if ui
+                .add_enabled(
+                    self.preview_receiver.is_none(),
+                    egui::Button::new(egui::RichText::new("👁").size(16.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Preview the content of this source")
+                .clicked()
+            {
+                self.start_preview(key, path);
+            }
+
+            // Diff button
+            if ui
+                .add_enabled(
+                    self.diff_receiver.is_none(),
+                    egui::Button::new(egui::RichText::new("⇄").size(16.0))
+                        .selected(self.is_diff_pick(key, path))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Pick two sources to compare them")
+                .clicked()
+            {
+                self.toggle_diff_pick(key, path);
+            }
+
+            // Disable button (only for non-ERF files)
+            if ui
+                .add_enabled(
+                    !is_erf && !self.read_only,
+                    egui::Button::new(egui::RichText::new("⏸").size(16.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Disable file (rename to .disabled)")
                 .clicked()
             {
-                self.pending_commands.push(Command::DeleteConflictFile(
+                self.pending_commands.push(Command::DisableConflictFile(
                     key.to_string(),
                     path.to_path_buf(),
                 ));
@@ -432,64 +4827,250 @@ impl App {
                 .display()
                 .to_string();
 
-            let text = if is_last {
-                format!("{} ⭐", display_path)
+            let is_preferred = self.config.preferred.get(key).is_some_and(|p| p == path);
+
+            // Keep button
+            let has_other_loose = self.conflicts.get(key).is_some_and(|paths| {
+                paths
+                    .iter()
+                    .any(|p| p != path && SourceKind::of(p) == SourceKind::Loose)
+            });
+            if ui
+                .add_enabled(
+                    has_other_loose && !self.read_only,
+                    egui::Button::new(egui::RichText::new("✔").size(16.0))
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text("Keep this file and delete the other loose files")
+                .clicked()
+            {
+                self.request_keep_only(key, path);
+            }
+
+            // Prefer button
+            if ui
+                .add(
+                    egui::Button::new(egui::RichText::new("📌").size(16.0))
+                        .selected(is_preferred)
+                        .corner_radius(BUTTON_RADIUS),
+                )
+                .on_hover_text(if is_preferred {
+                    "Clear the preferred provider"
+                } else {
+                    "Prefer this file (only alert when another file wins)"
+                })
+                .clicked()
+            {
+                self.pending_commands.push(if is_preferred {
+                    Command::ClearPreferredProvider(key.to_string())
+                } else {
+                    Command::SetPreferredProvider(key.to_string(), path.to_path_buf())
+                });
+            }
+
+            let mut text = display_path;
+            if win_reason.is_some() {
+                text.push_str(" ⭐");
+            }
+            if is_preferred {
+                text.push_str(" 📌");
+            }
+
+            let label =
+                ui.add(egui::Label::new(egui::RichText::new(text).size(13.0)).selectable(false));
+            if label.hovered() {
+                let mut hover = Vec::new();
+                if let Some(reason) = win_reason {
+                    hover.push(reason.describe());
+                }
+                if is_erf {
+                    let name = self.conflicts.entry_name(key, path);
+                    let details = self
+                        .archive_details
+                        .entry((path.to_path_buf(), name.to_string()))
+                        .or_insert_with(|| archive_entry_details(path, name));
+                    hover.extend(details.clone());
+                }
+                if !hover.is_empty() {
+                    label.on_hover_text(hover.join("\n"));
+                }
+            }
+
+            if let Some(profile) = self.config.generated_files.get(path) {
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(format!("[generated by profile {profile}]"))
+                            .color(egui::Color32::GRAY)
+                            .size(12.0),
+                    )
+                    .selectable(false),
+                );
+            }
+
+            let package = override_package(path, bioware_dir).map(|p| format!("{p} override"));
+            if let Some(label) = self.addin_label(path, bioware_dir).or(package) {
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(format!("[{label}]"))
+                            .color(egui::Color32::GRAY)
+                            .size(12.0),
+                    )
+                    .selectable(false),
+                );
+            }
+
+            if let Some(info) = self.source_info.get(&(key.to_string(), path.to_path_buf())) {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(
+                        egui::Label::new(
+                            egui::RichText::new(format_source_info(info))
+                                .color(egui::Color32::GRAY)
+                                .size(12.0),
+                        )
+                        .selectable(false),
+                    );
+                });
+            }
+        });
+    }
+
+    fn list_controls(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
+        ui.horizontal(|ui| {
+            // Segmented Unresolved / Resolved / All control
+            ui.spacing_mut().item_spacing.x = 0.0;
+            for view in ListView::ALL {
+                let count = match view {
+                    ListView::Unresolved => Some(self.resolution_counts().unresolved),
+                    ListView::Resolved => Some(self.config.ignored.len()),
+                    ListView::All => None,
+                };
+                let label = match count {
+                    Some(count) => format!("{} ({count})", view.label()),
+                    None => view.label().to_string(),
+                };
+                ui.selectable_value(&mut self.list_view, view, label);
+            }
+            ui.spacing_mut().item_spacing.x = 8.0;
+
+            ui.add_space(12.0);
+            let search = ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.results_filter.query)
+                        .hint_text("Search conflicts...")
+                        .desired_width(240.0),
+                )
+                .on_hover_text(format!(
+                    "Search conflicts ({})",
+                    ui.ctx().format_shortcut(&SEARCH_SHORTCUT)
+                ));
+            if mem::take(&mut self.focus_search) {
+                search.request_focus();
+            }
+            if search.changed() {
+                self.scroll_to_focus = true;
+            }
+            if !self.results_filter.query.is_empty()
+                && ui
+                    .add(egui::Button::new("✖").corner_radius(BUTTON_RADIUS))
+                    .on_hover_text("Clear the search")
+                    .clicked()
+            {
+                self.results_filter.query.clear();
+                self.search_focus = None;
+            }
+
+            let categories = &mut self.results_filter.categories;
+            let selected = if categories.is_empty() {
+                "All types".to_string()
+            } else if categories.len() == 1 {
+                categories.iter().map(|restype| restype.label()).collect()
             } else {
-                display_path
+                format!("{} types", categories.len())
             };
+            egui::ComboBox::from_id_salt("type_filter")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for restype in ResourceType::ALL {
+                        let mut shown = categories.contains(&restype);
+                        if ui.checkbox(&mut shown, restype.label()).changed() {
+                            if shown {
+                                categories.insert(restype);
+                            } else {
+                                categories.remove(&restype);
+                            }
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Only show conflicts over these types of resources");
+
+            egui::ComboBox::from_id_salt("list_sort")
+                .selected_text(self.config.list_sort.label())
+                .show_ui(ui, |ui| {
+                    for mode in SortMode::ALL {
+                        if ui
+                            .selectable_label(self.config.list_sort == mode, mode.label())
+                            .clicked()
+                        {
+                            self.set_sort(mode);
+                        }
+                    }
+                });
 
-            ui.add(egui::Label::new(egui::RichText::new(text).size(13.0)).selectable(false));
+            self.profile_selector(ui);
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(
+                        !self.config.decisions.is_empty(),
+                        egui::Button::new("Export decision log").corner_radius(BUTTON_RADIUS),
+                    )
+                    .on_hover_text("Write a dated log of every ignore, delete and disable")
+                    .clicked()
+                {
+                    self.export_decision_log(bioware_dir);
+                }
+            });
         });
     }
 
-    fn ignored_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
-        let mut ignored_conflicts: Vec<_> = self
-            .config
-            .ignored
-            .iter()
-            .map(|(key, paths)| (key.clone(), paths.clone()))
-            .collect();
-        ignored_conflicts.sort_by(|a, b| a.0.cmp(&b.0));
-
+    fn disabled_panel(&mut self, ui: &mut egui::Ui, bioware_dir: &Path) {
         egui::CollapsingHeader::new(
-            egui::RichText::new(format!(
-                "Resolved conflicts ({})",
-                self.config.ignored.len()
-            ))
-            .size(18.0),
+            egui::RichText::new(format!("Disabled files ({})", self.disabled_files.len()))
+                .size(18.0),
         )
         .show_unindented(ui, |ui| {
             egui::ScrollArea::both()
-                .id_salt("ignored_panel")
-                .min_scrolled_height(200.0)
-                .auto_shrink(false)
+                .id_salt("disabled_panel")
+                .max_height(200.0)
+                .auto_shrink([false, true])
                 .show(ui, |ui| {
-                    if ignored_conflicts.is_empty() {
-                        ui.centered_and_justified(|ui| {
-                            ui.add(
-                                egui::Label::new(
-                                    egui::RichText::new("No ignored conflicts")
-                                        .color(egui::Color32::DARK_GRAY)
-                                        .size(16.0),
+                    ui.spacing_mut().button_padding = egui::vec2(6.0, 2.0);
+
+                    for (key, path) in self.disabled_files.clone() {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    !self.read_only,
+                                    egui::Button::new("Enable").corner_radius(BUTTON_RADIUS),
                                 )
-                                .selectable(false),
+                                .on_hover_text("Restore the file under its original name")
+                                .clicked()
+                            {
+                                self.pending_commands
+                                    .push(Command::EnableFile(key, path.clone()));
+                            }
+
+                            let display_path = diff_paths(&path, bioware_dir)
+                                .unwrap_or_else(|| path.clone())
+                                .display()
+                                .to_string();
+                            ui.add(
+                                egui::Label::new(egui::RichText::new(display_path).size(12.0))
+                                    .selectable(false),
                             );
                         });
-                        return;
                     }
-
-                    egui::Frame::new()
-                        .inner_margin(egui::Margin {
-                            left: 12,
-                            right: 8,
-                            top: 4,
-                            bottom: 8,
-                        })
-                        .show(ui, |ui| {
-                            for (key, paths) in ignored_conflicts {
-                                self.render_ignored_conflict(ui, &key, &paths, bioware_dir);
-                            }
-                        });
                 });
         });
     }
@@ -501,43 +5082,48 @@ impl App {
         paths: &[PathBuf],
         bioware_dir: &Path,
     ) {
-        egui::CollapsingHeader::new(
-            egui::RichText::new(format!("{} ({})", key, paths.len())).size(14.0),
-        )
-        .show(ui, |ui| {
-            egui::Frame::new()
-                .inner_margin(egui::Margin {
-                    left: 2,
-                    right: 16,
-                    top: 6,
-                    bottom: 8,
-                })
-                .show(ui, |ui| {
-                    // Restore button
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().button_padding = egui::vec2(6.0, 4.0);
+        let response = egui::CollapsingHeader::new(self.conflict_title(key, paths))
+            .id_salt(key)
+            .show(ui, |ui| {
+                egui::Frame::new()
+                    .inner_margin(egui::Margin {
+                        left: 2,
+                        right: 16,
+                        top: 6,
+                        bottom: 8,
+                    })
+                    .show(ui, |ui| {
+                        // Restore button
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().button_padding = egui::vec2(6.0, 4.0);
 
-                        if ui
-                            .add(egui::Button::new("Forget").corner_radius(BUTTON_RADIUS))
-                            .clicked()
-                        {
-                            self.pending_commands
-                                .push(Command::UnignoreConflict(key.to_string()));
+                            if ui
+                                .add_enabled(
+                                    !self.read_only,
+                                    egui::Button::new("Forget").corner_radius(BUTTON_RADIUS),
+                                )
+                                .clicked()
+                            {
+                                self.pending_commands
+                                    .push(Command::UnignoreConflict(key.to_string()));
+                            }
+                        });
+
+                        ui.spacing_mut().item_spacing = egui::vec2(10.0, 4.0);
+
+                        let winner = resolve_winner(paths);
+                        for (index, path) in paths.iter().enumerate() {
+                            self.render_ignored_conflict_path(
+                                ui,
+                                path,
+                                bioware_dir,
+                                winner == Some(index),
+                            );
                         }
                     });
+            });
 
-                    ui.spacing_mut().item_spacing = egui::vec2(10.0, 4.0);
-
-                    for path in paths {
-                        self.render_ignored_conflict_path(
-                            ui,
-                            path,
-                            bioware_dir,
-                            paths.last().is_some_and(|p| p == path),
-                        );
-                    }
-                });
-        });
+        self.follow_search(key, &response.header_response);
     }
 
     fn render_ignored_conflict_path(
@@ -545,7 +5131,7 @@ impl App {
         ui: &mut egui::Ui,
         path: &Path,
         bioware_dir: &Path,
-        is_last: bool,
+        is_winner: bool,
     ) {
         ui.horizontal(|ui| {
             let display_path = diff_paths(path, bioware_dir)
@@ -553,7 +5139,7 @@ impl App {
                 .display()
                 .to_string();
 
-            let text = if is_last {
+            let text = if is_winner {
                 format!("{} ⭐", display_path)
             } else {
                 display_path
@@ -567,33 +5153,359 @@ impl App {
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.process_scan_results();
+        self.process_verify_results();
+        self.process_compare_results();
+        self.process_queue_results();
+        self.process_preview_results();
+        self.process_diff_results();
+        self.process_tlk_results();
+
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.remember_window(ctx);
+        }
+
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::P,
+            ))
+        }) {
+            self.toggle_palette();
+        }
 
         egui::CentralPanel::default()
             .frame(egui::Frame::central_panel(&ctx.style()).inner_margin(12.0))
-            .show(ctx, |ui| match get_bioware_dir() {
+            .show(ctx, |ui| match self.scan_root() {
                 Some(bioware_dir) if bioware_dir.exists() => {
+                    if mem::take(&mut self.scan_on_start) {
+                        self.start_scan(&bioware_dir);
+                    }
+
+                    self.handle_shortcuts(ctx, &bioware_dir);
                     self.main_ui(ui, &bioware_dir);
+                    self.show_verify_report(ctx, &bioware_dir);
+                    self.show_archive_inspector(ctx, &bioware_dir);
+                    self.show_compare_installs(ctx);
+                    self.show_scan_queue(ctx, &bioware_dir);
+                    self.show_delete_confirmation(ctx, &bioware_dir);
+                    self.show_keep_only_confirmation(ctx, &bioware_dir);
+                    self.show_forget_all_confirmation(ctx);
+                    self.show_provider_history(ctx, &bioware_dir);
+                    self.show_tlk_preview(ctx, &bioware_dir);
+                    self.show_resource_preview(ctx, &bioware_dir);
+                    self.show_diff_view(ctx, &bioware_dir);
+                    self.show_backup_sessions(ctx, &bioware_dir);
+                    self.show_resource_finder(ctx, &bioware_dir);
+                    self.show_plan_review(ctx, &bioware_dir);
+                    self.show_command_palette(ctx, &bioware_dir);
                 }
-                _ => {
-                    self.error = anyhow!(
-                        "'Documents/BioWare/Dragon Age' folder is missing, make sure it exists."
-                    )
-                    .into();
-                }
+                _ => self.missing_dir_state(ui),
             });
 
+        if self.config.stage_actions {
+            self.stage_commands();
+        }
         if let Err(e) = self.handle_commands() {
             self.error = Some(e);
         }
+        self.sync_view_state();
 
+        self.show_toasts(ctx);
         self.show_error_dialog(ctx);
     }
 }
 
-fn get_bioware_dir() -> Option<PathBuf> {
-    UserDirs::new()?
-        .document_dir()?
-        .join("BioWare/Dragon Age")
-        .canonicalize()
-        .ok()
+/// Compares the strings of every source of a talk table with the winner's,
+/// each source given with its entry name.
+fn load_tlk_preview(
+    key: String,
+    sources: Vec<(PathBuf, String)>,
+    winner: usize,
+) -> AnyhowResult<TlkPreview> {
+    let read = |(path, name): &(PathBuf, String)| -> AnyhowResult<TalkTable> {
+        Ok(TalkTable::parse(
+            &read_source(path, name)?,
+            MAX_TLK_ENTRIES,
+        )?)
+    };
+
+    let winner_table = read(&sources[winner]).with_context(|| format!("Failed to read {key}"))?;
+
+    let mut truncated = winner_table.truncated;
+    let comparisons = sources
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != winner)
+        .map(|(_, source)| {
+            let diff = read(source)
+                .map(|table| {
+                    truncated |= table.truncated;
+                    diff_tables(&table, &winner_table)
+                })
+                .map_err(|err| format!("{err:#}"));
+            (source.0.clone(), diff)
+        })
+        .collect();
+
+    Ok(TlkPreview {
+        key,
+        winner: sources[winner].0.clone(),
+        comparisons,
+        truncated,
+    })
+}
+
+/// Runs `run` on every item, returning the errors of those that failed.
+fn run_each<T>(
+    items: impl IntoIterator<Item = T>,
+    mut run: impl FnMut(T) -> AnyhowResult<()>,
+) -> Vec<AnyhowError> {
+    items
+        .into_iter()
+        .filter_map(|item| run(item).err())
+        .collect()
+}
+
+/// Reports failures together, a single one as is.
+fn combine_failures(mut failures: Vec<AnyhowError>) -> AnyhowResult<()> {
+    match failures.len() {
+        0 => Ok(()),
+        1 => Err(failures.remove(0)),
+        count => {
+            let lines: Vec<_> = failures.iter().map(|err| format!("{err:#}")).collect();
+            Err(anyhow!("{count} operations failed:\n{}", lines.join("\n")))
+        }
+    }
+}
+
+/// Describes how `name` is stored in the archive at `path`.
+fn archive_entry_details(path: &Path, name: &str) -> Option<String> {
+    let erf = ErfFile::open(path).ok()?;
+    let entry = erf.entry(name)?;
+
+    Some(if entry.is_compressed() {
+        format!(
+            "Packed to {} of {} ({})",
+            format_size(entry.packed_length.into()),
+            format_size(entry.length.into()),
+            entry
+                .compression_ratio()
+                .map_or_else(|| "-".to_string(), format_ratio)
+        )
+    } else {
+        format!("Stored uncompressed, {}", format_size(entry.length.into()))
+    })
+}
+
+/// Size and date shown next to a source, the resource's own length included
+/// for packed sources.
+fn format_source_info(info: &SourceInfo) -> String {
+    let mut parts = Vec::new();
+    if let Some(length) = info.resource_length {
+        parts.push(format!("{} in archive", format_size(length.into())));
+    }
+    parts.push(format_size(info.size));
+    parts.extend(info.modified.map(format_date));
+    parts.join(" · ")
+}
+
+fn format_ratio(ratio: f32) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        fixtures::{tlk, write},
+        plan::PlannedOperation,
+    };
+
+    fn every_command() -> Vec<Command> {
+        let key = || "armor.uti".to_string();
+        let path = || PathBuf::from("override/mod_a/armor.uti");
+        vec![
+            Command::IgnoreConflict(key(), vec![path()]),
+            Command::UnignoreConflict(key()),
+            Command::UnignoreAll,
+            Command::DeleteConflictFile(key(), path()),
+            Command::RemoveFromArchive(key(), PathBuf::from("data/patch.erf")),
+            Command::DisableConflictFile(key(), path()),
+            Command::DisableDuplicateInstall(vec![(key(), path())]),
+            Command::EnableFile(key(), path()),
+            Command::SetPreferredProvider(key(), path()),
+            Command::ClearPreferredProvider(key()),
+        ]
+    }
+
+    #[test]
+    fn applying_a_plan_skips_blocked_deletions() {
+        let operation = |path: &str, action| PlannedOperation {
+            key: "a".to_string(),
+            path: PathBuf::from(path),
+            action,
+        };
+        let plan = ResolutionPlan {
+            created_at: 0,
+            operations: vec![
+                operation("mod_a/a.uti", PlanAction::Disable),
+                operation("mod_b/a.uti", PlanAction::Delete),
+                operation("mod_c/a.dll", PlanAction::Delete),
+            ],
+        };
+
+        let (commands, blocked) = Command::from_plan(plan, &DeleteRules::default());
+
+        let described: Vec<_> = commands
+            .iter()
+            .map(|command| command.describe(Path::new("")))
+            .collect();
+        assert_eq!(
+            described,
+            ["Disable mod_a/a.uti (a)", "Delete mod_b/a.uti (a)"]
+        );
+        assert_eq!(blocked, 1);
+    }
+
+    #[test]
+    fn a_missing_file_doesnt_stop_the_rest_of_a_plan() {
+        let dir = TempDir::new().unwrap();
+        let kept = [
+            write(dir.path(), "mod_a/a.uti", b"a"),
+            write(dir.path(), "mod_c/a.uti", b"c"),
+        ];
+        let missing = dir.path().join("mod_b/a.uti");
+        let plan = ResolutionPlan {
+            created_at: 0,
+            operations: [&kept[0], &missing, &kept[1]]
+                .into_iter()
+                .map(|path| PlannedOperation {
+                    key: "a.uti".to_string(),
+                    path: path.clone(),
+                    action: PlanAction::Disable,
+                })
+                .collect(),
+        };
+        assert_eq!(plan.missing_files(Path::exists)[0].path, missing);
+
+        let (commands, _) = Command::from_plan(plan, &DeleteRules::default());
+        let failures = run_each(commands, |command| match command {
+            Command::DisableConflictFile(_, path) => disable(&path)
+                .map(drop)
+                .context(format!("Failed to disable {}", path.display())),
+            command => panic!("unexpected {command:?}"),
+        });
+
+        assert_eq!(failures.len(), 1);
+        for path in &kept {
+            assert!(!path.exists());
+            assert!(path.with_extension("uti.disabled").exists());
+        }
+        let err = combine_failures(failures).unwrap_err();
+        assert!(format!("{err:#}").contains("mod_b"));
+    }
+
+    #[test]
+    fn ignoring_again_undoes_to_the_earlier_resolution() {
+        let mut config = AppConfig::default();
+        assert!(matches!(
+            UndoAction::before_ignoring(&config, "a.uti"),
+            UndoAction::Unignore(key) if key == "a.uti"
+        ));
+
+        let paths = vec![PathBuf::from("override/mod_a/a.uti")];
+        config.ignored.insert("a.uti".to_string(), paths.clone());
+        config.ignored_at.insert("a.uti".to_string(), 1_700_000_000);
+        match UndoAction::before_ignoring(&config, "a.uti") {
+            UndoAction::Reignore {
+                key,
+                paths: previous,
+                ignored_at,
+            } => {
+                assert_eq!(key, "a.uti");
+                assert_eq!(previous, paths);
+                assert_eq!(ignored_at, Some(1_700_000_000));
+            }
+            other => panic!("expected Reignore, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn talk_tables_are_compared_with_the_winner() {
+        let dir = TempDir::new().unwrap();
+        let sources = vec![
+            (
+                write(dir.path(), "mod_a/core_en-us.tlk", &tlk(&[(1, "Old")])),
+                String::new(),
+            ),
+            (
+                write(dir.path(), "mod_b/core_en-us.tlk", &tlk(&[(1, "New")])),
+                String::new(),
+            ),
+            (
+                write(dir.path(), "mod_c/core_en-us.tlk", b"junk"),
+                String::new(),
+            ),
+        ];
+
+        let preview = load_tlk_preview("core_en-us.tlk".to_string(), sources.clone(), 1).unwrap();
+
+        assert_eq!(preview.winner, sources[1].0);
+        assert!(!preview.truncated);
+        let [(first, Ok(diffs)), (third, Err(_))] = preview.comparisons.as_slice() else {
+            panic!("unexpected comparisons");
+        };
+        assert_eq!((first, third), (&sources[0].0, &sources[2].0));
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].first.as_deref(), Some("Old"));
+        assert_eq!(diffs[0].second.as_deref(), Some("New"));
+
+        let Err(err) = load_tlk_preview("core_en-us.tlk".to_string(), sources, 2) else {
+            panic!("a junk winner was read");
+        };
+        assert!(err.to_string().contains("Failed to read core_en-us.tlk"));
+    }
+
+    #[test]
+    fn several_failures_are_reported_together() {
+        assert!(combine_failures(Vec::new()).is_ok());
+
+        let err = combine_failures(vec![anyhow!("first")]).unwrap_err();
+        assert_eq!(err.to_string(), "first");
+
+        let err = combine_failures(vec![anyhow!("first"), anyhow!("second")]).unwrap_err();
+        assert_eq!(err.to_string(), "2 operations failed:\nfirst\nsecond");
+    }
+
+    #[test]
+    fn read_only_mode_refuses_every_mutating_command() {
+        for command in every_command() {
+            assert!(command.is_mutating(), "{command:?}");
+            let err = command.check_allowed(true, false).unwrap_err();
+            assert!(err.to_string().contains("read-only"), "{command:?}");
+            assert!(command.check_allowed(false, false).is_ok(), "{command:?}");
+        }
+    }
+
+    #[test]
+    fn scanned_folders_refuse_only_resolution_changes() {
+        let refused: Vec<_> = every_command()
+            .into_iter()
+            .filter(|command| command.check_allowed(false, true).is_err())
+            .map(|command| command.describe(Path::new("")))
+            .collect();
+
+        assert_eq!(
+            refused,
+            [
+                "Ignore armor.uti",
+                "Restore armor.uti",
+                "Forget every resolved conflict",
+                "Prefer override/mod_a/armor.uti for armor.uti",
+                "Clear the preferred source of armor.uti",
+            ]
+        );
+    }
 }
@@ -0,0 +1,156 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result as AnyhowResult};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{backup_file, copy_with_metadata};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// A file copied into a backup session before it was changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub original: PathBuf,
+    pub backup: PathBuf,
+}
+
+/// The backups taken during one run of the app, in `backups/<timestamp>/`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupSession {
+    pub timestamp: u64,
+    pub entries: Vec<BackupEntry>,
+}
+
+/// How many files a restore copied back, and which were left alone because
+/// something already exists at their original path.
+#[derive(Debug, Default)]
+pub struct RestoreOutcome {
+    pub restored: usize,
+    pub skipped: Vec<PathBuf>,
+}
+
+impl BackupSession {
+    fn dir(backup_root: &Path, timestamp: u64) -> PathBuf {
+        backup_root.join(timestamp.to_string())
+    }
+
+    fn load(dir: &Path) -> AnyhowResult<Self> {
+        let json = fs::read_to_string(dir.join(MANIFEST_FILE))
+            .with_context(|| format!("Failed to read the backup manifest in {}", dir.display()))?;
+        serde_json::from_str(&json).context("Failed to parse the backup manifest")
+    }
+
+    fn save(&self, dir: &Path) -> AnyhowResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .context("Failed to serialize the backup manifest")?;
+        fs::write(dir.join(MANIFEST_FILE), json).context("Failed to write the backup manifest")
+    }
+
+    /// Copies every backup back to where it came from, never overwriting a file
+    /// that exists again.
+    pub fn restore(&self) -> AnyhowResult<RestoreOutcome> {
+        let mut outcome = RestoreOutcome::default();
+
+        for entry in &self.entries {
+            if entry.original.exists() {
+                outcome.skipped.push(entry.original.clone());
+                continue;
+            }
+            if let Some(parent) = entry.original.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            copy_with_metadata(&entry.backup, &entry.original)
+                .with_context(|| format!("Failed to restore {}", entry.original.display()))?;
+            outcome.restored += 1;
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Copies `path` into the backup session started at `session`, keeping its
+/// path relative to `root`, and records it in the session's manifest.
+pub fn back_up(
+    path: &Path,
+    root: &Path,
+    backup_root: &Path,
+    session: u64,
+) -> AnyhowResult<PathBuf> {
+    let dir = BackupSession::dir(backup_root, session);
+    let backup = backup_file(path, root, &dir)
+        .with_context(|| format!("Failed to back up {}", path.display()))?;
+
+    let mut manifest = BackupSession::load(&dir).unwrap_or_else(|_| BackupSession {
+        timestamp: session,
+        entries: Vec::new(),
+    });
+    manifest.entries.push(BackupEntry {
+        original: path.to_path_buf(),
+        backup: backup.clone(),
+    });
+    manifest.save(&dir)?;
+    Ok(backup)
+}
+
+/// Every backup session with a readable manifest, newest first.
+pub fn list_sessions(backup_root: &Path) -> Vec<BackupSession> {
+    let Ok(dirs) = fs::read_dir(backup_root) else {
+        return Vec::new();
+    };
+
+    let mut sessions: Vec<_> = dirs
+        .filter_map(Result::ok)
+        .filter_map(|entry| BackupSession::load(&entry.path()).ok())
+        .collect();
+    sessions.sort_by_key(|session| Reverse(session.timestamp));
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::write;
+
+    #[test]
+    fn deleted_file_keeps_a_copy_in_the_backup_folder() {
+        let game = TempDir::new().unwrap();
+        let backups = TempDir::new().unwrap();
+        let path = write(
+            game.path(),
+            "packages/core/override/moda/old_patch.gda",
+            b"rows",
+        );
+
+        let backup = back_up(&path, game.path(), backups.path(), 42).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            backup,
+            backups
+                .path()
+                .join("42/packages/core/override/moda/old_patch.gda")
+        );
+        assert_eq!(fs::read(&backup).unwrap(), b"rows");
+
+        let sessions = list_sessions(backups.path());
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].entries,
+            [BackupEntry {
+                original: path.clone(),
+                backup,
+            }]
+        );
+
+        let outcome = sessions[0].restore().unwrap();
+        assert_eq!(outcome.restored, 1);
+        assert_eq!(fs::read(&path).unwrap(), b"rows");
+    }
+}
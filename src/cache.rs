@@ -0,0 +1,83 @@
+use std::{
+    collections::HashMap,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, Result as AnyhowResult};
+use serde::{Deserialize, Serialize};
+
+pub const TOC_CACHE_FILE: &str = "toc_cache.json";
+
+/// The resources of one archive as of its size and modification time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedToc {
+    size: u64,
+    /// Nanoseconds since the unix epoch.
+    modified: u64,
+    /// Resource names with their unpacked lengths.
+    pub resources: Vec<(String, u32)>,
+}
+
+impl CachedToc {
+    pub fn new(metadata: &Metadata, resources: Vec<(String, u32)>) -> Option<Self> {
+        Some(Self {
+            size: metadata.len(),
+            modified: modified_nanos(metadata)?,
+            resources,
+        })
+    }
+
+    fn matches(&self, metadata: &Metadata) -> bool {
+        self.size == metadata.len() && Some(self.modified) == modified_nanos(metadata)
+    }
+}
+
+/// Parsed archive tables of contents from earlier scans, so archives that
+/// haven't changed aren't parsed again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TocCache {
+    archives: HashMap<PathBuf, CachedToc>,
+}
+
+impl TocCache {
+    /// Loads the cache, starting empty when it is missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> AnyhowResult<()> {
+        let json = serde_json::to_string(self).context("Failed to serialize the TOC cache")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create the cache directory")?;
+        }
+        fs::write(path, json).context("Failed to write the TOC cache")
+    }
+
+    /// The cached resources of `path`, unless the archive changed since.
+    pub fn get(&self, path: &Path, metadata: &Metadata) -> Option<&[(String, u32)]> {
+        self.archives
+            .get(path)
+            .filter(|toc| toc.matches(metadata))
+            .map(|toc| toc.resources.as_slice())
+    }
+
+    pub fn insert(&mut self, path: PathBuf, toc: CachedToc) {
+        self.archives.insert(path, toc);
+    }
+
+    /// Drops archives under `root` that weren't found by the last scan of it.
+    pub fn prune(&mut self, root: &Path, found: &[PathBuf]) {
+        self.archives
+            .retain(|path, _| !path.starts_with(root) || found.binary_search(path).is_ok());
+    }
+}
+
+fn modified_nanos(metadata: &Metadata) -> Option<u64> {
+    let since = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since.as_nanos()).ok()
+}
@@ -0,0 +1,104 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use crate::{
+    config::AppConfig,
+    loadorder::unexpected_winners,
+    scanner::scan_for_conflicts,
+    utils::attach_parent_console,
+    view::{ConflictEntry, ConflictSection, SectionKind, render_json, render_report},
+};
+
+/// Exit code when the scan found unresolved conflicts.
+const EXIT_CONFLICTS: u8 = 1;
+/// Exit code when the scan couldn't run.
+const EXIT_ERROR: u8 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A scan run from the command line with `--scan`, without opening a window.
+#[derive(Debug, Default)]
+pub struct HeadlessScan {
+    /// The folder to scan, the configured BioWare folder when not given.
+    pub dir: Option<PathBuf>,
+    pub format: OutputFormat,
+}
+
+/// Scans, prints the unresolved conflicts to stdout and returns the exit code,
+/// which is non-zero when any are found so scripts can gate on it.
+pub fn run_headless(scan: HeadlessScan) -> ExitCode {
+    attach_parent_console();
+
+    let (config, load_error) = AppConfig::load();
+    if let Some(err) = load_error {
+        eprintln!("Warning: Could not load saved config. Using default. Details: {err:#}");
+    }
+    let Some(bioware_dir) = scan.dir.or_else(|| config.bioware_dir()) else {
+        eprintln!("Error: Could not find the BioWare folder, pass it after --scan");
+        return ExitCode::from(EXIT_ERROR);
+    };
+
+    let report = match scan_for_conflicts(&bioware_dir, &config.scan_options(), None, None) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+
+    for warning in &report.warnings {
+        eprintln!("Warning: {}: {}", warning.path.display(), warning.message);
+    }
+
+    // Same as the window: ignored and expected winners don't count
+    let unexpected = unexpected_winners(&report.conflicts, &config.preferred);
+    let mut entries: Vec<ConflictEntry> = report
+        .conflicts
+        .iter()
+        .filter(|(key, paths)| {
+            let is_ignored = config.ignored.get(*key) == Some(*paths);
+            let is_expected = config.preferred.contains_key(*key) && !unexpected.contains(*key);
+            !is_ignored && !is_expected
+        })
+        .map(|(key, paths)| (key.clone(), paths.clone()))
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let found = !entries.is_empty();
+    let sections = [ConflictSection {
+        kind: SectionKind::Other,
+        entries,
+    }];
+
+    let output = match scan.format {
+        OutputFormat::Text => render_report(&sections, &bioware_dir),
+        OutputFormat::Json => match render_json(&sections, &report.conflicts, &bioware_dir) {
+            Ok(json) => json,
+            Err(err) => {
+                eprintln!("Error: {err}");
+                return ExitCode::from(EXIT_ERROR);
+            }
+        },
+    };
+    println!("{output}");
+
+    if !found {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_CONFLICTS)
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use crate::scanner::{Conflicts, ScanError, ScanOptions, scan_for_conflicts};
+
+/// Conflict keys of two installs split by where they occur, each list sorted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstallDiff {
+    pub only_first: Vec<String>,
+    pub only_second: Vec<String>,
+    pub both: Vec<String>,
+}
+
+pub fn diff_conflicts(first: &Conflicts, second: &Conflicts) -> InstallDiff {
+    let mut diff = InstallDiff::default();
+
+    for key in first.keys() {
+        if second.contains_key(key) {
+            diff.both.push(key.clone());
+        } else {
+            diff.only_first.push(key.clone());
+        }
+    }
+    diff.only_second = second
+        .keys()
+        .filter(|key| !first.contains_key(*key))
+        .cloned()
+        .collect();
+
+    diff.only_first.sort();
+    diff.only_second.sort();
+    diff.both.sort();
+    diff
+}
+
+/// Scans two game folders with the same options and compares their conflicts.
+pub fn compare_installs(
+    first: &Path,
+    second: &Path,
+    options: &ScanOptions,
+) -> Result<InstallDiff, ScanError> {
+    let first = scan_for_conflicts(first, options, None, None)?;
+    let second = scan_for_conflicts(second, options, None, None)?;
+
+    Ok(diff_conflicts(&first.conflicts, &second.conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::write;
+
+    fn install(conflicting: &[&str]) -> TempDir {
+        let root = TempDir::new().unwrap();
+        for name in conflicting {
+            for folder in ["mod_a", "mod_b"] {
+                write(
+                    root.path(),
+                    &format!("packages/core/override/{folder}/{name}"),
+                    name.as_bytes(),
+                );
+            }
+        }
+        root
+    }
+
+    #[test]
+    fn splits_the_conflicts_of_two_installs() {
+        let known_good = install(&["shared.gda", "old.uti"]);
+        let experimental = install(&["shared.gda", "new.dlg", "another.uti"]);
+
+        let diff = compare_installs(
+            known_good.path(),
+            experimental.path(),
+            &ScanOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            diff,
+            InstallDiff {
+                only_first: vec!["old.uti".to_string()],
+                only_second: vec!["another.uti".to_string(), "new.dlg".to_string()],
+                both: vec!["shared.gda".to_string()],
+            }
+        );
+    }
+}
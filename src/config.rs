@@ -1,34 +1,241 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs, mem,
+    path::PathBuf,
+};
 
-use anyhow::{Context, Result as AnyhowResult, anyhow};
-use directories::ProjectDirs;
+use anyhow::{Context, Error as AnyhowError, Result as AnyhowResult, anyhow};
+use directories::{ProjectDirs, UserDirs};
 use serde::{Deserialize, Serialize};
 
-use crate::scanner::Conflicts;
+use crate::{
+    cache::TOC_CACHE_FILE,
+    content::KnownIdentical,
+    decisions::Decision,
+    display::DisplayRule,
+    pairing::ScriptPair,
+    profiles::{DEFAULT_PROFILE, ProfileResolutions},
+    safety::DeleteRules,
+    scanner::{Conflicts, Grouping, ScanOptions, default_ignored_names},
+    view::{SortMode, ViewState},
+};
 
 const QUALIFIER: &str = "com";
 const ORGANIZATION: &str = "Azlands";
 const APPLICATION: &str = "DAO-Conflict-Scanner";
 
+const DEFAULT_LOOKUP_URL: &str = "https://dragonage.fandom.com/wiki/Special:Search?query={name}";
+
+const DEFAULT_LANGUAGES: &[&str] = &[
+    "en-us", "fr-fr", "de-de", "it-it", "es-es", "pl-pl", "ru-ru", "cs-cz", "hu-hu",
+];
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub ignored: Conflicts,
+    /// Unix timestamp of when each ignored conflict was resolved.
+    pub ignored_at: HashMap<String, u64>,
+    /// Language tokens used to tell localized resource variants apart.
+    pub languages: Vec<String>,
+    /// Free-form notes attached to conflicts, by conflict key.
+    pub notes: HashMap<String, String>,
+    /// Every resolution action taken, in the order it was applied.
+    pub decisions: Vec<Decision>,
+    /// The source the user expects to win each conflict, by conflict key.
+    pub preferred: HashMap<String, PathBuf>,
+    /// Hash source contents during scans to tell identical duplicates from
+    /// divergent ones.
+    pub detect_divergence: bool,
+    /// Expand conflicts whose sources differ in content after each scan.
+    pub auto_expand_divergent: bool,
+    /// Leave zero-byte files out of conflict grouping.
+    pub skip_empty_files: bool,
+    /// Regex rewrites applied to resource names shown in the UI.
+    pub display_rules: Vec<DisplayRule>,
+    /// Copy files into the backup folder before deleting them.
+    pub backup_before_delete: bool,
+    /// Scan at background I/O priority so a running game isn't slowed down.
+    pub low_priority_scan: bool,
+    /// Whether conflicts group by exact file name or by name without extension.
+    pub grouping: Grouping,
+    /// Script source and compiled extensions expected to be overridden together.
+    pub script_pairs: Vec<ScriptPair>,
+    /// The profile scans and generated files are attributed to, whose ignore
+    /// list is `ignored`.
+    pub active_profile: String,
+    /// Ignore lists of the other profiles, by name. A config saved before
+    /// profiles existed has none, so its `ignored` list becomes the default
+    /// profile's.
+    pub profiles: BTreeMap<String, ProfileResolutions>,
+    /// Files written by the app, with the profile that generated them.
+    pub generated_files: HashMap<PathBuf, String>,
+    /// Treat hardlinks to the same file as a single source.
+    pub dedup_hardlinks: bool,
+    /// Extensions that can't be deleted or need a typed confirmation.
+    pub delete_rules: DeleteRules,
+    /// Skip archives and only look for loose file conflicts in the override folder.
+    pub override_only_scan: bool,
+    /// Restore the last filter, sort and list view on launch.
+    pub remember_view: bool,
+    pub view_state: ViewState,
+    /// Conflicts confirmed to hold identical copies, skipped when hashing.
+    pub known_identical: HashMap<String, KnownIdentical>,
+    /// Search URL opened to look a resource up online, `{name}` is replaced
+    /// with the resource name.
+    pub lookup_url: String,
+    /// Show adjacent sources from the same add-in or override folder as one line.
+    pub collapse_layers: bool,
+    /// Only scan and inspect, refusing every action that changes files or resolutions.
+    pub read_only: bool,
+    /// How the conflicts list is sorted.
+    pub list_sort: SortMode,
+    /// Compare contents during scans and leave out conflicts between identical copies.
+    pub hide_identical: bool,
+    /// The BioWare folder to scan, detected from the Documents folder when unset.
+    pub bioware_dir: Option<PathBuf>,
+    /// Conflicts left expanded, pruned to the ones still found after each scan.
+    pub expanded_conflicts: HashSet<String>,
+    /// Name patterns such as `*.gda` or `genmenu_*` whose conflicts are always ignored.
+    pub ignore_patterns: Vec<String>,
+    /// Resource names such as `manifest.xml` left out of every scan.
+    pub ignored_names: BTreeSet<String>,
+    /// Folders and archives inside the BioWare folder that are never scanned.
+    pub excluded_dirs: Vec<PathBuf>,
+    /// Window size and position when the app was last closed.
+    pub window: Option<WindowGeometry>,
+    /// Collect resolution actions for review instead of applying them right away.
+    pub stage_actions: bool,
+}
+
+/// Logical size and screen position of the main window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub size: [f32; 2],
+    pub position: Option<[f32; 2]>,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             ignored: Conflicts::new(),
+            ignored_at: HashMap::new(),
+            languages: DEFAULT_LANGUAGES.iter().map(|&l| l.to_string()).collect(),
+            notes: HashMap::new(),
+            decisions: Vec::new(),
+            preferred: HashMap::new(),
+            detect_divergence: false,
+            auto_expand_divergent: false,
+            skip_empty_files: false,
+            display_rules: Vec::new(),
+            backup_before_delete: false,
+            low_priority_scan: false,
+            grouping: Grouping::default(),
+            script_pairs: vec![ScriptPair::default()],
+            active_profile: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::new(),
+            generated_files: HashMap::new(),
+            dedup_hardlinks: false,
+            delete_rules: DeleteRules::default(),
+            override_only_scan: false,
+            remember_view: false,
+            view_state: ViewState::default(),
+            known_identical: HashMap::new(),
+            lookup_url: DEFAULT_LOOKUP_URL.to_string(),
+            collapse_layers: false,
+            read_only: false,
+            list_sort: SortMode::default(),
+            hide_identical: false,
+            bioware_dir: None,
+            expanded_conflicts: HashSet::new(),
+            ignore_patterns: Vec::new(),
+            ignored_names: default_ignored_names(),
+            excluded_dirs: Vec::new(),
+            window: None,
+            stage_actions: false,
         }
     }
 }
 
 impl AppConfig {
-    pub fn load() -> Self {
-        Self::load_saved().unwrap_or_else(|err| {
-            eprintln!("Warning: Could not load saved config. Using default. Details: {err}");
-            Self::default()
-        })
+    /// Loads the saved config, or the defaults with the reason when a saved
+    /// config exists but can't be read. The unreadable file is copied to
+    /// `config.toml.bak` first, since the next save replaces it.
+    pub fn load() -> (Self, Option<AnyhowError>) {
+        match Self::load_saved() {
+            Ok(Some(config)) => (config, None),
+            Ok(None) => (Self::default(), None),
+            Err(err) => {
+                if let Ok(path) = Self::config_file_path() {
+                    let _ = fs::copy(&path, path.with_extension("toml.bak"));
+                }
+                (Self::default(), Some(err))
+            }
+        }
+    }
+
+    /// The chosen BioWare folder, or the default `Documents/BioWare/Dragon Age`
+    /// when none was chosen.
+    pub fn bioware_dir(&self) -> Option<PathBuf> {
+        match &self.bioware_dir {
+            Some(dir) => dir.canonicalize().ok(),
+            None => detect_bioware_dir(),
+        }
+    }
+
+    /// Every profile name, the active one included.
+    pub fn profile_names(&self) -> BTreeSet<String> {
+        self.profiles
+            .keys()
+            .cloned()
+            .chain([self.active_profile.clone()])
+            .collect()
+    }
+
+    /// Makes `name` the active profile, setting the current ignore list aside
+    /// and bringing back the one `name` had. Unknown names start empty.
+    pub fn switch_profile(&mut self, name: &str) {
+        if name == self.active_profile {
+            return;
+        }
+
+        let previous = ProfileResolutions {
+            ignored: mem::take(&mut self.ignored),
+            ignored_at: mem::take(&mut self.ignored_at),
+        };
+        let previous_name = mem::replace(&mut self.active_profile, name.to_string());
+        self.profiles.insert(previous_name, previous);
+
+        let resolutions = self.profiles.remove(name).unwrap_or_default();
+        self.ignored = resolutions.ignored;
+        self.ignored_at = resolutions.ignored_at;
+    }
+
+    /// Deletes a profile other than the active one along with its ignore list.
+    pub fn delete_profile(&mut self, name: &str) {
+        self.profiles.remove(name);
+    }
+
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            languages: self.languages.clone(),
+            detect_divergence: self.detect_divergence,
+            skip_empty: self.skip_empty_files,
+            excluded_dirs: Self::backup_dir()
+                .into_iter()
+                .chain(self.excluded_dirs.iter().cloned())
+                .collect(),
+            background_priority: self.low_priority_scan,
+            grouping: self.grouping,
+            dedup_hardlinks: self.dedup_hardlinks,
+            override_only: self.override_only_scan,
+            known_identical: self.known_identical.clone(),
+            hide_identical: self.hide_identical,
+            toc_cache: Self::config_dir().ok().map(|dir| dir.join(TOC_CACHE_FILE)),
+            ignore_patterns: self.ignore_patterns.clone(),
+            ignored_names: self.ignored_names.clone(),
+            ..Default::default()
+        }
     }
 
     pub fn save(&self) -> AnyhowResult<()> {
@@ -46,19 +253,74 @@ impl AppConfig {
         Ok(())
     }
 
-    fn load_saved() -> AnyhowResult<Self> {
+    /// The saved config, `None` when nothing was saved yet.
+    fn load_saved() -> AnyhowResult<Option<Self>> {
         let config_path = Self::config_file_path()?;
         if !config_path.exists() {
-            return Err(anyhow!("Configuration file not found"));
+            return Ok(None);
         }
 
         let contents = fs::read_to_string(&config_path).context("Failed to read config file")?;
-        toml::from_str(&contents).context("Failed to parse TOML config")
+        toml::from_str(&contents)
+            .map(Some)
+            .context("Failed to parse TOML config")
     }
 
-    fn config_file_path() -> AnyhowResult<PathBuf> {
+    pub fn config_dir() -> AnyhowResult<PathBuf> {
         ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
-            .map(|proj_dirs| proj_dirs.config_dir().join("config.toml"))
+            .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
             .ok_or_else(|| anyhow!("Could not determine configuration directory for the app"))
     }
+
+    /// Where files are copied before deletion when `backup_before_delete` is on,
+    /// one timestamped folder per session.
+    pub fn backup_dir() -> AnyhowResult<PathBuf> {
+        Ok(Self::config_dir()?.join("backups"))
+    }
+
+    fn config_file_path() -> AnyhowResult<PathBuf> {
+        Ok(Self::config_dir()?.join("config.toml"))
+    }
+}
+
+fn detect_bioware_dir() -> Option<PathBuf> {
+    UserDirs::new()?
+        .document_dir()?
+        .join("BioWare/Dragon Age")
+        .canonicalize()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        restype::ResourceType,
+        view::{ListView, ResultsFilter},
+    };
+
+    #[test]
+    fn view_state_round_trips_through_the_config_file() {
+        let config = AppConfig {
+            remember_view: true,
+            list_sort: SortMode::CountDesc,
+            view_state: ViewState {
+                filter: ResultsFilter {
+                    query: "armor".to_string(),
+                    resource_type: Some("uti".to_string()),
+                    categories: BTreeSet::from([ResourceType::Gff, ResourceType::Table]),
+                },
+                list_view: ListView::All,
+                only_actionable: true,
+            },
+            ..Default::default()
+        };
+
+        let contents = toml::to_string_pretty(&config).unwrap();
+        let restored: AppConfig = toml::from_str(&contents).unwrap();
+
+        assert!(restored.remember_view);
+        assert_eq!(restored.list_sort, SortMode::CountDesc);
+        assert_eq!(restored.view_state, config.view_state);
+    }
 }
@@ -0,0 +1,380 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    fs::{self, File},
+    hash::{DefaultHasher, Hasher},
+    io::{BufReader, Read},
+    num::NonZeroUsize,
+    panic,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use anyhow::{Context, Result as AnyhowResult, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rim::Archive,
+    scanner::{Conflicts, ScanWarning, SourceKind},
+};
+
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+/// How long a recorded identical group is trusted before it's hashed again.
+pub const REVERIFY_AFTER_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Divergence {
+    /// Every source holds byte-identical content.
+    Identical,
+    /// At least two sources differ.
+    Divergent,
+}
+
+/// A conflict the user confirmed holds identical copies, with the size of
+/// each source when it was confirmed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownIdentical {
+    pub sources: Vec<(PathBuf, u64)>,
+    /// Unix timestamp of when the group was last hashed.
+    pub verified_at: u64,
+}
+
+impl KnownIdentical {
+    /// Records the current sizes of a conflict's sources.
+    pub fn record(key: &str, conflicts: &Conflicts, now: u64) -> AnyhowResult<Self> {
+        let paths = conflicts
+            .get(key)
+            .ok_or_else(|| anyhow!("{key} is no longer a conflict"))?;
+        let mut archives = HashMap::new();
+        let sources = paths
+            .iter()
+            .map(|path| {
+                let name = conflicts.entry_name(key, path);
+                Ok((path.clone(), source_size(path, name, &mut archives)?))
+            })
+            .collect::<AnyhowResult<_>>()?;
+
+        Ok(Self {
+            sources,
+            verified_at: now,
+        })
+    }
+
+    /// Whether the record still describes `paths`: the same sources, none of
+    /// which changed size, verified recently enough.
+    fn holds(
+        &self,
+        key: &str,
+        paths: &[PathBuf],
+        conflicts: &Conflicts,
+        now: u64,
+        archives: &mut HashMap<PathBuf, Archive>,
+    ) -> bool {
+        now.saturating_sub(self.verified_at) < REVERIFY_AFTER_SECS
+            && self.sources.len() == paths.len()
+            && self
+                .sources
+                .iter()
+                .zip(paths)
+                .all(|((recorded, size), path)| {
+                    recorded == path
+                        && source_size(path, conflicts.entry_name(key, path), archives)
+                            .is_ok_and(|current| current == *size)
+                })
+    }
+}
+
+/// Content classification of a set of conflicts.
+#[derive(Debug, Default)]
+pub struct Classification {
+    pub divergence: HashMap<String, Divergence>,
+    /// Groups classified from a [`KnownIdentical`] record without hashing.
+    pub trusted: HashSet<String>,
+    /// Groups with an unreadable source, left out of `divergence`.
+    pub warnings: Vec<ScanWarning>,
+}
+
+/// Hashes the content of every conflict's sources and classifies each group.
+///
+/// Packed resources are hashed after decompression, so the same content stored
+/// compressed in one archive and uncompressed in another counts as identical.
+/// Groups with an unreadable source are left out rather than guessed at.
+pub fn classify_conflicts(conflicts: &Conflicts) -> Classification {
+    classify_trusting(conflicts, &HashMap::new(), 0, |_, _| {})
+}
+
+/// Like [`classify_conflicts`], but groups recorded in `known` are classified as
+/// identical without hashing while their record holds.
+///
+/// Groups are spread across worker threads, `on_hashed` is called with the
+/// number of groups done so far and a source of the last one.
+pub fn classify_trusting(
+    conflicts: &Conflicts,
+    known: &HashMap<String, KnownIdentical>,
+    now: u64,
+    on_hashed: impl Fn(usize, &Path) + Sync,
+) -> Classification {
+    let groups: Vec<_> = conflicts.iter().collect();
+    if groups.is_empty() {
+        return Default::default();
+    }
+
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = groups.len().div_ceil(workers);
+    let hashed = AtomicUsize::new(0);
+    let (hashed, on_hashed) = (&hashed, &on_hashed);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = groups
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut archives = HashMap::new();
+                    let mut found = Classification::default();
+
+                    for &(key, paths) in chunk {
+                        if known.get(key).is_some_and(|record| {
+                            record.holds(key, paths, conflicts, now, &mut archives)
+                        }) {
+                            found.trusted.insert(key.clone());
+                            found.divergence.insert(key.clone(), Divergence::Identical);
+                        } else {
+                            match classify_group(key, paths, conflicts, &mut archives) {
+                                Ok(group) => {
+                                    found.divergence.insert(key.clone(), group);
+                                }
+                                Err(err) => {
+                                    let err = err
+                                        .context(format!("Could not compare the sources of {key}"));
+                                    if let Some(path) = paths.first() {
+                                        found.warnings.push(ScanWarning::new(path, &err));
+                                    }
+                                }
+                            }
+                        }
+
+                        if let Some(path) = paths.first() {
+                            on_hashed(hashed.fetch_add(1, Ordering::Relaxed) + 1, path);
+                        }
+                    }
+
+                    found
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .fold(Classification::default(), |mut all, handle| {
+                let found = handle
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err));
+                all.divergence.extend(found.divergence);
+                all.trusted.extend(found.trusted);
+                all.warnings.extend(found.warnings);
+                all
+            })
+    })
+}
+
+fn classify_group(
+    key: &str,
+    paths: &[PathBuf],
+    conflicts: &Conflicts,
+    archives: &mut HashMap<PathBuf, Archive>,
+) -> AnyhowResult<Divergence> {
+    let hashes = paths
+        .iter()
+        .map(|path| hash_source(path, conflicts.entry_name(key, path), archives))
+        .collect::<AnyhowResult<HashSet<_>>>()?;
+
+    Ok(if hashes.len() <= 1 {
+        Divergence::Identical
+    } else {
+        Divergence::Divergent
+    })
+}
+
+pub fn divergent_keys(divergence: &HashMap<String, Divergence>) -> HashSet<String> {
+    divergence
+        .iter()
+        .filter(|&(_, d)| *d == Divergence::Divergent)
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Reads the full content of a conflict source, unpacking the entry `name`
+/// from its archive.
+pub fn read_source(path: &Path, name: &str) -> AnyhowResult<Vec<u8>> {
+    match SourceKind::of(path) {
+        SourceKind::Loose => {
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        }
+        SourceKind::Archive => {
+            let archive = Archive::open(path)?;
+            let mut file = File::open(path)
+                .with_context(|| format!("Failed to open archive at {}", path.display()))?;
+            archive.get_resource(name, &mut file)
+        }
+    }
+}
+
+/// Hashes what the game would load from a source, i.e. decompressed content
+/// for archive resources rather than the stored bytes. Archive resources are
+/// streamed from their range in the archive, never read whole.
+fn hash_source(
+    path: &Path,
+    name: &str,
+    archives: &mut HashMap<PathBuf, Archive>,
+) -> AnyhowResult<u64> {
+    match SourceKind::of(path) {
+        SourceKind::Loose => {
+            let file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            hash_reader(file, path)
+        }
+        SourceKind::Archive => {
+            let archive = cached_archive(path, archives)?;
+
+            let file = File::open(path)
+                .with_context(|| format!("Failed to open archive at {}", path.display()))?;
+            let mut file = BufReader::new(file);
+            let resource = archive.resource_reader(name, &mut file)?;
+            hash_reader(resource, path)
+        }
+    }
+}
+
+/// Size of a source's content, unpacked size for archive resources.
+fn source_size(
+    path: &Path,
+    name: &str,
+    archives: &mut HashMap<PathBuf, Archive>,
+) -> AnyhowResult<u64> {
+    match SourceKind::of(path) {
+        SourceKind::Loose => Ok(fs::metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .len()),
+        SourceKind::Archive => cached_archive(path, archives)?
+            .length(name)
+            .map(u64::from)
+            .ok_or_else(|| anyhow!("{name} is missing from {}", path.display())),
+    }
+}
+
+fn cached_archive<'a>(
+    path: &Path,
+    archives: &'a mut HashMap<PathBuf, Archive>,
+) -> AnyhowResult<&'a Archive> {
+    Ok(match archives.entry(path.to_path_buf()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(Archive::open(path)?),
+    })
+}
+
+/// Hashes everything `reader` yields through a fixed-size buffer.
+fn hash_reader(mut reader: impl Read, path: &Path) -> AnyhowResult<u64> {
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut hasher = DefaultHasher::new();
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.write(&buffer[..read]);
+    }
+
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::{BIOWARE_ZLIB, erf_v30, write};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn conflict(key: &str, sources: &[PathBuf]) -> Conflicts {
+        let mut conflicts = Conflicts::new();
+        for path in sources {
+            conflicts.add_source(key.to_string(), path.clone(), key);
+        }
+        conflicts
+    }
+
+    #[test]
+    fn compressed_and_stored_copies_of_the_same_content_are_identical() {
+        let root = TempDir::new().unwrap();
+        let content = b"GFF V4.0PC  UTI V4.0".repeat(16);
+        let compressed = write(
+            root.path(),
+            "AddIns/mod_a/core/data/mod_a.erf",
+            &erf_v30(BIOWARE_ZLIB, &[("armor.uti", &content)]),
+        );
+        let stored = write(
+            root.path(),
+            "AddIns/mod_b/core/data/mod_b.erf",
+            &erf_v30(0, &[("Armor.uti", &content)]),
+        );
+        let loose = write(root.path(), "override/mod_c/armor.uti", b"edited");
+
+        let identical = conflict("armor.uti", &[compressed.clone(), stored.clone()]);
+        assert_eq!(
+            classify_conflicts(&identical).divergence["armor.uti"],
+            Divergence::Identical
+        );
+
+        let edited = conflict("armor.uti", &[compressed, stored, loose]);
+        let classification = classify_conflicts(&edited);
+        assert_eq!(
+            classification.divergence["armor.uti"],
+            Divergence::Divergent
+        );
+        assert!(classification.warnings.is_empty());
+    }
+
+    #[test]
+    fn recorded_identical_group_skips_hashing_until_a_size_changes() {
+        let root = TempDir::new().unwrap();
+        // Same size, different bytes, so only hashing can tell them apart
+        let first = write(root.path(), "override/mod_a/big.dds", b"aaaa");
+        let second = write(root.path(), "override/mod_b/big.dds", b"bbbb");
+        let conflicts = conflict("big.dds", &[first, second.clone()]);
+        let known = HashMap::from([(
+            "big.dds".to_string(),
+            KnownIdentical::record("big.dds", &conflicts, NOW).unwrap(),
+        )]);
+
+        let trusted = classify_trusting(&conflicts, &known, NOW + 60, |_, _| {});
+        assert!(trusted.trusted.contains("big.dds"));
+        assert_eq!(trusted.divergence["big.dds"], Divergence::Identical);
+
+        let stale = classify_trusting(&conflicts, &known, NOW + REVERIFY_AFTER_SECS, |_, _| {});
+        assert!(stale.trusted.is_empty());
+        assert_eq!(stale.divergence["big.dds"], Divergence::Divergent);
+
+        fs::write(&second, b"bbbbb").unwrap();
+        let resized = classify_trusting(&conflicts, &known, NOW + 60, |_, _| {});
+        assert!(resized.trusted.is_empty());
+        assert_eq!(resized.divergence["big.dds"], Divergence::Divergent);
+    }
+
+    #[test]
+    fn only_divergent_keys_are_auto_expanded() {
+        let divergence = HashMap::from([
+            ("a.uti".to_string(), Divergence::Divergent),
+            ("b.uti".to_string(), Divergence::Identical),
+            ("c.gda".to_string(), Divergence::Divergent),
+        ]);
+
+        assert_eq!(
+            divergent_keys(&divergence),
+            HashSet::from(["a.uti".to_string(), "c.gda".to_string()])
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::{Path, PathBuf};
+
+use pathdiff::diff_paths;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::format_date;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionAction {
+    Ignored,
+    Unignored,
+    Deleted,
+    Disabled,
+    Enabled,
+    RemovedFromArchive,
+}
+
+impl DecisionAction {
+    pub fn verb(self) -> &'static str {
+        match self {
+            DecisionAction::Ignored => "ignored",
+            DecisionAction::Unignored => "forgot the resolution of",
+            DecisionAction::Deleted => "deleted",
+            DecisionAction::Disabled => "disabled",
+            DecisionAction::Enabled => "re-enabled",
+            DecisionAction::RemovedFromArchive => "removed from its archive",
+        }
+    }
+}
+
+/// A single resolution action taken on a conflict, kept across sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decision {
+    pub timestamp: u64,
+    pub key: String,
+    pub action: DecisionAction,
+    pub path: Option<PathBuf>,
+    pub note: Option<String>,
+}
+
+/// Renders the decisions as a chronological, human-readable log.
+pub fn render_log(decisions: &[Decision], bioware_dir: &Path) -> String {
+    let mut ordered: Vec<_> = decisions.iter().collect();
+    ordered.sort_by_key(|d| d.timestamp);
+
+    let mut log = String::from("Conflict resolution log\n\n");
+
+    for decision in ordered {
+        log.push_str(&format!(
+            "On {}, {} {}",
+            format_date(decision.timestamp),
+            decision.action.verb(),
+            decision.key
+        ));
+
+        if let Some(path) = &decision.path {
+            let display_path = diff_paths(path, bioware_dir).unwrap_or_else(|| path.clone());
+            log.push_str(&format!(" ({})", display_path.display()));
+        }
+
+        if let Some(note) = &decision.note {
+            log.push_str(&format!(" because {note}"));
+        }
+
+        log.push('\n');
+    }
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_is_chronological_and_includes_notes() {
+        let bioware_dir = Path::new("/games/BioWare");
+        let decisions = [
+            Decision {
+                timestamp: 1_709_596_800,
+                key: "old_patch.gda".to_string(),
+                action: DecisionAction::Deleted,
+                path: Some(bioware_dir.join("packages/core/override/old_patch.gda")),
+                note: None,
+            },
+            Decision {
+                timestamp: 1_709_251_200,
+                key: "darkspawn.utc".to_string(),
+                action: DecisionAction::Ignored,
+                path: None,
+                note: Some("identical".to_string()),
+            },
+        ];
+
+        let log = render_log(&decisions, bioware_dir);
+        let lines: Vec<_> = log.lines().skip(2).collect();
+
+        assert_eq!(
+            lines,
+            [
+                "On 2024-03-01, ignored darkspawn.utc because identical",
+                "On 2024-03-05, deleted old_patch.gda (packages/core/override/old_patch.gda)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::{ops::Range, path::Path};
+
+use anyhow::Result as AnyhowResult;
+
+use crate::{content::read_source, preview::decode_text};
+
+/// Most lines of each side compared, longer texts are cut.
+pub const MAX_DIFF_LINES: usize = 3000;
+/// Most differing byte ranges listed for binary content.
+pub const MAX_BYTE_RANGES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    /// Only in the first source.
+    Removed(String),
+    /// Only in the second source.
+    Added(String),
+}
+
+#[derive(Debug)]
+pub enum ResourceDiff {
+    Text {
+        lines: Vec<DiffLine>,
+        /// A side had more than [`MAX_DIFF_LINES`] lines.
+        truncated: bool,
+    },
+    Binary {
+        /// Byte ranges where the two sources differ, over their common length.
+        ranges: Vec<Range<usize>>,
+        first_len: usize,
+        second_len: usize,
+    },
+}
+
+impl ResourceDiff {
+    pub fn is_identical(&self) -> bool {
+        match self {
+            ResourceDiff::Text { lines, .. } => {
+                lines.iter().all(|line| matches!(line, DiffLine::Same(_)))
+            }
+            ResourceDiff::Binary {
+                ranges,
+                first_len,
+                second_len,
+            } => ranges.is_empty() && first_len == second_len,
+        }
+    }
+}
+
+/// Compares a resource as provided by two sources, each given with the name
+/// of its entry, line by line when both decode as text and byte by byte
+/// otherwise.
+pub fn diff_sources(first: (&Path, &str), second: (&Path, &str)) -> AnyhowResult<ResourceDiff> {
+    let first = read_source(first.0, first.1)?;
+    let second = read_source(second.0, second.1)?;
+
+    Ok(match (decode_text(&first), decode_text(&second)) {
+        (Some(first_text), Some(second_text)) => {
+            let first_lines: Vec<_> = first_text.lines().collect();
+            let second_lines: Vec<_> = second_text.lines().collect();
+            let truncated =
+                first_lines.len() > MAX_DIFF_LINES || second_lines.len() > MAX_DIFF_LINES;
+
+            ResourceDiff::Text {
+                lines: diff_lines(
+                    &first_lines[..first_lines.len().min(MAX_DIFF_LINES)],
+                    &second_lines[..second_lines.len().min(MAX_DIFF_LINES)],
+                ),
+                truncated,
+            }
+        }
+        _ => ResourceDiff::Binary {
+            ranges: differing_ranges(&first, &second),
+            first_len: first.len(),
+            second_len: second.len(),
+        },
+    })
+}
+
+/// Line diff from the longest common subsequence of the two texts.
+fn diff_lines(first: &[&str], second: &[&str]) -> Vec<DiffLine> {
+    let (rows, cols) = (first.len(), second.len());
+    // common[i][j] is the LCS length of first[i..] and second[j..]
+    let mut common = vec![0u32; (rows + 1) * (cols + 1)];
+    let at = |i: usize, j: usize| i * (cols + 1) + j;
+
+    for i in (0..rows).rev() {
+        for j in (0..cols).rev() {
+            common[at(i, j)] = if first[i] == second[j] {
+                common[at(i + 1, j + 1)] + 1
+            } else {
+                common[at(i + 1, j)].max(common[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(rows.max(cols));
+    let (mut i, mut j) = (0, 0);
+    while i < rows || j < cols {
+        if i < rows && j < cols && first[i] == second[j] {
+            lines.push(DiffLine::Same(first[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < rows && (j == cols || common[at(i + 1, j)] >= common[at(i, j + 1)]) {
+            lines.push(DiffLine::Removed(first[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(second[j].to_string()));
+            j += 1;
+        }
+    }
+
+    lines
+}
+
+fn differing_ranges(first: &[u8], second: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+
+    for (index, _) in first
+        .iter()
+        .zip(second)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+    {
+        if let Some(range) = ranges.last_mut()
+            && range.end == index
+        {
+            range.end += 1;
+        } else if ranges.len() == MAX_BYTE_RANGES {
+            break;
+        } else {
+            ranges.push(index..index + 1);
+        }
+    }
+
+    ranges
+}
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A user-supplied rewrite applied to resource names before they are shown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayRule {
+    pub pattern: String,
+    /// Replacement text, may reference capture groups as `$1` or `${name}`.
+    pub replacement: String,
+}
+
+/// Compiled display rules, applied in order to conflict keys for display only.
+///
+/// Keys used for matching, ignoring and saving are never rewritten.
+#[derive(Debug, Default)]
+pub struct DisplayRules {
+    rules: Vec<(Regex, String)>,
+}
+
+impl DisplayRules {
+    /// Compiles the valid rules and returns a message for every invalid one.
+    pub fn compile(rules: &[DisplayRule]) -> (Self, Vec<String>) {
+        let mut compiled = Vec::new();
+        let mut errors = Vec::new();
+
+        for rule in rules {
+            match Regex::new(&rule.pattern) {
+                Ok(regex) => compiled.push((regex, rule.replacement.clone())),
+                Err(err) => errors.push(format!("Invalid display rule `{}`: {err}", rule.pattern)),
+            }
+        }
+
+        (Self { rules: compiled }, errors)
+    }
+
+    pub fn apply<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        let mut name = Cow::Borrowed(key);
+
+        for (regex, replacement) in &self.rules {
+            if let Cow::Owned(replaced) = regex.replace_all(&name, replacement.as_str()) {
+                name = Cow::Owned(replaced);
+            }
+        }
+
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str) -> DisplayRule {
+        DisplayRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn rules_rewrite_the_display_name_only() {
+        let (rules, errors) = DisplayRules::compile(&[
+            rule(r"^gen00fl_(\w+)\.dlg$", "Follower dialogue: $1"),
+            rule("dog", "Dog"),
+        ]);
+
+        assert!(errors.is_empty());
+        assert_eq!(rules.apply("gen00fl_dog.dlg"), "Follower dialogue: Dog");
+        assert!(matches!(
+            rules.apply("armor.uti"),
+            Cow::Borrowed("armor.uti")
+        ));
+    }
+
+    #[test]
+    fn invalid_rules_are_reported_and_skipped() {
+        let (rules, errors) = DisplayRules::compile(&[rule("(unclosed", "x"), rule("a", "b")]);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("(unclosed"));
+        assert_eq!(rules.apply("a.uti"), "b.uti");
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    content::Divergence,
+    scanner::{Conflicts, addin_root},
+};
+
+/// Fewest identical conflicts two folders must share to count as one install.
+pub const MIN_DUPLICATE_CONFLICTS: usize = 5;
+
+/// Two folders holding identical copies of the same mod.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateInstall {
+    pub folders: [PathBuf; 2],
+    /// Keys of the identical conflicts the two copies cause, sorted.
+    pub keys: Vec<String>,
+}
+
+impl DuplicateInstall {
+    /// Every source of the cluster's conflicts that lives in `folder`.
+    pub fn files_in(&self, folder: &Path, conflicts: &Conflicts) -> Vec<(String, PathBuf)> {
+        self.keys
+            .iter()
+            .filter_map(|key| Some((key, conflicts.get(key)?)))
+            .flat_map(|(key, paths)| {
+                paths
+                    .iter()
+                    .filter(|p| p.starts_with(folder))
+                    .map(|p| (key.clone(), p.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Finds folders that install the same content twice.
+///
+/// Only two-source conflicts known to be byte-identical are considered, so this
+/// needs content classification from the scan.
+pub fn find_duplicate_installs(
+    conflicts: &Conflicts,
+    divergence: &HashMap<String, Divergence>,
+    bioware_dir: &Path,
+) -> Vec<DuplicateInstall> {
+    let mut clusters = BTreeMap::<[PathBuf; 2], Vec<String>>::new();
+
+    for (key, paths) in conflicts {
+        if paths.len() != 2 || divergence.get(key) != Some(&Divergence::Identical) {
+            continue;
+        }
+
+        let mut folders = [
+            install_root(&paths[0], bioware_dir),
+            install_root(&paths[1], bioware_dir),
+        ];
+        if folders[0] == folders[1] {
+            continue;
+        }
+
+        folders.sort();
+        clusters.entry(folders).or_default().push(key.clone());
+    }
+
+    clusters
+        .into_iter()
+        .filter(|(_, keys)| keys.len() >= MIN_DUPLICATE_CONFLICTS)
+        .map(|(folders, mut keys)| {
+            keys.sort();
+            DuplicateInstall { folders, keys }
+        })
+        .collect()
+}
+
+/// The folder a mod was installed into: its add-in folder, its top folder in the
+/// override directory, or the file's own folder.
+fn install_root(path: &Path, bioware_dir: &Path) -> PathBuf {
+    if let Some(root) = addin_root(path, bioware_dir) {
+        return root;
+    }
+
+    let parent = path.parent().unwrap_or(path);
+    let override_dir = bioware_dir.join("packages/core/override");
+
+    match parent.strip_prefix(&override_dir).map(|r| r.iter().next()) {
+        Ok(Some(first)) => override_dir.join(first),
+        _ => parent.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_copies_in_two_addins_form_one_cluster() {
+        let bioware_dir = Path::new("/BioWare");
+        let first = bioware_dir.join("AddIns/mod_a");
+        let second = bioware_dir.join("AddIns/mod_a_copy");
+        let mut conflicts = Conflicts::new();
+        let mut divergence = HashMap::new();
+
+        for index in 0..MIN_DUPLICATE_CONFLICTS {
+            let key = format!("item_{index}.uti");
+            for folder in [&second, &first] {
+                let path = folder.join("core/override").join(&key);
+                conflicts.add_source(key.clone(), path, &key);
+            }
+            divergence.insert(key, Divergence::Identical);
+        }
+        // Edited in one copy, so not part of the duplicate
+        for folder in [&first, &second] {
+            let path = folder.join("core/override/edited.uti");
+            conflicts.add_source("edited.uti".to_string(), path, "edited.uti");
+        }
+        divergence.insert("edited.uti".to_string(), Divergence::Divergent);
+        // Shared with another mod only once, too few to be an install
+        for folder in [
+            "packages/core/override/mod_b",
+            "packages/core/override/mod_c",
+        ] {
+            let path = bioware_dir.join(folder).join("shared.gda");
+            conflicts.add_source("shared.gda".to_string(), path, "shared.gda");
+        }
+        divergence.insert("shared.gda".to_string(), Divergence::Identical);
+
+        let duplicates = find_duplicate_installs(&conflicts, &divergence, bioware_dir);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].folders, [first, second.clone()]);
+        assert_eq!(
+            duplicates[0].keys,
+            (0..MIN_DUPLICATE_CONFLICTS)
+                .map(|index| format!("item_{index}.uti"))
+                .collect::<Vec<_>>()
+        );
+
+        let files = duplicates[0].files_in(&second, &conflicts);
+        assert_eq!(files.len(), MIN_DUPLICATE_CONFLICTS);
+        assert!(files.iter().all(|(_, path)| path.starts_with(&second)));
+    }
+}
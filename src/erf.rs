@@ -1,14 +1,17 @@
 #![allow(dead_code)]
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{self, Read, Seek, SeekFrom},
-    path::Path,
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
 };
 
 use anyhow::{Context, Result as AnyhowResult};
+use flate2::read::{DeflateDecoder, ZlibDecoder};
 use thiserror::Error as ThisError;
 
+use crate::restype::{ResourceType, extension_of_hash};
+
 #[derive(Debug, ThisError)]
 pub enum ErfError {
     #[error("Invalid file header: expected {expected:?}, found {found:?}")]
@@ -25,6 +28,31 @@ pub enum ErfError {
 
     #[error("Invalid UTF-16 character in string")]
     InvalidStringEncoding,
+
+    #[error("Unsupported compression type: {0}")]
+    UnsupportedCompression(u32),
+
+    #[error("Decompressed {name} to {found} bytes, expected {expected}")]
+    LengthMismatch {
+        name: String,
+        expected: u32,
+        found: usize,
+    },
+
+    #[error("Encrypted archives are not supported (encryption scheme {0})")]
+    Encrypted(u32),
+
+    #[error("{name} at offset {offset} with length {length} extends past the end of the archive")]
+    EntryOutOfBounds {
+        name: String,
+        offset: u32,
+        length: u32,
+    },
+
+    #[error(
+        "String table of {size} bytes doesn't fit in the {remaining} bytes left in the archive"
+    )]
+    StringTableOutOfBounds { size: u32, remaining: u64 },
 }
 
 #[derive(Debug)]
@@ -33,6 +61,16 @@ pub struct ErfFile {
     pub year: u32,
     pub day: u32,
     pub module_id: u32,
+    /// Raw header flags, compression in the top three bits and encryption in
+    /// bits 4 to 7. Always zero for V2.0 archives.
+    pub flags: u32,
+    pub compression: Compression,
+    /// Digest of the password of an encrypted V2.2 archive, zeroed otherwise.
+    pub password_digest: [u8; 16],
+    /// Talk table reference for the archive description, `None` when unset or truncated.
+    pub description_strref: Option<u32>,
+    /// Size of the archive in bytes.
+    pub file_len: u64,
     pub toc: Vec<ErfTocEntry>,
     pub by_name: HashMap<String, usize>,
 }
@@ -41,6 +79,39 @@ pub struct ErfFile {
 pub enum ErfVersion {
     V20,
     V22,
+    V30,
+}
+
+impl ErfVersion {
+    pub fn label(self) -> &'static str {
+        match self {
+            ErfVersion::V20 => "V2.0",
+            ErfVersion::V22 => "V2.2",
+            ErfVersion::V30 => "V3.0",
+        }
+    }
+}
+
+/// How compressed entries of an archive are packed, from the header flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    /// A byte holding the window size, then a raw deflate stream.
+    BiowareZlib,
+    /// A regular zlib stream.
+    HeaderlessZlib,
+    Unknown(u32),
+}
+
+impl Compression {
+    fn from_flags(flags: u32) -> Self {
+        match flags >> 29 {
+            0 => Compression::None,
+            1 => Compression::BiowareZlib,
+            7 => Compression::HeaderlessZlib,
+            other => Compression::Unknown(other),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -49,19 +120,74 @@ pub struct ErfTocEntry {
     pub offset: u32,
     pub packed_length: u32,
     pub length: u32,
+    pub restype: ResourceType,
 }
 
-#[derive(Debug)]
-pub struct ResourceEntry {
-    pub resref: String,
-    pub resid: u16,
-    pub restype: u16,
-    pub offset: u32,
-    pub length: u32,
+impl ErfTocEntry {
+    pub fn is_compressed(&self) -> bool {
+        self.packed_length != self.length
+    }
+
+    /// Packed size as a fraction of the unpacked size, `None` for empty entries.
+    pub fn compression_ratio(&self) -> Option<f32> {
+        (self.length > 0).then(|| self.packed_length as f32 / self.length as f32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErfIssue {
+    Unreadable(String),
+    EmptyToc,
+    DuplicateName(String),
+    EntryOutOfBounds {
+        name: String,
+        offset: u32,
+        length: u32,
+    },
+    TypeMismatch {
+        name: String,
+        content_type: String,
+    },
+}
+
+impl ErfIssue {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ErfIssue::Unreadable(_) => "Unreadable archive",
+            ErfIssue::EmptyToc => "Empty archive",
+            ErfIssue::DuplicateName(_) => "Duplicate resource name",
+            ErfIssue::EntryOutOfBounds { .. } => "Resource out of bounds",
+            ErfIssue::TypeMismatch { .. } => "Resource type mismatch",
+        }
+    }
+
+    pub fn details(&self) -> String {
+        match self {
+            ErfIssue::Unreadable(reason) => reason.clone(),
+            ErfIssue::EmptyToc => "The archive contains no resources".to_string(),
+            ErfIssue::DuplicateName(name) => format!("{name} is listed more than once"),
+            ErfIssue::EntryOutOfBounds {
+                name,
+                offset,
+                length,
+            } => format!("{name} (offset {offset}, length {length}) extends past the end of file"),
+            ErfIssue::TypeMismatch { name, content_type } => {
+                format!("{name} holds a .{content_type} resource")
+            }
+        }
+    }
 }
 
 pub type ErfResult<T> = Result<T, ErfError>;
 
+const NO_STRREF: u32 = 0xFFFF_FFFF;
+/// UTF-16 characters in a V2.x TOC name.
+const NAME_CHARS: usize = 32;
+/// Size of a V3.0 TOC entry.
+const V30_ENTRY_SIZE: usize = 28;
+const NO_NAME: u32 = 0xFFFF_FFFF;
+const GFF_SIGNATURE: &[u8; 8] = b"GFF V4.0";
+
 impl ErfFile {
     pub fn open<P: AsRef<Path>>(path: P) -> AnyhowResult<Self> {
         let path_ref = path.as_ref();
@@ -72,11 +198,71 @@ impl ErfFile {
             .with_context(|| format!("Failed to parse ERF file at {}", path_ref.display()))
     }
 
+    pub fn entries(&self) -> impl Iterator<Item = &ErfTocEntry> {
+        self.toc.iter()
+    }
+
+    /// Writes the resource `name` into `dir` under its own name, returning the
+    /// path of the written file.
+    pub fn extract_to<R: Read + Seek, P: AsRef<Path>>(
+        &self,
+        name: &str,
+        reader: &mut R,
+        dir: P,
+    ) -> AnyhowResult<PathBuf> {
+        // Only the last component, so a crafted name can't escape `dir`
+        let file_name = Path::new(name)
+            .file_name()
+            .ok_or_else(|| ErfError::InvalidResourceName(name.to_string()))?;
+        let target = dir.as_ref().join(file_name);
+
+        let data = self.get_resource(name, reader)?;
+        fs::write(&target, data)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+
+        Ok(target)
+    }
+
+    /// Extracts every resource into `dir`, creating it if needed.
+    pub fn extract_all<R: Read + Seek, P: AsRef<Path>>(
+        &self,
+        reader: &mut R,
+        dir: P,
+    ) -> AnyhowResult<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        self.entries()
+            .map(|entry| self.extract_to(&entry.name, reader, dir))
+            .collect()
+    }
+
+    /// Encryption scheme from the header flags, 0 when resources are stored
+    /// in the clear.
+    pub fn encryption(&self) -> u32 {
+        (self.flags >> 4) & 0xF
+    }
+
+    fn check_readable(&self) -> ErfResult<()> {
+        match self.encryption() {
+            0 => Ok(()),
+            scheme => Err(ErfError::Encrypted(scheme)),
+        }
+    }
+
+    /// Looks up a TOC entry by resource name, ignoring case.
+    pub fn entry(&self, name: &str) -> Option<&ErfTocEntry> {
+        self.by_name
+            .get(&name.to_lowercase())
+            .map(|&index| &self.toc[index])
+    }
+
     pub fn get_resource<R: Read + Seek>(
         &self,
         name: &str,
         reader: &mut R,
     ) -> AnyhowResult<Vec<u8>> {
+        self.check_readable()?;
         let key = name.to_lowercase();
         let index = self
             .by_name
@@ -89,20 +275,290 @@ impl ErfFile {
             .seek(SeekFrom::Start(entry.offset as u64))
             .context("Failed to seek to resource offset")?;
 
-        let mut data = vec![0u8; entry.length as usize];
+        if !entry.is_compressed() {
+            let mut data = vec![0u8; entry.length as usize];
+            reader
+                .read_exact(&mut data)
+                .context("Failed to read resource data")?;
+
+            return Ok(data);
+        }
+
+        let mut packed = vec![0u8; entry.packed_length as usize];
         reader
-            .read_exact(&mut data)
+            .read_exact(&mut packed)
             .context("Failed to read resource data")?;
 
+        self.decompress(entry, &packed)
+            .with_context(|| format!("Failed to decompress {}", entry.name))
+    }
+
+    /// Streams a resource's unpacked content straight from its range in the
+    /// archive, without reading it into memory first.
+    pub fn resource_reader<'a, R: Read + Seek>(
+        &self,
+        name: &str,
+        reader: &'a mut R,
+    ) -> AnyhowResult<Box<dyn Read + 'a>> {
+        self.check_readable()?;
+        let entry = self
+            .entry(name)
+            .ok_or_else(|| ErfError::InvalidResourceName(name.to_string()))?;
+
+        reader
+            .seek(SeekFrom::Start(entry.offset as u64))
+            .context("Failed to seek to resource offset")?;
+        let packed = reader.take(entry.packed_length as u64);
+
+        if !entry.is_compressed() {
+            return Ok(Box::new(packed));
+        }
+
+        Ok(match self.compression {
+            Compression::BiowareZlib => {
+                let mut packed = packed;
+                // The leading window size byte isn't needed to inflate
+                packed
+                    .read_exact(&mut [0u8; 1])
+                    .context("Failed to read resource data")?;
+                Box::new(DeflateDecoder::new(packed))
+            }
+            Compression::HeaderlessZlib => Box::new(ZlibDecoder::new(packed)),
+            Compression::None => {
+                return Err(ErfError::LengthMismatch {
+                    name: entry.name.clone(),
+                    expected: entry.length,
+                    found: entry.packed_length as usize,
+                }
+                .into());
+            }
+            Compression::Unknown(kind) => return Err(ErfError::UnsupportedCompression(kind).into()),
+        })
+    }
+
+    fn decompress(&self, entry: &ErfTocEntry, packed: &[u8]) -> ErfResult<Vec<u8>> {
+        let mut data = Vec::with_capacity(entry.length as usize);
+
+        match self.compression {
+            Compression::BiowareZlib => {
+                // The leading window size byte isn't needed to inflate
+                let stream = packed.get(1..).unwrap_or_default();
+                DeflateDecoder::new(stream).read_to_end(&mut data)?;
+            }
+            Compression::HeaderlessZlib => {
+                ZlibDecoder::new(packed).read_to_end(&mut data)?;
+            }
+            Compression::None => {
+                return Err(ErfError::LengthMismatch {
+                    name: entry.name.clone(),
+                    expected: entry.length,
+                    found: packed.len(),
+                });
+            }
+            Compression::Unknown(kind) => return Err(ErfError::UnsupportedCompression(kind)),
+        }
+
+        if data.len() != entry.length as usize {
+            return Err(ErfError::LengthMismatch {
+                name: entry.name.clone(),
+                expected: entry.length,
+                found: data.len(),
+            });
+        }
+
         Ok(data)
     }
 
+    /// Opens the archive at `path` and reports any structural problems found.
+    pub fn verify<P: AsRef<Path>>(path: P) -> Vec<ErfIssue> {
+        let path_ref = path.as_ref();
+
+        let file_len = match fs::metadata(path_ref) {
+            Ok(metadata) => metadata.len(),
+            Err(err) => return vec![ErfIssue::Unreadable(err.to_string())],
+        };
+
+        let erf = match Self::open(path_ref) {
+            Ok(erf) => erf,
+            Err(err) => return vec![ErfIssue::Unreadable(format!("{err:#}"))],
+        };
+
+        let mut issues = erf.check(file_len);
+        if let Ok(mut file) = File::open(path_ref) {
+            issues.extend(erf.check_types(&mut file));
+        }
+
+        issues
+    }
+
+    /// Flags GFF resources whose declared file type disagrees with their extension.
+    ///
+    /// Compressed and non-GFF resources can't be checked and are skipped.
+    pub fn check_types<R: Read + Seek>(&self, reader: &mut R) -> Vec<ErfIssue> {
+        self.toc
+            .iter()
+            .filter(|entry| !entry.is_compressed())
+            .filter_map(|entry| {
+                let content_type = gff_type(entry, reader).ok().flatten()?;
+                let extension = entry.name.rsplit_once('.')?.1.to_lowercase();
+
+                (extension != content_type).then(|| ErfIssue::TypeMismatch {
+                    name: entry.name.clone(),
+                    content_type,
+                })
+            })
+            .collect()
+    }
+
+    pub fn check(&self, file_len: u64) -> Vec<ErfIssue> {
+        let mut issues = Vec::new();
+
+        if self.toc.is_empty() {
+            issues.push(ErfIssue::EmptyToc);
+        }
+
+        let mut seen = HashSet::with_capacity(self.toc.len());
+        for entry in &self.toc {
+            if !seen.insert(entry.name.to_lowercase()) {
+                issues.push(ErfIssue::DuplicateName(entry.name.clone()));
+            }
+
+            if entry.offset as u64 + entry.packed_length as u64 > file_len {
+                issues.push(ErfIssue::EntryOutOfBounds {
+                    name: entry.name.clone(),
+                    offset: entry.offset,
+                    length: entry.packed_length,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Writes the archive in its own V2.0 or V2.2 layout, copying every
+    /// resource as stored from `reader`, the archive it was opened from.
+    pub fn write<R: Read + Seek, W: Write>(&self, reader: &mut R, writer: &mut W) -> ErfResult<()> {
+        let entries: Vec<_> = self.toc.iter().collect();
+        self.write_entries(&entries, reader, writer)
+    }
+
+    /// Writes a copy of the archive that leaves out the resource `name`.
+    pub fn write_without<R: Read + Seek, W: Write>(
+        &self,
+        name: &str,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> ErfResult<()> {
+        let removed = self
+            .entry(name)
+            .ok_or_else(|| ErfError::InvalidResourceName(name.to_string()))?;
+        let entries: Vec<_> = self
+            .toc
+            .iter()
+            .filter(|entry| !std::ptr::eq(*entry, removed))
+            .collect();
+
+        self.write_entries(&entries, reader, writer)
+    }
+
+    /// Rewrites the archive at `path` without the resource `name`, through a
+    /// temporary file so a failed write leaves the archive untouched.
+    pub fn remove_resource<P: AsRef<Path>>(path: P, name: &str) -> AnyhowResult<()> {
+        let path = path.as_ref();
+        let erf = Self::open(path)?;
+
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        let written = (|| -> AnyhowResult<()> {
+            let mut reader = File::open(path)?;
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            erf.write_without(name, &mut reader, &mut writer)?;
+            writer.flush()?;
+            Ok(())
+        })();
+        if let Err(err) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err.context(format!("Failed to rewrite {}", path.display())));
+        }
+
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))
+    }
+
+    fn write_entries<R: Read + Seek, W: Write>(
+        &self,
+        entries: &[&ErfTocEntry],
+        reader: &mut R,
+        writer: &mut W,
+    ) -> ErfResult<()> {
+        let (version, header_size, entry_size) = match self.version {
+            ErfVersion::V20 => ("V2.0", 32, 72),
+            ErfVersion::V22 => ("V2.2", 56, 76),
+            ErfVersion::V30 => {
+                return Err(ErfError::UnsupportedVersion(
+                    "V3.0 archives can't be written".to_string(),
+                ));
+            }
+        };
+
+        writer.write_all(&encode_utf16le("ERF "))?;
+        writer.write_all(&encode_utf16le(version))?;
+        writer.write_all(&(entries.len() as u32).to_le_bytes())?;
+        writer.write_all(&self.year.to_le_bytes())?;
+        writer.write_all(&self.day.to_le_bytes())?;
+        writer.write_all(&self.description_strref.unwrap_or(NO_STRREF).to_le_bytes())?;
+        if self.version == ErfVersion::V22 {
+            writer.write_all(&self.flags.to_le_bytes())?;
+            writer.write_all(&self.module_id.to_le_bytes())?;
+            writer.write_all(&self.password_digest)?;
+        }
+
+        let mut offset = (header_size + entries.len() * entry_size) as u32;
+        for entry in entries {
+            let mut name = encode_utf16le(&entry.name);
+            if name.len() > NAME_CHARS * 2 {
+                return Err(ErfError::InvalidResourceName(format!(
+                    "{} is longer than {NAME_CHARS} characters",
+                    entry.name
+                )));
+            }
+            name.resize(NAME_CHARS * 2, 0);
+
+            writer.write_all(&name)?;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(&entry.packed_length.to_le_bytes())?;
+            if self.version == ErfVersion::V22 {
+                writer.write_all(&entry.length.to_le_bytes())?;
+            }
+            offset += entry.packed_length;
+        }
+
+        for entry in entries {
+            reader.seek(SeekFrom::Start(entry.offset as u64))?;
+            let copied = io::copy(&mut reader.take(entry.packed_length as u64), writer)?;
+            if copied != entry.packed_length as u64 {
+                return Err(ErfError::EntryOutOfBounds {
+                    name: entry.name.clone(),
+                    offset: entry.offset,
+                    length: entry.packed_length,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     fn from_reader<R: Read + Seek>(reader: &mut R) -> ErfResult<Self> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
         let (magic, version_str) = Self::read_header(reader)?;
 
-        let version = match (magic.as_str(), version_str.as_str()) {
-            ("ERF ", "V2.0") => ErfVersion::V20,
-            ("ERF ", "V2.2") => ErfVersion::V22,
+        let mut erf = match (magic.as_str(), version_str.as_str()) {
+            ("ERF ", "V2.0") => Self::parse(reader, ErfVersion::V20, file_len)?,
+            ("ERF ", "V2.2") => Self::parse(reader, ErfVersion::V22, file_len)?,
+            ("ERF ", "V3.0") => Self::parse_v30(reader, file_len)?,
             ("ERF ", _) => return Err(ErfError::UnsupportedVersion(version_str)),
             (found, _) => {
                 return Err(ErfError::InvalidHeader {
@@ -112,7 +568,25 @@ impl ErfFile {
             }
         };
 
-        Self::parse(reader, version)
+        erf.file_len = file_len;
+        erf.check_bounds()?;
+        Ok(erf)
+    }
+
+    /// Fails on the first entry whose data doesn't fit inside the archive.
+    fn check_bounds(&self) -> ErfResult<()> {
+        match self
+            .toc
+            .iter()
+            .find(|entry| entry.offset as u64 + entry.packed_length as u64 > self.file_len)
+        {
+            Some(entry) => Err(ErfError::EntryOutOfBounds {
+                name: entry.name.clone(),
+                offset: entry.offset,
+                length: entry.packed_length,
+            }),
+            None => Ok(()),
+        }
     }
 
     fn read_header<R: Read>(reader: &mut R) -> ErfResult<(String, String)> {
@@ -125,35 +599,45 @@ impl ErfFile {
         Ok((magic, version))
     }
 
-    fn parse<R: Read + Seek>(reader: &mut R, version: ErfVersion) -> ErfResult<Self> {
-        let mut header = [0u8; 16];
+    fn parse<R: Read + Seek>(
+        reader: &mut R,
+        version: ErfVersion,
+        file_len: u64,
+    ) -> ErfResult<Self> {
+        let mut header = [0u8; 12];
         reader.read_exact(&mut header)?;
 
         let file_count = read_u32(&header[0..4]);
         let year = read_u32(&header[4..8]);
         let day = read_u32(&header[8..12]);
-        let module_id = if version == ErfVersion::V22 {
-            read_u32(&header[12..16])
+        let description_strref = read_optional_u32(reader)?.filter(|&strref| strref != NO_STRREF);
+
+        // V2.2 adds a flags word, the module id and a 16 byte password digest
+        // before the TOC, so its TOC starts at 56 rather than 32
+        let (flags, module_id, password_digest) = if version == ErfVersion::V22 {
+            let mut extended = [0u8; 24];
+            reader.read_exact(&mut extended)?;
+            let mut digest = [0u8; 16];
+            digest.copy_from_slice(&extended[8..24]);
+            (read_u32(&extended[0..4]), read_u32(&extended[4..8]), digest)
         } else {
-            0
+            (0, 0, [0u8; 16])
         };
 
-        let mut toc = Vec::with_capacity(file_count as usize);
-        let mut by_name = HashMap::with_capacity(file_count as usize);
+        // The count comes straight from the header, so only what fits in the
+        // file is allocated up front
+        let entry_size = if version == ErfVersion::V22 { 76 } else { 72 };
+        let remaining = file_len.saturating_sub(reader.stream_position()?);
+        let capacity = (file_count as u64).min(remaining / entry_size as u64) as usize;
+        let mut toc = Vec::with_capacity(capacity);
+        let mut by_name = HashMap::with_capacity(capacity);
 
         for i in 0..file_count {
-            let entry_size = if version == ErfVersion::V22 { 76 } else { 72 };
             let mut entry_data = vec![0u8; entry_size];
 
             reader.read_exact(&mut entry_data)?;
 
-            let name = decode_utf16le(&entry_data[0..64])?;
-
-            if name.is_empty() {
-                return Err(ErfError::InvalidResourceName(format!(
-                    "Empty resource name in TOC at index {i}"
-                )));
-            }
+            let name = normalize_name(&decode_utf16le(&entry_data[0..64])?, i)?;
 
             let offset = read_u32(&entry_data[64..68]);
             let packed_length = read_u32(&entry_data[68..72]);
@@ -164,6 +648,7 @@ impl ErfFile {
             };
 
             toc.push(ErfTocEntry {
+                restype: ResourceType::of_name(&name),
                 name: name.clone(),
                 offset,
                 packed_length,
@@ -178,14 +663,143 @@ impl ErfFile {
             year,
             day,
             module_id,
+            flags,
+            compression: Compression::from_flags(flags),
+            password_digest,
+            description_strref,
+            file_len: 0,
+            toc,
+            by_name,
+        })
+    }
+
+    /// V3.0 keeps names in a string table and identifies entries by hash, and
+    /// has no build date or description.
+    fn parse_v30<R: Read + Seek>(reader: &mut R, file_len: u64) -> ErfResult<Self> {
+        let mut header = [0u8; 32];
+        reader.read_exact(&mut header)?;
+
+        let string_table_size = read_u32(&header[0..4]);
+        let file_count = read_u32(&header[4..8]);
+        let flags = read_u32(&header[8..12]);
+        let module_id = read_u32(&header[12..16]);
+
+        // Sizes come straight from the header, so they're checked against the
+        // file before anything is allocated for them
+        let remaining = file_len.saturating_sub(reader.stream_position()?);
+        if string_table_size as u64 > remaining {
+            return Err(ErfError::StringTableOutOfBounds {
+                size: string_table_size,
+                remaining,
+            });
+        }
+        let mut string_table = vec![0u8; string_table_size as usize];
+        reader.read_exact(&mut string_table)?;
+
+        let entries_fit = (remaining - string_table_size as u64) / V30_ENTRY_SIZE as u64;
+        let capacity = (file_count as u64).min(entries_fit) as usize;
+        let mut toc = Vec::with_capacity(capacity);
+        let mut by_name = HashMap::with_capacity(capacity);
+
+        for i in 0..file_count {
+            let mut entry_data = [0u8; V30_ENTRY_SIZE];
+            reader.read_exact(&mut entry_data)?;
+
+            let name_offset = read_u32(&entry_data[0..4]);
+            let name_hash = u64::from_le_bytes(entry_data[4..12].try_into().unwrap_or_default());
+            let type_extension = extension_of_hash(read_u32(&entry_data[12..16]));
+            let name = if name_offset == NO_NAME {
+                // Unnamed entries are only known by hash, and by type when it's a known one
+                match type_extension {
+                    Some(ext) => format!("#{name_hash:016x}.{ext}"),
+                    None => format!("#{name_hash:016x}"),
+                }
+            } else {
+                let name = read_c_string(&string_table, name_offset as usize).ok_or_else(|| {
+                    ErfError::InvalidResourceName(format!(
+                        "Name offset {name_offset} outside the string table at index {i}"
+                    ))
+                })?;
+                normalize_name(&name, i)?
+            };
+
+            toc.push(ErfTocEntry {
+                restype: type_extension
+                    .map_or_else(|| ResourceType::of_name(&name), ResourceType::of_extension),
+                name: name.clone(),
+                offset: read_u32(&entry_data[16..20]),
+                packed_length: read_u32(&entry_data[20..24]),
+                length: read_u32(&entry_data[24..28]),
+            });
+
+            by_name.insert(name.to_lowercase(), i as usize);
+        }
+
+        Ok(Self {
+            version: ErfVersion::V30,
+            year: 0,
+            day: 0,
+            module_id,
+            flags,
+            compression: Compression::from_flags(flags),
+            password_digest: [0u8; 16],
+            description_strref: None,
+            file_len: 0,
             toc,
             by_name,
         })
     }
 }
 
+/// Cuts a TOC name at its NUL padding and rejects empty or control-character names.
+fn normalize_name(raw: &str, index: u32) -> ErfResult<String> {
+    let name = raw.split('\0').next().unwrap_or_default().trim_end();
+
+    if name.is_empty() {
+        return Err(ErfError::InvalidResourceName(format!(
+            "Empty resource name in TOC at index {index}"
+        )));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(ErfError::InvalidResourceName(format!(
+            "{} in TOC at index {index} holds control characters",
+            name.escape_debug()
+        )));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Reads a NUL-terminated string starting at `offset`, `None` if out of range.
+fn read_c_string(table: &[u8], offset: usize) -> Option<String> {
+    let bytes = table.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let name = String::from_utf8_lossy(&bytes[..end]).into_owned();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Reads the file type of a GFF resource, e.g. `utc`, or `None` if it isn't GFF.
+fn gff_type<R: Read + Seek>(entry: &ErfTocEntry, reader: &mut R) -> ErfResult<Option<String>> {
+    if entry.length < 16 {
+        return Ok(None);
+    }
+
+    let mut header = [0u8; 16];
+    reader.seek(SeekFrom::Start(entry.offset as u64))?;
+    reader.read_exact(&mut header)?;
+
+    if &header[0..8] != GFF_SIGNATURE {
+        return Ok(None);
+    }
+
+    let file_type = String::from_utf8_lossy(&header[12..16])
+        .trim()
+        .to_lowercase();
+    Ok((!file_type.is_empty()).then_some(file_type))
+}
+
 fn decode_utf16le(bytes: &[u8]) -> ErfResult<String> {
-    if bytes.len() % 2 != 0 {
+    if !bytes.len().is_multiple_of(2) {
         return Err(ErfError::InvalidStringEncoding);
     }
 
@@ -203,8 +817,284 @@ fn decode_utf16le(bytes: &[u8]) -> ErfResult<String> {
     Ok(result)
 }
 
+fn encode_utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// Reads a `u32`, returning `None` instead of failing when the input ends early.
+fn read_optional_u32<R: Read>(reader: &mut R) -> ErfResult<Option<u32>> {
+    let mut buf = [0u8; 4];
+    match reader.read_exact(&mut buf) {
+        Ok(()) => Ok(Some(u32::from_le_bytes(buf))),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn read_u32(bytes: &[u8]) -> u32 {
     let mut buf = [0u8; 4];
     buf.copy_from_slice(bytes);
     u32::from_le_bytes(buf)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::{BIOWARE_ZLIB, erf_v20, erf_v22, erf_v30, write};
+
+    fn parse(bytes: Vec<u8>) -> ErfResult<ErfFile> {
+        ErfFile::from_reader(&mut Cursor::new(bytes))
+    }
+
+    #[test]
+    fn reads_the_description_strref_after_the_build_date() {
+        let mut bytes = erf_v20(&[("a.gda", b"abc")]);
+        bytes[28..32].copy_from_slice(&123_456u32.to_le_bytes());
+
+        assert_eq!(parse(bytes).unwrap().description_strref, Some(123_456));
+    }
+
+    #[test]
+    fn unset_description_strref_is_none() {
+        assert_eq!(parse(erf_v20(&[])).unwrap().description_strref, None);
+    }
+
+    #[test]
+    fn stored_and_compressed_entries_read_back_the_same() {
+        let content = b"2DA V2.0\nrow,label\n0,Dog\n1,Dog\n2,Dog\n3,Dog\n".repeat(4);
+        let stored = erf_v22(0, &[("party.gda", &content)]);
+        let compressed = erf_v22(BIOWARE_ZLIB, &[("party.gda", &content)]);
+
+        let stored_erf = parse(stored.clone()).unwrap();
+        let compressed_erf = parse(compressed.clone()).unwrap();
+        assert!(!stored_erf.entry("party.gda").unwrap().is_compressed());
+        assert!(compressed_erf.entry("party.gda").unwrap().is_compressed());
+
+        for (erf, bytes) in [(stored_erf, stored), (compressed_erf, compressed)] {
+            let mut reader = Cursor::new(bytes);
+            assert_eq!(erf.get_resource("party.gda", &mut reader).unwrap(), content);
+
+            let mut streamed = Vec::new();
+            erf.resource_reader("party.gda", &mut reader)
+                .unwrap()
+                .read_to_end(&mut streamed)
+                .unwrap();
+            assert_eq!(streamed, content);
+        }
+    }
+
+    // Synthesized in the toolset's V2.2 layout, since an exported archive
+    // can't be shipped with the tests
+    #[test]
+    fn v22_reads_the_extended_header_before_the_toc() {
+        let mut bytes = erf_v22(BIOWARE_ZLIB, &[("a.gda", &[1; 100]), ("b.uti", b"x")]);
+        bytes[20..28].copy_from_slice(&[110, 0, 0, 0, 200, 0, 0, 0]);
+        bytes[36..40].copy_from_slice(&0x0BAD_F00Du32.to_le_bytes());
+        bytes[40..56].copy_from_slice(&[0xAB; 16]);
+
+        let erf = parse(bytes).unwrap();
+
+        assert_eq!(erf.version, ErfVersion::V22);
+        assert_eq!((erf.year, erf.day), (110, 200));
+        assert_eq!(erf.flags, BIOWARE_ZLIB);
+        assert_eq!(erf.compression, Compression::BiowareZlib);
+        assert_eq!(erf.encryption(), 0);
+        assert_eq!(erf.module_id, 0x0BAD_F00D);
+        assert_eq!(erf.password_digest, [0xAB; 16]);
+        assert_eq!(toc(&erf)[0].0, "a.gda");
+        // The TOC starts at 56 and holds two 76 byte entries
+        assert_eq!(toc(&erf)[0].1, 56 + 2 * 76);
+        assert_eq!(toc(&erf)[1].0, "b.uti");
+    }
+
+    #[test]
+    fn encrypted_v22_resources_are_refused() {
+        let mut bytes = erf_v22(0, &[("a.gda", b"secret")]);
+        bytes[32..36].copy_from_slice(&(1u32 << 4).to_le_bytes());
+
+        let erf = parse(bytes.clone()).unwrap();
+
+        assert_eq!(erf.encryption(), 1);
+        assert!(erf.get_resource("a.gda", &mut Cursor::new(bytes)).is_err());
+    }
+
+    fn toc(erf: &ErfFile) -> Vec<(&str, u32, u32, u32)> {
+        erf.entries()
+            .map(|e| (e.name.as_str(), e.offset, e.packed_length, e.length))
+            .collect()
+    }
+
+    #[test]
+    fn written_archive_reopens_with_the_same_toc() {
+        let mut v20 = erf_v20(&[("a.gda", b"first"), ("b.uti", b"second")]);
+        v20[28..32].copy_from_slice(&42u32.to_le_bytes());
+        let v22 = erf_v22(
+            BIOWARE_ZLIB,
+            &[("c.dlg", &[7; 300]), ("d.nss", b"void main() {}")],
+        );
+
+        for bytes in [v20, v22] {
+            let erf = parse(bytes.clone()).unwrap();
+            let mut written = Vec::new();
+            erf.write(&mut Cursor::new(&bytes), &mut written).unwrap();
+            let reopened = parse(written.clone()).unwrap();
+
+            assert_eq!(written, bytes);
+            assert_eq!(reopened.version, erf.version);
+            assert_eq!(reopened.flags, erf.flags);
+            assert_eq!(reopened.description_strref, erf.description_strref);
+            assert_eq!(toc(&reopened), toc(&erf));
+        }
+    }
+
+    #[test]
+    fn removing_a_resource_keeps_the_others_readable() {
+        let dir = TempDir::new().unwrap();
+        let path = write(
+            dir.path(),
+            "patch.erf",
+            &erf_v22(
+                BIOWARE_ZLIB,
+                &[
+                    ("a.gda", &[1; 200]),
+                    ("b.uti", &[2; 200]),
+                    ("c.dlg", &[3; 200]),
+                ],
+            ),
+        );
+
+        ErfFile::remove_resource(&path, "B.UTI").unwrap();
+
+        let erf = ErfFile::open(&path).unwrap();
+        let names: Vec<_> = erf.entries().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["a.gda", "c.dlg"]);
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(erf.get_resource("c.dlg", &mut file).unwrap(), [3; 200]);
+        assert!(!dir.path().join("patch.erf.tmp").exists());
+    }
+
+    #[test]
+    fn v30_names_resolve_through_the_string_table() {
+        let bytes = erf_v30(0, &[("gen00fl_dog.dlg", b"woof"), ("Armor.UTI", b"plate")]);
+
+        let erf = parse(bytes.clone()).unwrap();
+
+        assert_eq!(erf.version, ErfVersion::V30);
+        let names: Vec<_> = erf.entries().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["gen00fl_dog.dlg", "Armor.UTI"]);
+        assert_eq!(erf.entry("armor.uti").unwrap().restype, ResourceType::Gff);
+        assert_eq!(
+            erf.get_resource("GEN00FL_DOG.DLG", &mut Cursor::new(bytes))
+                .unwrap(),
+            b"woof"
+        );
+    }
+
+    #[test]
+    fn v30_name_offset_outside_the_string_table_is_rejected() {
+        let mut bytes = erf_v30(0, &[("a.gda", b"x")]);
+        // The first TOC entry follows the 48 byte header and 6 byte string table
+        bytes[54..58].copy_from_slice(&100u32.to_le_bytes());
+
+        assert!(matches!(
+            parse(bytes),
+            Err(ErfError::InvalidResourceName(_))
+        ));
+    }
+
+    fn entry(packed_length: u32, length: u32) -> ErfTocEntry {
+        ErfTocEntry {
+            name: "a.gda".to_string(),
+            offset: 0,
+            packed_length,
+            length,
+            restype: ResourceType::Table,
+        }
+    }
+
+    #[test]
+    fn compression_ratio_compares_packed_to_unpacked_size() {
+        let compressed = entry(250, 1000);
+        let stored = entry(1000, 1000);
+
+        assert!(compressed.is_compressed());
+        assert_eq!(compressed.compression_ratio(), Some(0.25));
+        assert!(!stored.is_compressed());
+        assert_eq!(stored.compression_ratio(), Some(1.0));
+        assert_eq!(entry(0, 0).compression_ratio(), None);
+    }
+
+    fn gff(file_type: &[u8; 4]) -> Vec<u8> {
+        [GFF_SIGNATURE.as_slice(), b"PC  ", file_type, &[0; 8]].concat()
+    }
+
+    #[test]
+    fn gff_declaring_another_type_is_flagged() {
+        let bytes = erf_v20(&[
+            ("darkspawn.utc", &gff(b"GFF ")),
+            ("alistair.utc", &gff(b"UTC ")),
+            ("notes.txt", b"not a gff resource at all"),
+        ]);
+        let erf = parse(bytes.clone()).unwrap();
+
+        let issues = erf.check_types(&mut Cursor::new(bytes));
+
+        assert_eq!(
+            issues,
+            [ErfIssue::TypeMismatch {
+                name: "darkspawn.utc".to_string(),
+                content_type: "gff".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn truncated_description_strref_is_not_fatal() {
+        let mut bytes = erf_v20(&[]);
+        bytes.truncate(30);
+
+        let erf = parse(bytes).unwrap();
+        assert_eq!(erf.description_strref, None);
+        assert!(erf.toc.is_empty());
+    }
+
+    #[test]
+    fn a_corrupt_file_count_fails_without_allocating_for_it() {
+        for mut bytes in [erf_v20(&[("a.uti", b"a")]), erf_v22(0, &[("a.uti", b"a")])] {
+            bytes[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(matches!(parse(bytes), Err(ErfError::Io(_))));
+        }
+    }
+
+    #[test]
+    fn padded_names_normalize_to_one_key() {
+        assert_eq!(normalize_name("armor.gda", 0).unwrap(), "armor.gda");
+        assert_eq!(normalize_name("armor.gda\0\0", 0).unwrap(), "armor.gda");
+        assert_eq!(normalize_name("armor.gda  \0junk", 0).unwrap(), "armor.gda");
+    }
+
+    #[test]
+    fn empty_or_control_names_are_invalid() {
+        assert!(matches!(
+            normalize_name("\0\0", 3),
+            Err(ErfError::InvalidResourceName(_))
+        ));
+        assert!(matches!(
+            normalize_name("arm\u{1}or.gda", 3),
+            Err(ErfError::InvalidResourceName(_))
+        ));
+    }
+
+    #[test]
+    fn c_strings_stop_at_the_first_null() {
+        let table = b"armor.gda\0\0sword.uti";
+        assert_eq!(read_c_string(table, 0).as_deref(), Some("armor.gda"));
+        assert_eq!(read_c_string(table, 11).as_deref(), Some("sword.uti"));
+        assert_eq!(read_c_string(table, 10), None);
+        assert_eq!(read_c_string(table, 100), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Builders for the files tests scan: loose files laid out like a BioWare
+//! folder and small archives assembled in memory.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use flate2::{Compression, write::DeflateEncoder};
+
+/// Archive flags selecting BioWare's zlib variant for every resource.
+pub const BIOWARE_ZLIB: u32 = 1 << 29;
+
+/// Writes `contents` at `relative` under `root`, creating folders on the way.
+pub fn write(root: &Path, relative: &str, contents: &[u8]) -> PathBuf {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().expect("fixture paths have a parent"))
+        .expect("create fixture folder");
+    fs::write(&path, contents).expect("write fixture file");
+    path
+}
+
+/// Assembles a V2.0 ERF archive holding `resources` stored as-is.
+pub fn erf_v20(resources: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut header = utf16("ERF V2.0");
+    header.extend((resources.len() as u32).to_le_bytes());
+    // Build year and day, then no description
+    header.extend(0u32.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend(u32::MAX.to_le_bytes());
+
+    let toc_size = resources.len() * 72;
+    let mut offset = header.len() + toc_size;
+    let mut toc = Vec::with_capacity(toc_size);
+    for (name, data) in resources {
+        let mut field = utf16(name);
+        field.resize(64, 0);
+        toc.extend(field);
+        toc.extend((offset as u32).to_le_bytes());
+        toc.extend((data.len() as u32).to_le_bytes());
+        offset += data.len();
+    }
+
+    let mut archive = header;
+    archive.extend(toc);
+    for (_, data) in resources {
+        archive.extend_from_slice(data);
+    }
+    archive
+}
+
+/// Assembles a V2.2 ERF archive, packing `resources` when `flags` ask for
+/// [`BIOWARE_ZLIB`] compression.
+pub fn erf_v22(flags: u32, resources: &[(&str, &[u8])]) -> Vec<u8> {
+    let packed: Vec<_> = resources
+        .iter()
+        .map(|(_, data)| pack(flags, data))
+        .collect();
+
+    let mut header = utf16("ERF V2.2");
+    header.extend((resources.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend(u32::MAX.to_le_bytes());
+    // Flags, module id and an empty password digest
+    header.extend(flags.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend([0; 16]);
+
+    let mut offset = header.len() + resources.len() * 76;
+    let mut archive = header;
+    for ((name, data), packed) in resources.iter().zip(&packed) {
+        let mut field = utf16(name);
+        field.resize(64, 0);
+        archive.extend(field);
+        archive.extend((offset as u32).to_le_bytes());
+        archive.extend((packed.len() as u32).to_le_bytes());
+        archive.extend((data.len() as u32).to_le_bytes());
+        offset += packed.len();
+    }
+    archive.extend(packed.concat());
+    archive
+}
+
+/// Assembles a V3.0 ERF archive with named entries, packing `resources` when
+/// `flags` ask for [`BIOWARE_ZLIB`] compression. Name and type hashes are
+/// left at zero since the reader goes by the string table.
+pub fn erf_v30(flags: u32, resources: &[(&str, &[u8])]) -> Vec<u8> {
+    let packed: Vec<_> = resources
+        .iter()
+        .map(|(_, data)| pack(flags, data))
+        .collect();
+
+    let mut strings = Vec::new();
+    let mut name_offsets = Vec::new();
+    for (name, _) in resources {
+        name_offsets.push(strings.len() as u32);
+        strings.extend(name.as_bytes());
+        strings.push(0);
+    }
+
+    let mut archive = utf16("ERF V3.0");
+    archive.extend((strings.len() as u32).to_le_bytes());
+    archive.extend((resources.len() as u32).to_le_bytes());
+    archive.extend(flags.to_le_bytes());
+    archive.extend(0u32.to_le_bytes());
+    archive.extend([0; 16]);
+    archive.extend(&strings);
+
+    let mut offset = archive.len() + resources.len() * 28;
+    for (((_, data), packed), name_offset) in resources.iter().zip(&packed).zip(name_offsets) {
+        archive.extend(name_offset.to_le_bytes());
+        archive.extend(0u64.to_le_bytes());
+        archive.extend(0u32.to_le_bytes());
+        archive.extend((offset as u32).to_le_bytes());
+        archive.extend((packed.len() as u32).to_le_bytes());
+        archive.extend((data.len() as u32).to_le_bytes());
+        offset += packed.len();
+    }
+    archive.extend(packed.concat());
+    archive
+}
+
+/// Raw deflate behind a window size byte, as BioWare's zlib variant stores it.
+fn pack(flags: u32, data: &[u8]) -> Vec<u8> {
+    if flags >> 29 != BIOWARE_ZLIB >> 29 {
+        return data.to_vec();
+    }
+
+    let mut encoder = DeflateEncoder::new(vec![0x0F], Compression::best());
+    encoder.write_all(data).expect("compress fixture resource");
+    encoder.finish().expect("compress fixture resource")
+}
+
+/// Assembles an uncompressed (V0.2) talk table holding `strings` by id.
+///
+/// The GFF has a root struct with the string list field and one list item
+/// struct with the id and text fields, the smallest layout the reader walks.
+pub fn tlk(strings: &[(u32, &str)]) -> Vec<u8> {
+    const STRUCTS: usize = 28;
+    const FIELDS: usize = STRUCTS + 2 * 16;
+    const DATA: usize = FIELDS + 3 * 12;
+
+    let mut tlk = b"GFF V4.0PC  TLK V0.2".to_vec();
+    tlk.extend(2u32.to_le_bytes());
+    tlk.extend((DATA as u32).to_le_bytes());
+    // Struct definitions: type, field count, first field, instance size
+    for [field_count, first_field, size] in [[1, FIELDS, 4], [2, FIELDS + 12, 8]] {
+        tlk.extend(0u32.to_le_bytes());
+        for value in [field_count, first_field, size] {
+            tlk.extend((value as u32).to_le_bytes());
+        }
+    }
+    // Field definitions: label, type (the item struct for the list), offset
+    for [label, type_id, offset] in [[19001, 1, 0], [19002, 4, 0], [19003, 14, 4]] {
+        for value in [label, type_id, offset] {
+            tlk.extend((value as u32).to_le_bytes());
+        }
+    }
+
+    let list = 4;
+    let mut text_offset = list + 4 + strings.len() * 8;
+    let mut data = (list as u32).to_le_bytes().to_vec();
+    let mut texts = Vec::new();
+    data.extend((strings.len() as u32).to_le_bytes());
+    for (id, text) in strings {
+        data.extend(id.to_le_bytes());
+        data.extend((text_offset as u32).to_le_bytes());
+
+        let encoded = utf16(text);
+        texts.extend((text.encode_utf16().count() as u32).to_le_bytes());
+        text_offset += 4 + encoded.len();
+        texts.extend(encoded);
+    }
+
+    tlk.extend(data);
+    tlk.extend(texts);
+    tlk
+}
+
+fn utf16(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
@@ -0,0 +1,299 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::scanner::{Conflicts, SourceKind, is_core_archive};
+
+/// Why a source wins its conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinReason {
+    /// The only loose file, which beats packed copies such as the one in `archive`.
+    LooseOverPacked { archive: String },
+    /// The user override folder in `packages`, which beats add-in override folders.
+    UserOverride,
+    /// One of several loose files, loaded last because of its `folder`.
+    LastLoose { folder: String },
+    /// An add-in archive, which beats the base game's packed copies.
+    AddinOverBase { archive: String },
+    /// Every copy is packed and this archive is loaded last.
+    LastPacked { archive: String },
+}
+
+impl WinReason {
+    pub fn describe(&self) -> String {
+        match self {
+            WinReason::LooseOverPacked { archive } => format!(
+                "Wins because it's a loose override file, which beats the packed copy in {archive}"
+            ),
+            WinReason::UserOverride => {
+                "Wins because packages override folders are loaded after add-in override folders"
+                    .to_string()
+            }
+            WinReason::LastLoose { folder } => {
+                format!("Wins because it's in {folder}, loaded after the other loose files")
+            }
+            WinReason::AddinOverBase { archive } => {
+                format!(
+                    "Wins because {archive} belongs to an add-in, which beats base game archives"
+                )
+            }
+            WinReason::LastPacked { archive } => {
+                format!("Wins because {archive} is loaded after the other archives")
+            }
+        }
+    }
+}
+
+/// Where a source sits in the game's precedence, later variants win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Layer {
+    /// Packed in a `packages` archive, i.e. the base game and DLC installed there.
+    BasePacked,
+    AddinPacked,
+    /// Loose in an add-in's `core/override` or `module/override`.
+    AddinOverride,
+    /// Loose in a `packages/<package>/override` folder.
+    UserOverride,
+}
+
+impl Layer {
+    /// Tells the layer from the path alone, using its last `packages` or
+    /// `AddIns` component so the folders above the BioWare folder don't matter.
+    fn of(path: &Path) -> Self {
+        let is_addin = path
+            .iter()
+            .rev()
+            .find_map(|component| {
+                let component = component.to_string_lossy();
+                if component.eq_ignore_ascii_case("addins") {
+                    Some(true)
+                } else if component.eq_ignore_ascii_case("packages") {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(false);
+
+        match (SourceKind::of(path), is_addin) {
+            (SourceKind::Loose, true) => Layer::AddinOverride,
+            (SourceKind::Loose, false) => Layer::UserOverride,
+            (SourceKind::Archive, true) => Layer::AddinPacked,
+            (SourceKind::Archive, false) => Layer::BasePacked,
+        }
+    }
+}
+
+/// Returns the index of the source the game will load for a conflict.
+///
+/// Loose override files beat packed resources, and the `packages` override
+/// folders beat add-in override folders. Add-in archives beat the base game's
+/// archives. Among sources in the same layer the last one in path order wins.
+pub fn resolve_winner(paths: &[PathBuf]) -> Option<usize> {
+    explain_winner(paths).map(|(index, _)| index)
+}
+
+/// Like [`resolve_winner`], along with the precedence rule that decided it.
+pub fn explain_winner(paths: &[PathBuf]) -> Option<(usize, WinReason)> {
+    let mut ranked: Vec<_> = paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| (Layer::of(path), index))
+        .collect();
+    ranked.sort();
+
+    let &(layer, index) = ranked.last()?;
+    let runner_up = ranked.iter().rev().nth(1).map(|&(layer, _)| layer);
+    let winner = &paths[index];
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned())
+    };
+
+    let reason = match (layer, runner_up) {
+        (
+            Layer::UserOverride | Layer::AddinOverride,
+            Some(Layer::BasePacked | Layer::AddinPacked),
+        )
+        | (Layer::UserOverride | Layer::AddinOverride, None) => WinReason::LooseOverPacked {
+            archive: paths
+                .iter()
+                .find(|p| SourceKind::of(p) == SourceKind::Archive)
+                .map_or_else(String::new, |p| file_name(p)),
+        },
+        (Layer::UserOverride, Some(Layer::AddinOverride)) => WinReason::UserOverride,
+        (Layer::UserOverride | Layer::AddinOverride, Some(_)) => WinReason::LastLoose {
+            folder: winner.parent().map_or_else(String::new, |parent| {
+                format!("folder {}", file_name(parent))
+            }),
+        },
+        (Layer::AddinPacked, Some(Layer::BasePacked)) => WinReason::AddinOverBase {
+            archive: file_name(winner),
+        },
+        (Layer::BasePacked | Layer::AddinPacked, _) => WinReason::LastPacked {
+            archive: file_name(winner),
+        },
+    };
+
+    Some((index, reason))
+}
+
+/// Absolute location of the winning source, for use outside the app.
+///
+/// Packed winners are written as `<archive path>::<entry name>`.
+pub fn winner_location(key: &str, paths: &[PathBuf], conflicts: &Conflicts) -> Option<String> {
+    let winner = &paths[resolve_winner(paths)?];
+    // Clean Windows extended path prefix
+    let path = winner.display().to_string().replace(r"\\?\", "");
+
+    Some(match SourceKind::of(winner) {
+        SourceKind::Loose => path,
+        SourceKind::Archive => format!("{path}::{}", conflicts.entry_name(key, winner)),
+    })
+}
+
+/// Keys of conflicts with a preferred provider that isn't the resolved winner.
+pub fn unexpected_winners(
+    conflicts: &Conflicts,
+    preferred: &HashMap<String, PathBuf>,
+) -> HashSet<String> {
+    preferred
+        .iter()
+        .filter_map(|(key, preferred_path)| {
+            let paths = conflicts.get(key)?;
+            let winner = resolve_winner(paths).map(|index| &paths[index]);
+            (winner != Some(preferred_path)).then(|| key.clone())
+        })
+        .collect()
+}
+
+/// Whether a loose override replaces a resource packed in a base-game archive.
+///
+/// These are riskier than mod-vs-mod conflicts, since the replaced content is
+/// what every other mod was built against.
+pub fn shadows_core(paths: &[PathBuf], bioware_dir: &Path) -> bool {
+    paths.iter().any(|p| SourceKind::of(p) == SourceKind::Loose)
+        && paths.iter().any(|p| is_core_archive(p, bioware_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explains_why_the_winner_wins() {
+        let source = |relative: &str| Path::new("/BioWare").join(relative);
+        let core = source("packages/core/data/core.erf");
+        let addin_erf = source("AddIns/modb/core/data/modb.erf");
+        let loose = source("packages/core/override/moda/a.uti");
+        let addin_loose = source("AddIns/modb/core/override/a.uti");
+        let patch = source("packages/core/override/99_patch/a.uti");
+
+        assert_eq!(
+            explain_winner(&[core.clone(), loose.clone()]),
+            Some((
+                1,
+                WinReason::LooseOverPacked {
+                    archive: "core.erf".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            explain_winner(&[loose.clone(), addin_loose.clone()]),
+            Some((0, WinReason::UserOverride))
+        );
+        assert_eq!(
+            explain_winner(&[loose.clone(), patch]),
+            Some((
+                1,
+                WinReason::LastLoose {
+                    folder: "folder 99_patch".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            explain_winner(&[addin_erf, core.clone()]),
+            Some((
+                0,
+                WinReason::AddinOverBase {
+                    archive: "modb.erf".to_string()
+                }
+            ))
+        );
+        assert_eq!(
+            explain_winner(&[core.clone(), source("packages/core/data/patch.erf")]),
+            Some((
+                1,
+                WinReason::LastPacked {
+                    archive: "patch.erf".to_string()
+                }
+            ))
+        );
+        assert_eq!(explain_winner(&[]), None);
+    }
+
+    #[test]
+    fn flags_a_winner_that_is_not_the_preferred_provider() {
+        let loose = PathBuf::from("/BioWare/packages/core/override/moda/a.uti");
+        let packed = PathBuf::from("/BioWare/AddIns/modb/core/data/modb.erf");
+        let other = PathBuf::from("/BioWare/packages/core/override/modc/b.uti");
+        let conflicts: Conflicts = [
+            ("a.uti".to_string(), vec![packed.clone(), loose]),
+            ("b.uti".to_string(), vec![packed.clone(), other.clone()]),
+        ]
+        .into_iter()
+        .collect();
+        let preferred =
+            HashMap::from([("a.uti".to_string(), packed), ("b.uti".to_string(), other)]);
+
+        let unexpected = unexpected_winners(&conflicts, &preferred);
+
+        assert_eq!(unexpected, HashSet::from(["a.uti".to_string()]));
+    }
+
+    #[test]
+    fn only_overrides_of_base_game_archives_shadow_core() {
+        let bioware_dir = Path::new("/BioWare");
+        let source = |relative: &str| bioware_dir.join(relative);
+        let loose = source("packages/core/override/moda/a.uti");
+
+        assert!(shadows_core(
+            &[source("packages/core/data/core.erf"), loose.clone()],
+            bioware_dir
+        ));
+        assert!(shadows_core(
+            &[source("packages/core/data/globalvfx.rim"), loose.clone()],
+            bioware_dir
+        ));
+        assert!(!shadows_core(
+            &[source("AddIns/modb/core/data/modb.erf"), loose.clone()],
+            bioware_dir
+        ));
+        assert!(!shadows_core(
+            &[source("packages/core/override/modb/a.uti"), loose],
+            bioware_dir
+        ));
+    }
+
+    #[test]
+    fn winner_location_points_at_the_loaded_file() {
+        let loose = PathBuf::from("/BioWare/packages/core/override/moda/a.uti");
+        let packed = PathBuf::from("/BioWare/AddIns/modb/core/data/modb.erf");
+        let base = PathBuf::from("/BioWare/packages/core/data/core.erf");
+        let mut conflicts = Conflicts::new();
+        conflicts.add_source("a.uti".to_string(), packed.clone(), "a.uti");
+        conflicts.add_source("a.uti".to_string(), loose.clone(), "a.uti");
+        conflicts.add_source("b.uti [fr-fr]".to_string(), base, "b.uti");
+        conflicts.add_source("b.uti [fr-fr]".to_string(), packed.clone(), "b_fr.uti");
+
+        assert_eq!(
+            winner_location("a.uti", &conflicts["a.uti"], &conflicts),
+            Some(loose.display().to_string())
+        );
+        assert_eq!(
+            winner_location("b.uti [fr-fr]", &conflicts["b.uti [fr-fr]"], &conflicts),
+            Some(format!("{}::b_fr.uti", packed.display()))
+        );
+    }
+}
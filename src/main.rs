@@ -1,19 +1,150 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod backups;
+mod cache;
+mod cli;
+mod compare;
 mod config;
+mod content;
+mod decisions;
+mod diff;
+mod display;
+mod duplicates;
 mod erf;
+#[cfg(test)]
+mod fixtures;
+mod loadorder;
+mod manifest;
+mod pairing;
+mod palette;
+mod plan;
+mod preview;
+mod profiles;
+mod queue;
+mod resolution;
+mod restype;
+mod rim;
+mod safety;
 mod scanner;
+mod snapshots;
+mod summary;
+mod tlk;
 mod utils;
+mod verify;
+mod view;
+
+use std::process::ExitCode;
+
+use cli::{HeadlessScan, OutputFormat, run_headless};
+use config::AppConfig;
+use view::ResultsFilter;
+
+/// Options given on the command line.
+#[derive(Debug, Default)]
+struct Args {
+    filter: ResultsFilter,
+    read_only: bool,
+    /// Scan and print instead of opening the window.
+    headless: Option<HeadlessScan>,
+    format: OutputFormat,
+}
+
+fn main() -> ExitCode {
+    let args = parse_args(std::env::args().skip(1));
+
+    if let Some(scan) = args.headless {
+        return run_headless(scan);
+    }
+
+    let mut viewport = egui::ViewportBuilder::default();
+    if let Some(window) = AppConfig::load().0.window {
+        viewport = viewport.with_inner_size(window.size);
+        if let Some(position) = window.position {
+            viewport = viewport.with_position(position);
+        }
+    }
 
-fn main() {
     let options = eframe::NativeOptions {
+        viewport,
         ..Default::default()
     };
 
     let _ = eframe::run_native(
         "DA:O Conflict Scanner",
         options,
-        Box::new(|cc| Ok(Box::new(app::App::new(cc)))),
+        Box::new(|cc| Ok(Box::new(app::App::new(cc, args.filter, args.read_only)))),
     );
+
+    ExitCode::SUCCESS
+}
+
+/// Reads `--filter <query>`, `--type <ext>`, `--read-only`, `--scan [dir]` and
+/// `--format <text|json>`, ignoring anything else.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Args {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--filter" => parsed.filter.query = args.next().unwrap_or_default(),
+            "--type" => {
+                parsed.filter.resource_type = args
+                    .next()
+                    .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                    .filter(|ext| !ext.is_empty())
+            }
+            "--read-only" => parsed.read_only = true,
+            "--scan" => {
+                let dir = args.next_if(|arg| !arg.starts_with("--")).map(Into::into);
+                parsed.headless = Some(HeadlessScan {
+                    dir,
+                    ..Default::default()
+                });
+            }
+            "--format" => {
+                if let Some(format) = args.next().as_deref().and_then(OutputFormat::parse) {
+                    parsed.format = format;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(headless) = &mut parsed.headless {
+        headless.format = parsed.format;
+    }
+
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn filter_arguments_set_the_initial_filter() {
+        let args = parse(&["--filter", "gen00fl", "--type", ".DLG", "--unknown"]);
+
+        assert_eq!(
+            args.filter,
+            ResultsFilter {
+                query: "gen00fl".to_string(),
+                resource_type: Some("dlg".to_string()),
+                ..Default::default()
+            }
+        );
+        assert!(!args.read_only);
+        assert!(args.headless.is_none());
+    }
+
+    #[test]
+    fn missing_values_leave_the_filter_empty() {
+        assert_eq!(parse(&["--type", ""]).filter, ResultsFilter::default());
+        assert_eq!(parse(&["--filter"]).filter, ResultsFilter::default());
+    }
 }
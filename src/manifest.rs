@@ -0,0 +1,127 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result as AnyhowResult, anyhow};
+use roxmltree::{Document, Node};
+
+pub const MANIFEST_FILE: &str = "manifest.xml";
+
+/// The human-friendly fields of an add-in's `Manifest.xml`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddinManifest {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+}
+
+impl AddinManifest {
+    pub fn open<P: AsRef<Path>>(path: P) -> AnyhowResult<Self> {
+        let path_ref = path.as_ref();
+        let contents = fs::read_to_string(path_ref)
+            .with_context(|| format!("Failed to read manifest at {}", path_ref.display()))?;
+
+        Self::parse(&contents)
+            .with_context(|| format!("Failed to parse manifest at {}", path_ref.display()))
+    }
+
+    pub fn parse(xml: &str) -> AnyhowResult<Self> {
+        // Some toolset exports start with a BOM, which roxmltree rejects
+        let document = Document::parse(xml.trim_start_matches('\u{feff}'))?;
+
+        let item = document
+            .descendants()
+            .find(|n| n.has_tag_name("AddInItem"))
+            .unwrap_or_else(|| document.root_element());
+
+        let manifest = Self {
+            title: localized_child(item, "Title").or_else(|| attribute(item, "Name")),
+            version: attribute(item, "Version").or_else(|| child_text(item, "Version")),
+            author: localized_child(item, "Publisher").or_else(|| child_text(item, "Author")),
+        };
+
+        if manifest.title.is_none() {
+            return Err(anyhow!("Manifest has no add-in title"));
+        }
+
+        Ok(manifest)
+    }
+
+    /// Formats the manifest as e.g. "Return to Ostagar v1.2 by BioWare".
+    pub fn label(&self) -> String {
+        let mut label = self.title.clone().unwrap_or_default();
+
+        if let Some(version) = &self.version {
+            label.push_str(&format!(" v{version}"));
+        }
+        if let Some(author) = &self.author {
+            label.push_str(&format!(" by {author}"));
+        }
+
+        label
+    }
+}
+
+fn attribute(node: Node, name: &str) -> Option<String> {
+    node.attribute(name)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+fn child_text(node: Node, tag: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name(tag))
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Reads a `<Tag><LocalizedString Value=".."/></Tag>` field, falling back to plain text.
+fn localized_child(node: Node, tag: &str) -> Option<String> {
+    let child = node.children().find(|n| n.has_tag_name(tag))?;
+
+    child
+        .descendants()
+        .filter(|n| n.has_tag_name("LocalizedString"))
+        .find_map(|n| attribute(n, "Value").or_else(|| attribute(n, "DefaultText")))
+        .or_else(|| child_text(node, tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<AddInsList>
+  <AddInItem UID="dao_prc_cp_2" Name="dao_prc_cp_2" Version="1.2">
+    <Title>
+      <LocalizedString Value="Return to Ostagar" />
+    </Title>
+    <Publisher>
+      <LocalizedString Value="BioWare" />
+    </Publisher>
+  </AddInItem>
+</AddInsList>
+"#;
+
+    #[test]
+    fn parses_a_minimal_manifest() {
+        let manifest = AddinManifest::parse(MINIMAL).unwrap();
+
+        assert_eq!(
+            manifest,
+            AddinManifest {
+                title: Some("Return to Ostagar".to_string()),
+                version: Some("1.2".to_string()),
+                author: Some("BioWare".to_string()),
+            }
+        );
+        assert_eq!(manifest.label(), "Return to Ostagar v1.2 by BioWare");
+    }
+
+    #[test]
+    fn manifest_without_a_title_is_rejected() {
+        assert!(AddinManifest::parse("<AddInsList><AddInItem /></AddInsList>").is_err());
+        assert!(AddinManifest::parse("not xml").is_err());
+    }
+}
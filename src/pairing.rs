@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scanner::{Conflicts, resource_name},
+    summary::resource_type,
+};
+
+/// Extensions of a script source and the compiled file built from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptPair {
+    pub source: String,
+    pub compiled: String,
+}
+
+impl Default for ScriptPair {
+    fn default() -> Self {
+        Self {
+            source: "nss".into(),
+            compiled: "ncs".into(),
+        }
+    }
+}
+
+/// Keys of script conflicts whose counterpart isn't overridden as well.
+///
+/// When a mod replaces only the compiled script (or only its source), the two
+/// can drift apart and the game runs code that doesn't match the source.
+pub fn unpaired_scripts(conflicts: &Conflicts, pairs: &[ScriptPair]) -> HashSet<String> {
+    // Loose file names keep their on-disk case, so counterparts match case-insensitively
+    let keys: HashSet<_> = conflicts.keys().map(|key| key.to_lowercase()).collect();
+
+    conflicts
+        .keys()
+        .filter(|key| {
+            let extension = resource_type(key);
+            pairs.iter().any(|pair| {
+                let counterpart = if extension == pair.source {
+                    &pair.compiled
+                } else if extension == pair.compiled {
+                    &pair.source
+                } else {
+                    return false;
+                };
+
+                !keys.contains(&counterpart_key(key, counterpart).to_lowercase())
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// The key of the same resource with another extension, keeping any language suffix.
+fn counterpart_key(key: &str, extension: &str) -> String {
+    let name = resource_name(key);
+    let suffix = &key[name.len()..];
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+
+    format!("{stem}.{extension}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn conflicts(keys: &[&str]) -> Conflicts {
+        keys.iter()
+            .map(|key| {
+                let sources = ["mod_a", "mod_b"].map(|folder| PathBuf::from(folder).join(key));
+                (key.to_string(), sources.to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compiled_script_without_its_source_is_flagged() {
+        let conflicts = conflicts(&[
+            "gen00pt_party.ncs",
+            "sp_paired.ncs",
+            "SP_Paired.nss",
+            "armor.uti",
+        ]);
+
+        assert_eq!(
+            unpaired_scripts(&conflicts, &[ScriptPair::default()]),
+            HashSet::from(["gen00pt_party.ncs".to_string()])
+        );
+    }
+
+    #[test]
+    fn language_suffix_is_kept_when_looking_for_the_counterpart() {
+        let conflicts = conflicts(&["line.nss [fr-fr]", "line.ncs [fr-fr]", "line.ncs [de-de]"]);
+
+        assert_eq!(
+            unpaired_scripts(&conflicts, &[ScriptPair::default()]),
+            HashSet::from(["line.ncs [de-de]".to_string()])
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::cmp::Reverse;
+
+/// Scores how well `query` fuzzy-matches `candidate`, higher is better.
+///
+/// Every query character must appear in the candidate in order (case-insensitive).
+/// Consecutive matches and matches at the start of a word score extra, so word
+/// prefixes and initials rank above scattered matches.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut query_index = 0;
+    let mut previous_matched = false;
+    let mut previous_char = ' ';
+
+    for c in candidate.chars().flat_map(char::to_lowercase) {
+        if query_index < query.len() && c == query[query_index] {
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if !previous_char.is_alphanumeric() {
+                score += 6;
+            }
+
+            query_index += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+
+        previous_char = c;
+    }
+
+    (query_index == query.len()).then_some(score)
+}
+
+/// Returns the indices of `names` matching `query`, best match first.
+pub fn rank_matches<'a>(names: impl IntoIterator<Item = &'a str>, query: &str) -> Vec<usize> {
+    let mut matches: Vec<_> = names
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, name)| fuzzy_score(query, name).map(|score| (index, score)))
+        .collect();
+
+    // Stable sort keeps registry order for equal scores
+    matches.sort_by_key(|&(_, score)| Reverse(score));
+    matches.into_iter().map(|(index, _)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMANDS: &[&str] = &[
+        "Scan",
+        "Expand all conflicts",
+        "Collapse all conflicts",
+        "Export decision log",
+        "Clear all preferred providers",
+        "Toggle only actionable conflicts",
+    ];
+
+    fn best(query: &str) -> Option<&'static str> {
+        rank_matches(COMMANDS.iter().copied(), query)
+            .first()
+            .map(|&index| COMMANDS[index])
+    }
+
+    #[test]
+    fn initials_surface_the_matching_command() {
+        assert_eq!(best("eac"), Some("Expand all conflicts"));
+        assert_eq!(best("cpp"), Some("Clear all preferred providers"));
+    }
+
+    #[test]
+    fn word_prefixes_beat_scattered_matches() {
+        assert_eq!(best("collapse"), Some("Collapse all conflicts"));
+        assert_eq!(best("EXPORT"), Some("Export decision log"));
+    }
+
+    #[test]
+    fn out_of_order_queries_match_nothing() {
+        assert_eq!(fuzzy_score("gol", "Export decision log"), None);
+        assert!(rank_matches(COMMANDS.iter().copied(), "zzz").is_empty());
+    }
+
+    #[test]
+    fn an_empty_query_keeps_registry_order() {
+        assert_eq!(
+            rank_matches(COMMANDS.iter().copied(), "  "),
+            (0..COMMANDS.len()).collect::<Vec<_>>()
+        );
+    }
+}
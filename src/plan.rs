@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{loadorder::resolve_winner, scanner::SourceKind, view::ConflictEntry};
+
+pub const PLAN_FILE: &str = "resolution_plan.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Delete,
+    Disable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedOperation {
+    /// The conflict the file is a source of.
+    pub key: String,
+    pub path: PathBuf,
+    pub action: PlanAction,
+}
+
+/// File operations to review, share and apply later as one batch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionPlan {
+    /// Unix timestamp of when the plan was generated.
+    pub created_at: u64,
+    pub operations: Vec<PlannedOperation>,
+}
+
+impl ResolutionPlan {
+    /// Plans disabling every loose source that isn't kept, keeping the
+    /// preferred provider where one is set and the resolved winner otherwise.
+    ///
+    /// Disabling is reversible, operations can be switched to `delete` by
+    /// editing the exported plan.
+    pub fn from_conflicts<'a>(
+        conflicts: impl IntoIterator<Item = &'a ConflictEntry>,
+        preferred: &HashMap<String, PathBuf>,
+        created_at: u64,
+    ) -> Self {
+        let mut operations = Vec::new();
+
+        for (key, paths) in conflicts {
+            let kept = preferred
+                .get(key)
+                .filter(|p| paths.contains(p))
+                .or_else(|| resolve_winner(paths).map(|index| &paths[index]));
+
+            operations.extend(
+                paths
+                    .iter()
+                    .filter(|path| Some(*path) != kept && SourceKind::of(path) == SourceKind::Loose)
+                    .map(|path| PlannedOperation {
+                        key: key.clone(),
+                        path: path.clone(),
+                        action: PlanAction::Disable,
+                    }),
+            );
+        }
+
+        Self {
+            created_at,
+            operations,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Operations whose file no longer exists, which can't be applied.
+    pub fn missing_files(&self, exists: impl Fn(&Path) -> bool) -> Vec<&PlannedOperation> {
+        self.operations
+            .iter()
+            .filter(|operation| !exists(&operation.path))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn plan_round_trips_and_keeps_the_winner_or_preferred_source() {
+        let source = |relative: &str| PathBuf::from("/BioWare").join(relative);
+        let conflicts = [
+            (
+                "a.uti".to_string(),
+                vec![
+                    source("packages/core/data/core.erf"),
+                    source("packages/core/override/mod_a/a.uti"),
+                    source("packages/core/override/mod_b/a.uti"),
+                ],
+            ),
+            (
+                "b.gda".to_string(),
+                vec![
+                    source("packages/core/override/mod_a/b.gda"),
+                    source("packages/core/override/mod_b/b.gda"),
+                ],
+            ),
+        ];
+        // mod_b would win b.gda, but the user prefers mod_a's copy
+        let preferred = HashMap::from([(
+            "b.gda".to_string(),
+            source("packages/core/override/mod_a/b.gda"),
+        )]);
+
+        let plan = ResolutionPlan::from_conflicts(&conflicts, &preferred, 42);
+        let restored = ResolutionPlan::from_json(&plan.to_json().unwrap()).unwrap();
+
+        assert_eq!(restored, plan);
+        let planned: Vec<_> = restored
+            .operations
+            .iter()
+            .map(|operation| {
+                (
+                    operation.key.as_str(),
+                    operation.path.clone(),
+                    operation.action,
+                )
+            })
+            .collect();
+        assert_eq!(
+            planned,
+            [
+                (
+                    "a.uti",
+                    source("packages/core/override/mod_a/a.uti"),
+                    PlanAction::Disable
+                ),
+                (
+                    "b.gda",
+                    source("packages/core/override/mod_b/b.gda"),
+                    PlanAction::Disable
+                ),
+            ]
+        );
+
+        // Only the first file is still on the mocked filesystem
+        let existing = HashSet::from([source("packages/core/override/mod_a/a.uti")]);
+        let missing = restored.missing_files(|path| existing.contains(path));
+        assert_eq!(missing, [&restored.operations[1]]);
+    }
+}
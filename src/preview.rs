@@ -0,0 +1,120 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result as AnyhowResult};
+
+use crate::{content::read_source, scanner::SourceKind};
+
+/// Most bytes of a resource read for a preview.
+pub const MAX_PREVIEW_BYTES: usize = 64 * 1024;
+const HEX_ROW: usize = 16;
+
+#[derive(Debug)]
+pub enum PreviewContent {
+    Text(String),
+    /// A hex dump of binary content.
+    Hex(String),
+}
+
+/// The beginning of one conflict source, decoded for display.
+#[derive(Debug)]
+pub struct ResourcePreview {
+    pub key: String,
+    pub path: PathBuf,
+    pub content: PreviewContent,
+    /// Full size of the resource in bytes.
+    pub total_len: u64,
+}
+
+impl ResourcePreview {
+    pub fn truncated(&self) -> bool {
+        self.total_len > MAX_PREVIEW_BYTES as u64
+    }
+}
+
+/// Reads up to [`MAX_PREVIEW_BYTES`] of the resource `key` provided by `path`.
+///
+/// Loose files are read only as far as needed, archive resources are unpacked
+/// whole and then cut.
+pub fn load_preview(path: &Path, key: &str, name: &str) -> AnyhowResult<ResourcePreview> {
+    let (data, total_len) = match SourceKind::of(path) {
+        SourceKind::Loose => {
+            let file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let total_len = file.metadata().map_or(0, |metadata| metadata.len());
+
+            let mut data = Vec::with_capacity(MAX_PREVIEW_BYTES.min(total_len as usize));
+            file.take(MAX_PREVIEW_BYTES as u64)
+                .read_to_end(&mut data)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            (data, total_len)
+        }
+        SourceKind::Archive => {
+            let mut data = read_source(path, name)?;
+            let total_len = data.len() as u64;
+            data.truncate(MAX_PREVIEW_BYTES);
+            (data, total_len)
+        }
+    };
+
+    let content = match decode_text(&data) {
+        Some(text) => PreviewContent::Text(text),
+        None => PreviewContent::Hex(hex_dump(&data)),
+    };
+
+    Ok(ResourcePreview {
+        key: key.to_string(),
+        path: path.to_path_buf(),
+        content,
+        total_len,
+    })
+}
+
+/// Decodes `data` as text unless it holds NUL bytes or isn't UTF-8, allowing
+/// for a character cut in half at the end of a truncated read.
+pub fn decode_text(data: &[u8]) -> Option<String> {
+    if data.contains(&0) {
+        return None;
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(text) => Some(text.to_string()),
+        Err(err) if err.error_len().is_none() => {
+            Some(String::from_utf8_lossy(&data[..err.valid_up_to()]).into_owned())
+        }
+        Err(_) => None,
+    }
+}
+
+/// Formats `data` as offset, hex bytes and printable ASCII, 16 bytes per row.
+fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::with_capacity(data.len() * 4);
+
+    for (row, chunk) in data.chunks(HEX_ROW).enumerate() {
+        let _ = write!(dump, "{:08x}  ", row * HEX_ROW);
+        for index in 0..HEX_ROW {
+            match chunk.get(index) {
+                Some(byte) => {
+                    let _ = write!(dump, "{byte:02x} ");
+                }
+                None => dump.push_str("   "),
+            }
+        }
+
+        dump.push(' ');
+        dump.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        dump.push('\n');
+    }
+
+    dump
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::scanner::Conflicts;
+
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// The ignore list of a profile, kept aside while another profile is active.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileResolutions {
+    pub ignored: Conflicts,
+    /// Unix timestamp of when each ignored conflict was resolved.
+    pub ignored_at: HashMap<String, u64>,
+}
+
+/// Removes files another profile generated from the conflicts, dropping groups
+/// left with a single source. Returns how many sources were removed.
+///
+/// `generated` maps each generated file to the profile that wrote it.
+pub fn exclude_foreign_generated(
+    conflicts: &mut Conflicts,
+    generated: &HashMap<PathBuf, String>,
+    active_profile: &str,
+) -> usize {
+    let mut excluded = 0;
+
+    conflicts.retain(|_, paths| {
+        paths.retain(|path| {
+            let is_foreign = generated
+                .get(path)
+                .is_some_and(|profile| profile != active_profile);
+            excluded += usize::from(is_foreign);
+            !is_foreign
+        });
+        paths.len() > 1
+    });
+
+    excluded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_generated_by_another_profile_are_excluded() {
+        let source = |folder: &str, name: &str| PathBuf::from("override").join(folder).join(name);
+        let conflicts: Conflicts = [
+            (
+                "armor.uti".to_string(),
+                vec![source("mod_a", "armor.uti"), source("patches", "armor.uti")],
+            ),
+            (
+                "helmet.uti".to_string(),
+                vec![
+                    source("mod_a", "helmet.uti"),
+                    source("mod_b", "helmet.uti"),
+                    source("patches", "helmet.uti"),
+                ],
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let generated = ["armor.uti", "helmet.uti"]
+            .map(|name| (source("patches", name), "Testing".to_string()))
+            .into();
+
+        let mut own = conflicts.clone();
+        assert_eq!(
+            exclude_foreign_generated(&mut own, &generated, "Testing"),
+            0
+        );
+        assert_eq!(own, conflicts);
+
+        let mut other = conflicts;
+        assert_eq!(
+            exclude_foreign_generated(&mut other, &generated, DEFAULT_PROFILE),
+            2
+        );
+        assert!(!other.contains_key("armor.uti"));
+        assert_eq!(
+            other["helmet.uti"],
+            [source("mod_a", "helmet.uti"), source("mod_b", "helmet.uti")]
+        );
+    }
+}
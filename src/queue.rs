@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use crate::scanner::{ScanError, ScanReport};
+
+/// Progress of a queue of scans, in the order it happens.
+#[derive(Debug)]
+pub enum QueueEvent {
+    /// The target at `index` (zero-based) out of `total` started scanning.
+    Started { index: usize, total: usize },
+    Finished {
+        index: usize,
+        result: Result<Box<ScanReport>, ScanError>,
+    },
+}
+
+/// Scans `targets` one after another, reporting each start and result.
+pub fn run_queue(
+    targets: &[PathBuf],
+    mut scan: impl FnMut(&Path) -> Result<ScanReport, ScanError>,
+    mut report: impl FnMut(QueueEvent),
+) {
+    let total = targets.len();
+
+    for (index, target) in targets.iter().enumerate() {
+        report(QueueEvent::Started { index, total });
+        report(QueueEvent::Finished {
+            index,
+            result: scan(target).map(Box::new),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_targets_in_turn_and_collects_each_result() {
+        let targets = ["install_a", "install_b", "install_c"].map(PathBuf::from);
+        let mut scanned = Vec::new();
+        let mut events = Vec::new();
+
+        run_queue(
+            &targets,
+            |target| {
+                scanned.push(target.to_path_buf());
+                if target.ends_with("install_b") {
+                    return Err(ScanError::Cancelled);
+                }
+                Ok(ScanReport {
+                    root: target.to_path_buf(),
+                    ..Default::default()
+                })
+            },
+            |event| events.push(event),
+        );
+
+        assert_eq!(scanned, targets);
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                QueueEvent::Started { index, total } => format!("start {index}/{total}"),
+                QueueEvent::Finished {
+                    index,
+                    result: Ok(report),
+                } => format!("done {index} {}", report.root.display()),
+                QueueEvent::Finished {
+                    index,
+                    result: Err(err),
+                } => format!("failed {index} {err}"),
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                "start 0/3",
+                "done 0 install_a",
+                "start 1/3",
+                "failed 1 Scan cancelled",
+                "start 2/3",
+                "done 2 install_c",
+            ]
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use std::fmt::Debug;
+
+/// A hook for resources the engine resolves through indirection rather than by
+/// file name, e.g. names redirected by a data table.
+///
+/// Rules run while keying scanned resources, so two differently named files the
+/// engine treats as one resource end up in the same conflict.
+pub trait ResolutionRule: Debug + Send + Sync {
+    /// Returns the name `name` is actually loaded as, or `None` to keep it.
+    fn redirect(&self, name: &str) -> Option<String>;
+}
+
+/// The default rule: every resource resolves under its own file name.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRedirection;
+
+impl ResolutionRule for NoRedirection {
+    fn redirect(&self, _name: &str) -> Option<String> {
+        None
+    }
+}
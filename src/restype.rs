@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// What kind of data a resource holds, told apart by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ResourceType {
+    Texture,
+    Table,
+    Gff,
+    Model,
+    Script,
+    Audio,
+    TalkTable,
+    Other,
+}
+
+/// Extensions of the common DA:O resource types.
+const EXTENSIONS: &[(&str, ResourceType)] = &[
+    ("dds", ResourceType::Texture),
+    ("tga", ResourceType::Texture),
+    ("bmp", ResourceType::Texture),
+    ("tnt", ResourceType::Texture),
+    ("gda", ResourceType::Table),
+    ("2da", ResourceType::Table),
+    ("gff", ResourceType::Gff),
+    ("are", ResourceType::Gff),
+    ("cif", ResourceType::Gff),
+    ("cut", ResourceType::Gff),
+    ("dlg", ResourceType::Gff),
+    ("gui", ResourceType::Gff),
+    ("plo", ResourceType::Gff),
+    ("utc", ResourceType::Gff),
+    ("utd", ResourceType::Gff),
+    ("ute", ResourceType::Gff),
+    ("uti", ResourceType::Gff),
+    ("utm", ResourceType::Gff),
+    ("utp", ResourceType::Gff),
+    ("uts", ResourceType::Gff),
+    ("utt", ResourceType::Gff),
+    ("utw", ResourceType::Gff),
+    ("mao", ResourceType::Model),
+    ("mat", ResourceType::Model),
+    ("mmh", ResourceType::Model),
+    ("msh", ResourceType::Model),
+    ("mdl", ResourceType::Model),
+    ("phy", ResourceType::Model),
+    ("ani", ResourceType::Model),
+    ("ncs", ResourceType::Script),
+    ("nss", ResourceType::Script),
+    ("wav", ResourceType::Audio),
+    ("fsb", ResourceType::Audio),
+    ("fev", ResourceType::Audio),
+    ("lip", ResourceType::Audio),
+    ("tlk", ResourceType::TalkTable),
+];
+
+impl ResourceType {
+    pub const ALL: [ResourceType; 8] = [
+        ResourceType::Texture,
+        ResourceType::Table,
+        ResourceType::Gff,
+        ResourceType::Model,
+        ResourceType::Script,
+        ResourceType::Audio,
+        ResourceType::TalkTable,
+        ResourceType::Other,
+    ];
+
+    pub fn of_extension(ext: &str) -> Self {
+        EXTENSIONS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+            .map_or(ResourceType::Other, |&(_, restype)| restype)
+    }
+
+    pub fn of_name(name: &str) -> Self {
+        Path::new(name)
+            .extension()
+            .map_or(ResourceType::Other, |ext| {
+                Self::of_extension(&ext.to_string_lossy())
+            })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResourceType::Texture => "Texture",
+            ResourceType::Table => "2DA table",
+            ResourceType::Gff => "GFF data",
+            ResourceType::Model => "Model",
+            ResourceType::Script => "Script",
+            ResourceType::Audio => "Audio",
+            ResourceType::TalkTable => "Talk table",
+            ResourceType::Other => "Other",
+        }
+    }
+}
+
+/// The extension a V3.0 archive's type hash stands for, if it's a known one.
+pub fn extension_of_hash(hash: u32) -> Option<&'static str> {
+    EXTENSIONS
+        .iter()
+        .map(|&(ext, _)| ext)
+        .find(|ext| fnv32(ext.as_bytes()) == hash)
+}
+
+/// 32-bit FNV-1a, which V3.0 archives use to hash resource extensions.
+fn fnv32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
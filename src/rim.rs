@@ -0,0 +1,341 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use anyhow::{Context, Result as AnyhowResult};
+use thiserror::Error as ThisError;
+
+use crate::erf::ErfFile;
+
+const RIM_MAGIC: &[u8; 8] = b"RIM V1.0";
+const HEADER_SIZE: usize = 24;
+const KEY_SIZE: usize = 32;
+const RESREF_SIZE: usize = 16;
+
+/// Extensions of the resource types found in RIM archives, by type id.
+const RESOURCE_TYPES: &[(u32, &str)] = &[
+    (1, "bmp"),
+    (3, "tga"),
+    (4, "wav"),
+    (6, "plt"),
+    (7, "ini"),
+    (10, "txt"),
+    (2002, "mdl"),
+    (2009, "nss"),
+    (2010, "ncs"),
+    (2012, "are"),
+    (2013, "set"),
+    (2014, "ifo"),
+    (2016, "wok"),
+    (2017, "2da"),
+    (2018, "tlk"),
+    (2022, "txi"),
+    (2023, "git"),
+    (2025, "uti"),
+    (2027, "utc"),
+    (2029, "dlg"),
+    (2030, "itp"),
+    (2032, "utt"),
+    (2033, "dds"),
+    (2035, "uts"),
+    (2036, "ltr"),
+    (2037, "gff"),
+    (2038, "fac"),
+    (2040, "ute"),
+    (2042, "utd"),
+    (2044, "utp"),
+    (2046, "gic"),
+    (2047, "gui"),
+    (2051, "utm"),
+    (2056, "jrl"),
+    (2058, "utw"),
+    (2060, "ssf"),
+];
+
+#[derive(Debug, ThisError)]
+pub enum RimError {
+    #[error("Not a RIM archive")]
+    InvalidHeader,
+
+    #[error("RIM key table is truncated")]
+    Truncated,
+
+    #[error("{name} at offset {offset} with length {length} extends past the end of the archive")]
+    EntryOutOfBounds {
+        name: String,
+        offset: u32,
+        length: u32,
+    },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RimEntry {
+    pub name: String,
+    pub offset: u32,
+    pub length: u32,
+}
+
+/// A `RIM V1.0` resource archive: a flat key table of uncompressed resources.
+#[derive(Debug)]
+pub struct RimFile {
+    pub entries: Vec<RimEntry>,
+}
+
+/// An archive opened by what its header holds, since DA:O ships most of its
+/// own `.rim` files in the ERF layout.
+#[derive(Debug)]
+pub enum Archive {
+    Erf(ErfFile),
+    Rim(RimFile),
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> AnyhowResult<Self> {
+        match ErfFile::open(path) {
+            Ok(erf) => Ok(Archive::Erf(erf)),
+            Err(err) => match RimFile::open(path) {
+                Ok(rim) => Ok(Archive::Rim(rim)),
+                Err(_) => Err(err),
+            },
+        }
+    }
+
+    /// Names and unpacked lengths of every resource.
+    pub fn entries(&self) -> Vec<(&str, u32)> {
+        match self {
+            Archive::Erf(erf) => erf
+                .toc
+                .iter()
+                .map(|entry| (entry.name.as_str(), entry.length))
+                .collect(),
+            Archive::Rim(rim) => rim
+                .entries
+                .iter()
+                .map(|entry| (entry.name.as_str(), entry.length))
+                .collect(),
+        }
+    }
+
+    /// Unpacked length of a resource, looked up ignoring case.
+    pub fn length(&self, name: &str) -> Option<u32> {
+        match self {
+            Archive::Erf(erf) => erf.entry(name).map(|entry| entry.length),
+            Archive::Rim(rim) => rim.entry(name).map(|entry| entry.length),
+        }
+    }
+
+    pub fn get_resource<R: Read + Seek>(
+        &self,
+        name: &str,
+        reader: &mut R,
+    ) -> AnyhowResult<Vec<u8>> {
+        match self {
+            Archive::Erf(erf) => erf.get_resource(name, reader),
+            Archive::Rim(rim) => rim.get_resource(name, reader),
+        }
+    }
+
+    /// Streams a resource's unpacked content from its range in the archive.
+    pub fn resource_reader<'a, R: Read + Seek>(
+        &self,
+        name: &str,
+        reader: &'a mut R,
+    ) -> AnyhowResult<Box<dyn Read + 'a>> {
+        match self {
+            Archive::Erf(erf) => erf.resource_reader(name, reader),
+            Archive::Rim(rim) => {
+                let entry = rim
+                    .entry(name)
+                    .with_context(|| format!("No resource named {name} in the archive"))?;
+                reader
+                    .seek(SeekFrom::Start(entry.offset as u64))
+                    .context("Failed to seek to resource offset")?;
+                Ok(Box::new(reader.take(entry.length as u64)))
+            }
+        }
+    }
+}
+
+impl RimFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> AnyhowResult<Self> {
+        let path_ref = path.as_ref();
+        let mut file = File::open(path_ref)
+            .with_context(|| format!("Failed to open RIM file at {}", path_ref.display()))?;
+
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic)
+            .with_context(|| format!("Failed to read RIM file at {}", path_ref.display()))?;
+
+        if &magic != RIM_MAGIC {
+            return Err(RimError::InvalidHeader.into());
+        }
+
+        let file_len = file
+            .metadata()
+            .with_context(|| format!("Failed to read RIM file at {}", path_ref.display()))?
+            .len();
+        Self::read_keys(&mut file, file_len)
+            .with_context(|| format!("Failed to parse RIM file at {}", path_ref.display()))
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&RimEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Reads an entry, RIM resources are never compressed.
+    pub fn get_resource<R: Read + Seek>(
+        &self,
+        name: &str,
+        reader: &mut R,
+    ) -> AnyhowResult<Vec<u8>> {
+        let entry = self
+            .entry(name)
+            .with_context(|| format!("No resource named {name} in the archive"))?;
+
+        reader
+            .seek(SeekFrom::Start(entry.offset as u64))
+            .context("Failed to seek to resource offset")?;
+        let mut data = vec![0u8; entry.length as usize];
+        reader
+            .read_exact(&mut data)
+            .context("Failed to read resource data")?;
+
+        Ok(data)
+    }
+
+    /// Reads the header and key table only, resource data stays on disk.
+    fn read_keys<R: Read + Seek>(reader: &mut R, file_len: u64) -> Result<Self, RimError> {
+        let mut header = [0u8; HEADER_SIZE];
+        reader.seek(SeekFrom::Start(0))?;
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| RimError::Truncated)?;
+        let count = read_u32(&header[12..16]) as u64;
+        let keys_offset = read_u32(&header[16..20]) as u64;
+
+        // Checked before allocating so a corrupt count can't ask for gigabytes
+        if keys_offset + count * KEY_SIZE as u64 > file_len {
+            return Err(RimError::Truncated);
+        }
+        let mut keys = vec![0u8; count as usize * KEY_SIZE];
+        reader.seek(SeekFrom::Start(keys_offset))?;
+        reader.read_exact(&mut keys)?;
+
+        let entries = keys
+            .chunks_exact(KEY_SIZE)
+            .map(|key| {
+                let resref = &key[..RESREF_SIZE];
+                let resref_len = resref.iter().position(|&b| b == 0).unwrap_or(RESREF_SIZE);
+                let resref = String::from_utf8_lossy(&resref[..resref_len]).to_lowercase();
+                let restype = read_u32(&key[16..20]);
+
+                RimEntry {
+                    name: format!("{resref}.{}", extension(restype)),
+                    offset: read_u32(&key[24..28]),
+                    length: read_u32(&key[28..32]),
+                }
+            })
+            .collect();
+
+        let rim = Self { entries };
+        rim.check_bounds(file_len)?;
+        Ok(rim)
+    }
+
+    /// Fails on the first entry whose data doesn't fit inside the archive.
+    fn check_bounds(&self, file_len: u64) -> Result<(), RimError> {
+        match self
+            .entries
+            .iter()
+            .find(|entry| entry.offset as u64 + entry.length as u64 > file_len)
+        {
+            Some(entry) => Err(RimError::EntryOutOfBounds {
+                name: entry.name.clone(),
+                offset: entry.offset,
+                length: entry.length,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The extension of a resource type, the type id itself for unknown types.
+fn extension(restype: u32) -> String {
+    RESOURCE_TYPES
+        .iter()
+        .find(|(id, _)| *id == restype)
+        .map_or_else(|| restype.to_string(), |(_, ext)| ext.to_string())
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// A RIM with one key per `(resref, restype, offset, length)`, no data.
+    fn rim(keys: &[(&str, u32, u32, u32)], count: u32) -> Vec<u8> {
+        let mut bytes = RIM_MAGIC.to_vec();
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        for &(resref, restype, offset, length) in keys {
+            let mut name = [0u8; RESREF_SIZE];
+            name[..resref.len()].copy_from_slice(resref.as_bytes());
+            bytes.extend_from_slice(&name);
+            bytes.extend_from_slice(&restype.to_le_bytes());
+            bytes.extend_from_slice(&[0; 4]);
+            bytes.extend_from_slice(&offset.to_le_bytes());
+            bytes.extend_from_slice(&length.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn parse(bytes: &[u8]) -> Result<RimFile, RimError> {
+        RimFile::read_keys(&mut Cursor::new(bytes), bytes.len() as u64)
+    }
+
+    #[test]
+    fn keys_name_entries_by_resref_and_type() {
+        let bytes = rim(&[("Sword", 2025, 0, 8), ("x", 9999, 8, 0)], 2);
+        let rim = parse(&bytes).unwrap();
+
+        let names: Vec<_> = rim.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["sword.uti", "x.9999"]);
+        assert!(rim.entry("SWORD.UTI").is_some());
+    }
+
+    #[test]
+    fn a_count_past_the_end_of_the_file_is_truncated() {
+        let bytes = rim(&[("sword", 2025, 0, 8)], 1_000_000);
+        assert!(matches!(parse(&bytes), Err(RimError::Truncated)));
+    }
+
+    #[test]
+    fn an_entry_past_the_end_of_the_file_is_out_of_bounds() {
+        let bytes = rim(&[("sword", 2025, 0, 8), ("shield", 2025, 40, 100)], 2);
+        match parse(&bytes) {
+            Err(RimError::EntryOutOfBounds {
+                name,
+                offset,
+                length,
+            }) => {
+                assert_eq!(name, "shield.uti");
+                assert_eq!((offset, length), (40, 100));
+            }
+            other => panic!("expected EntryOutOfBounds, got {other:?}"),
+        }
+    }
+}
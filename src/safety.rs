@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// What the delete flow must do before deleting a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteSafety {
+    Allowed,
+    /// The user has to type a confirmation first.
+    Confirm,
+    Blocked,
+}
+
+/// Extensions that are riskier to delete, matched case-insensitively without the dot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeleteRules {
+    pub blocked: Vec<String>,
+    pub confirm: Vec<String>,
+}
+
+impl Default for DeleteRules {
+    fn default() -> Self {
+        Self {
+            blocked: vec!["exe".into(), "dll".into()],
+            confirm: vec!["tlk".into(), "gda".into(), "ncs".into()],
+        }
+    }
+}
+
+impl DeleteRules {
+    pub fn check(&self, path: &Path) -> DeleteSafety {
+        let Some(extension) = path.extension().map(|e| e.to_string_lossy()) else {
+            return DeleteSafety::Allowed;
+        };
+        let listed = |extensions: &[String]| {
+            extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+        };
+
+        if listed(&self.blocked) {
+            DeleteSafety::Blocked
+        } else if listed(&self.confirm) {
+            DeleteSafety::Confirm
+        } else {
+            DeleteSafety::Allowed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_and_risky_extensions_are_guarded() {
+        let rules = DeleteRules::default();
+
+        assert_eq!(
+            rules.check(Path::new("override/mod/Injector.DLL")),
+            DeleteSafety::Blocked
+        );
+        assert_eq!(
+            rules.check(Path::new("override/mod/core_en-us.tlk")),
+            DeleteSafety::Confirm
+        );
+        assert_eq!(
+            rules.check(Path::new("override/mod/face.tga")),
+            DeleteSafety::Allowed
+        );
+        assert_eq!(
+            rules.check(Path::new("override/mod/README")),
+            DeleteSafety::Allowed
+        );
+    }
+
+    #[test]
+    fn configured_extensions_may_carry_a_dot() {
+        let rules = DeleteRules {
+            blocked: vec![".uti".into()],
+            confirm: Vec::new(),
+        };
+
+        assert_eq!(rules.check(Path::new("armor.uti")), DeleteSafety::Blocked);
+        assert_eq!(rules.check(Path::new("core.tlk")), DeleteSafety::Allowed);
+    }
+}
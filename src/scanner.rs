@@ -1,15 +1,46 @@
 use std::{
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet, hash_map},
+    fs,
+    num::NonZeroUsize,
+    ops::{Deref, DerefMut},
+    panic,
     path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::Sender,
+    },
+    thread,
+    time::UNIX_EPOCH,
 };
 
 use anyhow::Error as AnyhowError;
+use same_file::Handle;
+use serde::{Deserialize, Serialize};
 use thiserror::Error as ThisError;
 use walkdir::WalkDir;
 
-use crate::erf::ErfFile;
+use crate::{
+    cache::{CachedToc, TocCache},
+    content::{Classification, Divergence, KnownIdentical, classify_trusting},
+    manifest::{AddinManifest, MANIFEST_FILE},
+    resolution::{NoRedirection, ResolutionRule},
+    rim::Archive,
+    utils::{BackgroundPriority, is_disabled, unix_timestamp},
+};
 
-const IGNORED_FILES: &[&str] = &["manifest.xml", "credits.txt", "readme.txt"];
+/// File names left out of every scan until the user edits the list.
+const DEFAULT_IGNORED_NAMES: &[&str] = &["manifest.xml", "credits.txt", "readme.txt"];
+const ADDINS_DIR: &str = "addins";
+const OVERRIDE_DIR: &str = "override";
+const PACKAGES_DIR: &str = "packages";
+/// Packages whose `override` folder the game loads loose files from.
+const OVERRIDE_PACKAGES: &[&str] = &["core", "core_ep1", "single player"];
+/// Add-in content folders that can hold an `override` folder.
+const ADDIN_CONTENT_DIRS: &[&str] = &["core", "module"];
+/// Files walked between two progress updates.
+const PROGRESS_INTERVAL: usize = 64;
 
 #[derive(Debug, ThisError)]
 pub enum ScanError {
@@ -19,74 +50,1517 @@ pub enum ScanError {
         #[source]
         source: AnyhowError,
     },
+
+    #[error("Scan cancelled")]
+    Cancelled,
+}
+
+/// Sources of each resource by conflict key.
+///
+/// Derefs to the map of keys to source paths. Alongside it, archive sources
+/// whose entry is named differently from their key, such as under
+/// [`Grouping::BaseName`] or a redirecting [`ResolutionRule`], keep that
+/// original entry name so the resource can still be found in the archive.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Conflicts {
+    sources: HashMap<String, Vec<PathBuf>>,
+    #[serde(skip)]
+    entry_names: HashMap<String, HashMap<PathBuf, String>>,
+}
+
+impl Conflicts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `path` as a source of `key`, providing the resource named `entry`.
+    pub fn add_source(&mut self, key: String, path: PathBuf, entry: &str) {
+        if !entry.eq_ignore_ascii_case(resource_name(&key)) {
+            self.entry_names
+                .entry(key.clone())
+                .or_default()
+                .insert(path.clone(), entry.to_string());
+        }
+        self.sources.entry(key).or_default().push(path);
+    }
+
+    /// The name the resource of `key` has in the source at `path`.
+    pub fn entry_name<'a>(&'a self, key: &'a str, path: &Path) -> &'a str {
+        self.entry_names
+            .get(key)
+            .and_then(|names| names.get(path))
+            .map_or_else(|| resource_name(key), String::as_str)
+    }
+
+    /// Keeps the entry names recorded in `other`, for sources merged from it.
+    pub fn keep_entry_names(&mut self, other: &Conflicts) {
+        for (key, names) in &other.entry_names {
+            self.entry_names.entry(key.clone()).or_default().extend(
+                names
+                    .iter()
+                    .map(|(path, name)| (path.clone(), name.clone())),
+            );
+        }
+    }
+}
+
+impl Deref for Conflicts {
+    type Target = HashMap<String, Vec<PathBuf>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sources
+    }
+}
+
+impl DerefMut for Conflicts {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sources
+    }
+}
+
+impl FromIterator<(String, Vec<PathBuf>)> for Conflicts {
+    fn from_iter<I: IntoIterator<Item = (String, Vec<PathBuf>)>>(iter: I) -> Self {
+        Self {
+            sources: iter.into_iter().collect(),
+            entry_names: HashMap::new(),
+        }
+    }
+}
+
+impl IntoIterator for Conflicts {
+    type Item = (String, Vec<PathBuf>);
+    type IntoIter = hash_map::IntoIter<String, Vec<PathBuf>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sources.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Conflicts {
+    type Item = (&'a String, &'a Vec<PathBuf>);
+    type IntoIter = hash_map::Iter<'a, String, Vec<PathBuf>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.sources.iter()
+    }
+}
+
+/// A file the scan couldn't use, with the reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanWarning {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl ScanWarning {
+    pub fn new(path: &Path, err: &AnyhowError) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            message: format!("{err:#}"),
+        }
+    }
+
+    /// A folder or file the walk couldn't get into, so whatever it holds is
+    /// missing from the results.
+    fn from_walk_error(err: &walkdir::Error, root: &Path) -> Self {
+        let message = match (err.loop_ancestor(), err.io_error()) {
+            (Some(ancestor), _) => {
+                format!("Skipped, it links back to {}", ancestor.display())
+            }
+            (None, Some(io_err)) => {
+                format!("Could not be read, its contents were skipped: {io_err}")
+            }
+            (None, None) => err.to_string(),
+        };
+
+        Self {
+            path: err.path().unwrap_or(root).to_path_buf(),
+            message,
+        }
+    }
+
+    fn from_scan_error(path: &Path, err: &ScanError) -> Self {
+        match err {
+            ScanError::ErfError { source, .. } => Self::new(path, source),
+            ScanError::Cancelled => Self {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// A loose file that can be deleted or disabled on its own.
+    Loose,
+    /// A resource packed inside an ERF or RIM archive.
+    Archive,
+}
+
+impl SourceKind {
+    pub fn of(path: &Path) -> Self {
+        if is_erf_file(path) || is_rim_file(path) {
+            SourceKind::Archive
+        } else {
+            SourceKind::Loose
+        }
+    }
+}
+
+/// How resources are grouped into conflicts.
+///
+/// [`Grouping::ExactName`] matches how the engine resolves files, since the
+/// extension is part of the name. [`Grouping::BaseName`] also groups different
+/// types sharing a name (`foo.utc` with `foo.uti`), which shows everything a mod
+/// touches under that name but reports groups that don't actually override
+/// each other, and can't be compared by content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grouping {
+    #[default]
+    ExactName,
+    BaseName,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Language tokens (e.g. `en-us`) that mark localized variants of a resource.
+    pub languages: Vec<String>,
+    /// Hash source contents to tell identical duplicates from divergent ones.
+    pub detect_divergence: bool,
+    /// Leave zero-byte loose files and zero-length ERF resources out of grouping.
+    pub skip_empty: bool,
+    /// Treat every non-archive file as a loose override file wherever it is, for
+    /// folders that don't follow the game's layout such as a modpack staging folder.
+    pub loose_anywhere: bool,
+    /// Folders and archives never scanned, such as the delete backup folder.
+    pub excluded_dirs: Vec<PathBuf>,
+    /// Run the walk at background I/O priority, see [`BackgroundPriority`].
+    pub background_priority: bool,
+    /// Maps resource names to the name the engine loads them as before keying.
+    pub resolution: Arc<dyn ResolutionRule>,
+    pub grouping: Grouping,
+    /// Collapse sources that are hardlinks to the same file into one.
+    pub dedup_hardlinks: bool,
+    /// Only walk the override folders for loose files, skipping every archive.
+    pub override_only: bool,
+    /// Groups confirmed identical, trusted without hashing while they hold.
+    pub known_identical: HashMap<String, KnownIdentical>,
+    /// Hash every conflict and drop the ones whose sources are byte-identical.
+    pub hide_identical: bool,
+    /// Where parsed archive tables of contents are cached between scans.
+    pub toc_cache: Option<PathBuf>,
+    /// Name patterns of resources never reported as conflicts, see [`matches_pattern`].
+    pub ignore_patterns: Vec<String>,
+    /// Lowercase resource names never reported as conflicts.
+    pub ignored_names: BTreeSet<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            detect_divergence: false,
+            skip_empty: false,
+            loose_anywhere: false,
+            excluded_dirs: Vec::new(),
+            background_priority: false,
+            resolution: Arc::new(NoRedirection),
+            grouping: Grouping::default(),
+            dedup_hardlinks: false,
+            override_only: false,
+            known_identical: HashMap::new(),
+            hide_identical: false,
+            toc_cache: None,
+            ignore_patterns: Vec::new(),
+            ignored_names: default_ignored_names(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    /// Zero-byte files and resources left out because of [`ScanOptions::skip_empty`].
+    pub skipped_empty: usize,
+    /// ERF archives that could not be read and contributed no resources.
+    pub failed_archives: usize,
+    /// Sources dropped because they were hardlinks to another source.
+    pub collapsed_links: usize,
+    /// Conflicts dropped because of [`ScanOptions::hide_identical`].
+    pub hidden_identical: usize,
+    /// Loose files grouped, including ones that don't conflict.
+    pub loose_files: usize,
+    /// ERF archives found, including unreadable ones.
+    pub archives: usize,
+    /// Distinct resources found before non-conflicting ones were dropped.
+    pub resources: usize,
+    /// Sources providing those resources, counting every archive entry.
+    pub providers: usize,
+}
+
+/// How far a running scan has come.
+#[derive(Debug, Clone, Default)]
+pub struct ScanProgress {
+    pub files_seen: usize,
+    pub erfs_parsed: usize,
+    /// Archives to parse, known once the walk is done.
+    pub erfs_total: Option<usize>,
+    pub groups_hashed: usize,
+    /// Conflict groups to compare by content, known once hashing starts.
+    pub groups_total: Option<usize>,
+    pub current_path: PathBuf,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// The folder that was scanned.
+    pub root: PathBuf,
+    /// Archives were skipped, so the conflicts only cover loose override files.
+    pub override_only: bool,
+    pub conflicts: Conflicts,
+    /// Every ERF archive discovered during the walk, including unreadable ones.
+    pub archives: Vec<PathBuf>,
+    /// Loose files that were disabled by the app and are skipped from grouping,
+    /// with the conflict key they had while enabled.
+    pub disabled: Vec<(String, PathBuf)>,
+    /// Manifest labels of the add-ins found, keyed by add-in root folder.
+    pub addins: HashMap<PathBuf, String>,
+    /// Content classification per conflict, empty unless divergence detection is on.
+    pub divergence: HashMap<String, Divergence>,
+    /// Whether sources were hashed this scan, so `divergence` is complete.
+    pub compared_contents: bool,
+    /// Conflicts classified from [`ScanOptions::known_identical`] without hashing.
+    pub trusted_identical: HashSet<String>,
+    /// ERF archives that could not be read, see [`retry_archives`].
+    pub failed_archives: Vec<PathBuf>,
+    /// Unreadable archives and manifests, sorted by path.
+    pub warnings: Vec<ScanWarning>,
+    /// Resources provided by a single source, kept so retried archives can be
+    /// folded in without a full rescan.
+    pub single_providers: HashMap<String, PathBuf>,
+    /// Every resource found with all of its sources, conflicting or not.
+    pub resource_index: Conflicts,
+    /// Size and modification time of every conflict source, by conflict key and path.
+    pub source_info: HashMap<(String, PathBuf), SourceInfo>,
+    pub stats: ScanStats,
+}
+
+/// Size and modification time of a conflict source, read once per scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceInfo {
+    /// Size of the file, or of the whole archive for packed sources.
+    pub size: u64,
+    /// Unix timestamp of the last modification.
+    pub modified: Option<u64>,
+    /// Unpacked length of the resource, for packed sources.
+    pub resource_length: Option<u32>,
+}
+
+/// What [`retry_archives`] changed.
+#[derive(Debug, Default)]
+pub struct RetryOutcome {
+    /// Conflicts that gained sources, including newly formed ones.
+    pub changed: Vec<String>,
+    /// Archives that still could not be read.
+    pub still_failing: Vec<ScanWarning>,
 }
 
-pub type Conflicts = HashMap<String, Vec<PathBuf>>;
+/// Aggregate numbers over a set of conflicts, for status lines and report headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConflictsSummary {
+    pub conflicts: usize,
+    /// Distinct files involved, an archive counted once however many of its
+    /// resources conflict.
+    pub files: usize,
+    /// Sources that are loose override files.
+    pub loose_sources: usize,
+    /// Sources that are resources packed in archives.
+    pub packed_sources: usize,
+    /// The resource with the most sources and how many it has, ties going to
+    /// the first name.
+    pub most_conflicted: Option<(String, usize)>,
+}
+
+impl ConflictsSummary {
+    pub fn describe(&self) -> String {
+        if self.conflicts == 0 {
+            return "No conflicts".to_string();
+        }
+
+        format!("{} conflicts {}", self.conflicts, self.sources())
+    }
+
+    /// Where the conflicts come from, without their count.
+    pub fn sources(&self) -> String {
+        let mut text = format!(
+            "across {} files ({} loose, {} packed sources)",
+            self.files, self.loose_sources, self.packed_sources
+        );
+        if let Some((key, sources)) = &self.most_conflicted {
+            text.push_str(&format!(", most contested: {key} ({sources} sources)"));
+        }
+        text
+    }
+}
+
+/// Summarizes conflicts given as key and sources pairs, such as a [`Conflicts`]
+/// map or the entries of report sections.
+pub fn summarize_conflicts<'a>(
+    conflicts: impl IntoIterator<Item = (&'a String, &'a Vec<PathBuf>)>,
+) -> ConflictsSummary {
+    let mut summary = ConflictsSummary::default();
+    let mut files = HashSet::new();
+
+    for (key, paths) in conflicts {
+        summary.conflicts += 1;
+        for path in paths {
+            match SourceKind::of(path) {
+                SourceKind::Loose => summary.loose_sources += 1,
+                SourceKind::Archive => summary.packed_sources += 1,
+            }
+            files.insert(path);
+        }
+
+        let is_most = summary
+            .most_conflicted
+            .as_ref()
+            .is_none_or(|(most, count)| (paths.len(), Reverse(key)) > (*count, Reverse(most)));
+        if is_most {
+            summary.most_conflicted = Some((key.clone(), paths.len()));
+        }
+    }
+
+    summary.files = files.len();
+    summary
+}
+
+pub fn scan_for_conflicts(
+    bioware_dir: &Path,
+    options: &ScanOptions,
+    progress: Option<&Sender<ScanProgress>>,
+    cancel: Option<&AtomicBool>,
+) -> Result<ScanReport, ScanError> {
+    let cancelled = || cancel.is_some_and(|flag| flag.load(Ordering::Relaxed));
+    let _priority = options.background_priority.then(BackgroundPriority::enter);
 
-pub fn scan_for_conflicts(bioware_dir: &Path) -> Result<Conflicts, ScanError> {
     let mut conflicts = Conflicts::new();
-    let override_dir = bioware_dir.join("packages/core/override");
+    let mut archives = Vec::new();
+    let mut disabled = Vec::new();
+    let mut addins = HashMap::new();
+    let mut warnings = Vec::new();
+    let mut walk_errors = Vec::new();
+    let mut stats = ScanStats::default();
+    let walk_roots = if options.override_only {
+        override_dirs(bioware_dir)
+    } else {
+        vec![bioware_dir.to_path_buf()]
+    };
 
-    WalkDir::new(bioware_dir)
-        .into_iter()
-        .filter_map(Result::ok)
+    walk_roots
+        .iter()
+        .flat_map(|root| {
+            WalkDir::new(root)
+                .into_iter()
+                .filter_entry(|e| {
+                    !options
+                        .excluded_dirs
+                        .iter()
+                        .any(|dir| e.path().starts_with(dir))
+                })
+                .map(move |entry| (root, entry))
+        })
+        .filter_map(|(root, entry)| {
+            entry
+                .map_err(|err| walk_errors.push(ScanWarning::from_walk_error(&err, root)))
+                .ok()
+        })
         .filter(|e| e.file_type().is_file())
-        .for_each(|entry| {
+        .take_while(|_| !cancelled())
+        .enumerate()
+        .for_each(|(index, entry)| {
             let path = entry.path();
 
-            if path.starts_with(&override_dir) {
-                process_loose_file(path, &mut conflicts);
-            } else if is_erf_file(path) {
-                if let Err(err) = process_erf_file(path, &mut conflicts) {
-                    eprintln!(
-                        "Warning: Failed to process ERF file {}: {}",
-                        path.display(),
-                        err
-                    );
+            if let Some(progress) = progress
+                && index % PROGRESS_INTERVAL == 0
+            {
+                let _ = progress.send(ScanProgress {
+                    files_seen: index + 1,
+                    current_path: path.to_path_buf(),
+                    ..Default::default()
+                });
+            }
+
+            if is_disabled(path) {
+                let name = path.with_extension("");
+                let name = name.file_name().unwrap_or_default().to_string_lossy();
+                let key = conflict_key(&name, path, bioware_dir, options);
+                disabled.push((key, path.to_path_buf()));
+            } else if override_root(path, bioware_dir).is_some()
+                || (options.loose_anywhere && SourceKind::of(path) == SourceKind::Loose)
+            {
+                if options.skip_empty && entry.metadata().is_ok_and(|m| m.len() == 0) {
+                    stats.skipped_empty += 1;
+                } else {
+                    stats.loose_files += 1;
+                    process_loose_file(path, bioware_dir, options, &mut conflicts);
+                }
+            } else if SourceKind::of(path) == SourceKind::Archive {
+                archives.push(path.to_path_buf());
+            } else if is_addin_manifest(path, bioware_dir) {
+                match AddinManifest::open(path) {
+                    Ok(manifest) => {
+                        if let Some(addin_dir) = path.parent() {
+                            addins.insert(addin_dir.to_path_buf(), manifest.label());
+                        }
+                    }
+                    Err(err) => warnings.push(ScanWarning::new(path, &err)),
                 }
             }
         });
 
-    conflicts.retain(|key, paths| paths.len() > 1 && !should_ignore(key));
+    if cancelled() {
+        return Err(ScanError::Cancelled);
+    }
+    warnings.append(&mut walk_errors);
+
+    let files_seen = loose_files_seen(&stats, &archives, &disabled);
+    let mut toc_cache = options
+        .toc_cache
+        .as_deref()
+        .map(TocCache::load)
+        .unwrap_or_default();
+    let erf_warnings = process_erf_files(
+        &archives,
+        bioware_dir,
+        options,
+        &mut toc_cache,
+        &mut conflicts,
+        &mut stats,
+        |erfs_parsed, path| {
+            if let Some(progress) = progress {
+                let _ = progress.send(ScanProgress {
+                    files_seen,
+                    erfs_parsed,
+                    erfs_total: Some(archives.len()),
+                    current_path: path.to_path_buf(),
+                    ..Default::default()
+                });
+            }
+            !cancelled()
+        },
+    );
+    warnings.extend(erf_warnings);
+    if cancelled() {
+        return Err(ScanError::Cancelled);
+    }
 
+    // An archive listing a name twice, or a file reached twice, is one source
     for paths in conflicts.values_mut() {
         paths.sort();
+        paths.dedup();
+    }
+    let resource_index = conflicts.clone();
+
+    stats.resources = conflicts.len();
+    stats.providers = conflicts.values().map(Vec::len).sum();
+    let mut single_providers = HashMap::new();
+    conflicts.retain(|key, paths| keep_conflict(key, paths, options, &mut single_providers));
+
+    if options.dedup_hardlinks {
+        for paths in conflicts.values_mut() {
+            stats.collapsed_links += dedup_same_files(paths);
+        }
+        conflicts.retain(|key, paths| keep_conflict(key, paths, options, &mut single_providers));
+    }
+
+    archives.sort();
+    if let Some(cache_path) = &options.toc_cache
+        && !options.override_only
+    {
+        toc_cache.prune(bioware_dir, &archives);
+        let _ = toc_cache.save(cache_path);
+    }
+    warnings.sort_by(|a, b| a.path.cmp(&b.path));
+    let failed_archives = warnings
+        .iter()
+        .filter(|warning| SourceKind::of(&warning.path) == SourceKind::Archive)
+        .map(|warning| warning.path.clone())
+        .collect();
+    stats.archives = archives.len();
+    disabled.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    let compared_contents = options.detect_divergence || options.hide_identical;
+    let classification = if compared_contents {
+        classify_trusting(
+            &conflicts,
+            &options.known_identical,
+            unix_timestamp(),
+            |groups_hashed, path| {
+                if let Some(progress) = progress {
+                    let _ = progress.send(ScanProgress {
+                        files_seen,
+                        erfs_parsed: archives.len(),
+                        erfs_total: Some(archives.len()),
+                        groups_hashed,
+                        groups_total: Some(conflicts.len()),
+                        current_path: path.to_path_buf(),
+                    });
+                }
+            },
+        )
+    } else {
+        Default::default()
+    };
+
+    let Classification {
+        mut divergence,
+        trusted: mut trusted_identical,
+        warnings: compare_warnings,
+    } = classification;
+    // Unlike unreadable archives these aren't retried, so they only join the
+    // warnings once failed archives were picked out
+    warnings.extend(compare_warnings);
+    warnings.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if options.hide_identical {
+        let before = conflicts.len();
+        conflicts.retain(|key, _| divergence.get(key) != Some(&Divergence::Identical));
+        stats.hidden_identical = before - conflicts.len();
+        divergence.retain(|key, _| conflicts.contains_key(key));
+        trusted_identical.retain(|key| conflicts.contains_key(key));
+    }
+
+    let source_info = collect_source_info(&conflicts);
+
+    Ok(ScanReport {
+        root: bioware_dir.to_path_buf(),
+        override_only: options.override_only,
+        source_info,
+        conflicts,
+        archives,
+        disabled,
+        addins,
+        divergence,
+        compared_contents,
+        trusted_identical,
+        failed_archives,
+        warnings,
+        single_providers,
+        resource_index,
+        stats,
+    })
+}
+
+/// Parses `archives` again, typically ones that failed during the last scan,
+/// and folds their resources into `conflicts`.
+///
+/// A resource that previously had one provider becomes a conflict when a
+/// retried archive also provides it. Content classification is not updated.
+pub fn retry_archives(
+    archives: &[PathBuf],
+    bioware_dir: &Path,
+    options: &ScanOptions,
+    conflicts: &mut Conflicts,
+    single_providers: &mut HashMap<String, PathBuf>,
+    resource_index: &mut Conflicts,
+) -> RetryOutcome {
+    let mut found = Conflicts::new();
+    let mut outcome = RetryOutcome::default();
+    let mut stats = ScanStats::default();
+
+    for path in archives {
+        let no_cache = TocCache::default();
+        if let Err(err) = process_erf_file(
+            path,
+            bioware_dir,
+            options,
+            &no_cache,
+            &mut found,
+            &mut stats,
+        ) {
+            outcome
+                .still_failing
+                .push(ScanWarning::from_scan_error(path, &err));
+        }
+    }
+
+    resource_index.keep_entry_names(&found);
+    conflicts.keep_entry_names(&found);
+    for (key, paths) in &found {
+        let sources = resource_index.entry(key.clone()).or_default();
+        sources.extend(paths.iter().cloned());
+        sources.sort();
+        sources.dedup();
+    }
+
+    for (key, mut paths) in found {
+        if let Some(existing) = conflicts.get_mut(&key) {
+            existing.extend(paths);
+            existing.sort();
+            existing.dedup();
+            outcome.changed.push(key);
+            continue;
+        }
+
+        paths.extend(single_providers.remove(&key));
+        paths.sort();
+        paths.dedup();
+        if keep_conflict(&key, &mut paths, options, single_providers) {
+            conflicts.insert(key.clone(), paths);
+            outcome.changed.push(key);
+        }
+    }
+
+    outcome.changed.sort();
+    outcome
+}
+
+/// Whether a group of sources is a reportable conflict, moving a lone source
+/// into `single_providers` when it isn't.
+fn keep_conflict(
+    key: &str,
+    paths: &mut Vec<PathBuf>,
+    options: &ScanOptions,
+    single_providers: &mut HashMap<String, PathBuf>,
+) -> bool {
+    if should_ignore(key, options) {
+        return false;
+    }
+
+    if paths.len() == 1
+        && let Some(path) = paths.pop()
+    {
+        single_providers.insert(key.to_string(), path);
+    }
+
+    paths.len() > 1
+}
+
+/// Files the walk went through, for progress reporting.
+fn loose_files_seen(
+    stats: &ScanStats,
+    archives: &[PathBuf],
+    disabled: &[(String, PathBuf)],
+) -> usize {
+    stats.loose_files + stats.skipped_empty + archives.len() + disabled.len()
+}
+
+/// Keeps only the first of several paths pointing at the same file on disk
+/// (hardlinks), returning how many were removed.
+fn dedup_same_files(paths: &mut Vec<PathBuf>) -> usize {
+    let mut seen: Vec<Handle> = Vec::with_capacity(paths.len());
+    let before = paths.len();
+
+    paths.retain(|path| match Handle::from_path(path) {
+        Ok(handle) if seen.contains(&handle) => false,
+        Ok(handle) => {
+            seen.push(handle);
+            true
+        }
+        // Unreadable sources are kept, they can't be proven identical
+        Err(_) => true,
+    });
+
+    before - paths.len()
+}
+
+/// Reads the [`SourceInfo`] of every conflict source, opening each archive once.
+fn collect_source_info(conflicts: &Conflicts) -> HashMap<(String, PathBuf), SourceInfo> {
+    let mut files: HashMap<&Path, SourceInfo> = HashMap::new();
+    let mut archives: HashMap<&Path, Option<Archive>> = HashMap::new();
+    let mut info = HashMap::new();
+
+    for (key, paths) in conflicts {
+        for path in paths {
+            let mut source = *files.entry(path).or_insert_with(|| {
+                fs::metadata(path).map_or_else(
+                    |_| SourceInfo::default(),
+                    |metadata| SourceInfo {
+                        size: metadata.len(),
+                        modified: metadata
+                            .modified()
+                            .ok()
+                            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                            .map(|since| since.as_secs()),
+                        resource_length: None,
+                    },
+                )
+            });
+
+            if SourceKind::of(path) == SourceKind::Archive {
+                source.resource_length = archives
+                    .entry(path)
+                    .or_insert_with(|| Archive::open(path).ok())
+                    .as_ref()
+                    .and_then(|archive| archive.length(conflicts.entry_name(key, path)));
+            }
+
+            info.insert((key.clone(), path.clone()), source);
+        }
     }
 
-    Ok(conflicts)
+    info
 }
 
-fn process_loose_file(path: &Path, conflicts: &mut Conflicts) {
+fn process_loose_file(path: &Path, root: &Path, options: &ScanOptions, conflicts: &mut Conflicts) {
     if let Some(file_name) = path.file_name() {
-        conflicts
-            .entry(file_name.to_string_lossy().into_owned())
-            .or_default()
-            .push(path.to_path_buf());
+        let name = file_name.to_string_lossy();
+        let key = conflict_key(&name, path, root, options);
+        conflicts.add_source(key, path.to_path_buf(), &name);
     }
 }
 
+/// Whether an archive ships with the base game, i.e. lives in `packages/core`
+/// outside the override folder, rather than in an add-in or DLC.
+pub fn is_core_archive(path: &Path, bioware_dir: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(bioware_dir) else {
+        return false;
+    };
+    let mut components = relative.iter().map(|c| c.to_string_lossy().to_lowercase());
+
+    components.next().as_deref() == Some("packages")
+        && components.next().as_deref() == Some("core")
+        && components.next().as_deref() != Some(OVERRIDE_DIR)
+        && SourceKind::of(path) == SourceKind::Archive
+}
+
+/// The override folder a loose file is loaded from, `packages/<package>/override`
+/// for one of [`OVERRIDE_PACKAGES`] or `AddIns/<name>/<core|module>/override`.
+pub fn override_root(path: &Path, bioware_dir: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(bioware_dir).ok()?;
+    let components: Vec<_> = relative.iter().take(5).collect();
+    let is = |index: usize, names: &[&str]| {
+        components.get(index).is_some_and(|component| {
+            names
+                .iter()
+                .any(|name| component.eq_ignore_ascii_case(name))
+        })
+    };
+
+    // The override folder itself and the file inside it
+    let depth = if is(0, &[PACKAGES_DIR]) && is(1, OVERRIDE_PACKAGES) && is(2, &[OVERRIDE_DIR]) {
+        3
+    } else if is(0, &[ADDINS_DIR]) && is(2, ADDIN_CONTENT_DIRS) && is(3, &[OVERRIDE_DIR]) {
+        4
+    } else {
+        return None;
+    };
+
+    (components.len() > depth).then(|| {
+        components[..depth]
+            .iter()
+            .fold(bioware_dir.to_path_buf(), |dir, c| dir.join(c))
+    })
+}
+
+/// The package a loose file in `packages/<package>/override` belongs to.
+pub fn override_package(path: &Path, bioware_dir: &Path) -> Option<String> {
+    override_root(path, bioware_dir)
+        .filter(|_| addin_root(path, bioware_dir).is_none())
+        .and_then(|root| Some(root.parent()?.file_name()?.to_string_lossy().into_owned()))
+}
+
+/// Every existing override folder, walked instead of the whole tree by
+/// override-only scans.
+fn override_dirs(bioware_dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = OVERRIDE_PACKAGES
+        .iter()
+        .map(|package| {
+            bioware_dir
+                .join(PACKAGES_DIR)
+                .join(package)
+                .join(OVERRIDE_DIR)
+        })
+        .collect();
+
+    let addins = fs::read_dir(bioware_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().eq_ignore_ascii_case(ADDINS_DIR))
+        .flat_map(|entry| fs::read_dir(entry.path()).into_iter().flatten())
+        .filter_map(Result::ok);
+    for addin in addins {
+        dirs.extend(
+            ADDIN_CONTENT_DIRS
+                .iter()
+                .map(|content| addin.path().join(content).join(OVERRIDE_DIR)),
+        );
+    }
+
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+/// Returns the `AddIns/<name>` folder that `path` belongs to, if any.
+pub fn addin_root(path: &Path, bioware_dir: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(bioware_dir).ok()?;
+    let mut components = relative.iter();
+
+    let addins_dir = components.next()?;
+    if !addins_dir.eq_ignore_ascii_case(ADDINS_DIR) {
+        return None;
+    }
+
+    let name = components.next()?;
+    Some(bioware_dir.join(addins_dir).join(name))
+}
+
+fn is_addin_manifest(path: &Path, bioware_dir: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case(MANIFEST_FILE))
+        && addin_root(path, bioware_dir).as_deref() == path.parent()
+}
+
 fn is_erf_file(path: &Path) -> bool {
     path.extension()
-        .map_or(false, |ext| ext.eq_ignore_ascii_case("erf"))
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("erf"))
+}
+
+fn is_rim_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("rim"))
+}
+
+/// Reads the archives on a pool of threads and merges what each found in
+/// archive order, so the result doesn't depend on how the work was scheduled.
+///
+/// Each worker stops early once `on_parsed` returns `false`.
+fn process_erf_files(
+    archives: &[PathBuf],
+    root: &Path,
+    options: &ScanOptions,
+    cache: &mut TocCache,
+    conflicts: &mut Conflicts,
+    stats: &mut ScanStats,
+    on_parsed: impl Fn(usize, &Path) -> bool + Sync,
+) -> Vec<ScanWarning> {
+    let mut warnings = Vec::new();
+    if archives.is_empty() {
+        return warnings;
+    }
+
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = archives.len().div_ceil(workers);
+    let parsed = AtomicUsize::new(0);
+    let (parsed, on_parsed, shared_cache) = (&parsed, &on_parsed, &*cache);
+
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = archives
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let _priority = options.background_priority.then(BackgroundPriority::enter);
+                    let mut found = Conflicts::new();
+                    let mut stats = ScanStats::default();
+                    let mut failed = Vec::new();
+                    let mut parsed_tocs = Vec::new();
+
+                    for path in chunk {
+                        match process_erf_file(
+                            path,
+                            root,
+                            options,
+                            shared_cache,
+                            &mut found,
+                            &mut stats,
+                        ) {
+                            Ok(Some(toc)) => parsed_tocs.push((path.clone(), toc)),
+                            Ok(None) => {}
+                            Err(err) => failed.push(ScanWarning::from_scan_error(path, &err)),
+                        }
+                        if !on_parsed(parsed.fetch_add(1, Ordering::Relaxed) + 1, path) {
+                            break;
+                        }
+                    }
+
+                    (found, stats, failed, parsed_tocs)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    });
+
+    for (found, chunk_stats, failed, parsed_tocs) in results {
+        conflicts.keep_entry_names(&found);
+        for (key, paths) in found {
+            conflicts.entry(key).or_default().extend(paths);
+        }
+        for (path, toc) in parsed_tocs {
+            cache.insert(path, toc);
+        }
+        stats.skipped_empty += chunk_stats.skipped_empty;
+        stats.failed_archives += failed.len();
+        warnings.extend(failed);
+    }
+
+    warnings
 }
 
-fn process_erf_file(path: &Path, conflicts: &mut Conflicts) -> Result<(), ScanError> {
-    let erf = ErfFile::open(path).map_err(|source| ScanError::ErfError {
-        path: path.to_path_buf(),
-        source,
-    })?;
+/// Folds the resources of an archive into `conflicts`, reading them from
+/// `cache` when the archive is unchanged.
+///
+/// Returns the table of contents to cache when the archive had to be parsed.
+fn process_erf_file(
+    path: &Path,
+    root: &Path,
+    options: &ScanOptions,
+    cache: &TocCache,
+    conflicts: &mut Conflicts,
+    stats: &mut ScanStats,
+) -> Result<Option<CachedToc>, ScanError> {
+    let metadata = fs::metadata(path).ok();
+    let cached = metadata
+        .as_ref()
+        .and_then(|metadata| cache.get(path, metadata));
+
+    let (resources, parsed) = match cached {
+        Some(resources) => (resources.to_vec(), None),
+        None => {
+            let archive = Archive::open(path).map_err(|source| ScanError::ErfError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            let resources: Vec<(String, u32)> = archive
+                .entries()
+                .into_iter()
+                .map(|(name, length)| (name.to_string(), length))
+                .collect();
+            let toc = metadata
+                .as_ref()
+                .and_then(|metadata| CachedToc::new(metadata, resources.clone()));
+            (resources, toc)
+        }
+    };
 
-    for entry in erf.toc {
-        conflicts
-            .entry(entry.name)
-            .or_default()
-            .push(path.to_path_buf());
+    for (name, length) in &resources {
+        if options.skip_empty && *length == 0 {
+            stats.skipped_empty += 1;
+            continue;
+        }
+
+        let key = conflict_key(name, path, root, options);
+        conflicts.add_source(key, path.to_path_buf(), name);
     }
 
-    Ok(())
+    Ok(parsed)
+}
+
+pub fn default_ignored_names() -> BTreeSet<String> {
+    DEFAULT_IGNORED_NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn should_ignore(key: &str, options: &ScanOptions) -> bool {
+    options
+        .ignored_names
+        .contains(&resource_name(key).to_ascii_lowercase())
+        || options
+            .ignore_patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern, resource_name(key)))
+}
+
+/// Matches a resource name against a pattern where `*` stands for any run of
+/// characters and `?` for one, ignoring case.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.trim().to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was and how much of the name it had taken
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, taken)) => {
+                    p = star + 1;
+                    n = taken + 1;
+                    backtrack = Some((star, taken + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Returns the resource name a conflict key was built from.
+pub fn resource_name(key: &str) -> &str {
+    match key.rsplit_once(" [") {
+        Some((name, language)) if language.ends_with(']') => name,
+        _ => key,
+    }
+}
+
+/// Builds the conflict key for a resource provided by `source`.
+///
+/// Resources that live under a localized folder or archive (e.g. `vo/fr-fr/` or
+/// `foo_fr-fr.erf`) are keyed per language, so the same name shipped for two
+/// languages is not reported as a conflict. Names that already carry a language
+/// suffix are distinct on their own and are kept as-is.
+///
+/// Names are lowercased since the game looks resources up ignoring case, so
+/// `Armor.gda` and `armor.gda` are the same conflict.
+fn conflict_key(name: &str, source: &Path, root: &Path, options: &ScanOptions) -> String {
+    let redirected = options.resolution.redirect(name);
+    let name = redirected.as_deref().unwrap_or(name).to_lowercase();
+    let name = name.as_str();
+
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let name = match options.grouping {
+        Grouping::ExactName => name,
+        Grouping::BaseName => stem,
+    };
+    if language_of(stem, &options.languages).is_some() {
+        return name.to_string();
+    }
+
+    match source_language(source, root, &options.languages) {
+        Some(language) => format!("{name} [{language}]"),
+        None => name.to_string(),
+    }
+}
+
+fn source_language<'a>(source: &Path, root: &Path, languages: &'a [String]) -> Option<&'a str> {
+    let relative = source.strip_prefix(root).unwrap_or(source);
+
+    relative.iter().find_map(|component| {
+        let component = Path::new(component);
+        let stem = component.file_stem().unwrap_or(component.as_os_str());
+        language_of(&stem.to_string_lossy(), languages)
+    })
+}
+
+/// Returns the language token `stem` is localized for, either as a `_<lang>`
+/// suffix or as the whole stem (language folders).
+fn language_of<'a>(stem: &str, languages: &'a [String]) -> Option<&'a str> {
+    let stem = stem.to_ascii_lowercase();
+
+    languages.iter().map(String::as_str).find(|language| {
+        let language = language.to_ascii_lowercase();
+        stem == language
+            || stem
+                .strip_suffix(language.as_str())
+                .is_some_and(|rest| rest.ends_with('_'))
+    })
 }
 
-fn should_ignore(name: &str) -> bool {
-    let lowercase_name = name.to_ascii_lowercase();
-    IGNORED_FILES.iter().any(|&f| f == lowercase_name)
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::{erf_v20, write};
+
+    const OVERRIDE: &str = "packages/core/override";
+
+    fn scan(root: &Path, options: &ScanOptions) -> ScanReport {
+        scan_for_conflicts(root, options, None, None).expect("scan succeeds")
+    }
+
+    fn with_languages() -> ScanOptions {
+        ScanOptions {
+            languages: vec!["en-us".to_string(), "fr-fr".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn keys(conflicts: &Conflicts) -> Vec<&str> {
+        let mut keys: Vec<_> = conflicts.keys().map(String::as_str).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn summary_counts_files_sources_and_the_most_contested_resource() {
+        let core = PathBuf::from("data/core.erf");
+        let conflicts: Conflicts = [
+            (
+                "b.uti",
+                vec![
+                    core.clone(),
+                    "override/mod_a/b.uti".into(),
+                    "override/mod_b/b.uti".into(),
+                ],
+            ),
+            (
+                "a.uti",
+                vec![
+                    core.clone(),
+                    "override/mod_a/a.uti".into(),
+                    "override/mod_c/a.uti".into(),
+                ],
+            ),
+            ("c.gda", vec![core, "data/patch.rim".into()]),
+        ]
+        .into_iter()
+        .map(|(key, paths)| (key.to_string(), paths))
+        .collect();
+
+        let summary = summarize_conflicts(&conflicts);
+
+        assert_eq!(
+            summary,
+            ConflictsSummary {
+                conflicts: 3,
+                files: 6,
+                loose_sources: 4,
+                packed_sources: 4,
+                most_conflicted: Some(("a.uti".to_string(), 3)),
+            }
+        );
+        assert_eq!(
+            summary.describe(),
+            "3 conflicts across 6 files (4 loose, 4 packed sources), \
+             most contested: a.uti (3 sources)"
+        );
+    }
+
+    #[test]
+    fn empty_summary_says_there_are_no_conflicts() {
+        let summary = summarize_conflicts(&Conflicts::new());
+
+        assert_eq!(summary, ConflictsSummary::default());
+        assert_eq!(summary.describe(), "No conflicts");
+    }
+
+    #[test]
+    fn language_variants_do_not_conflict_but_same_language_copies_do() {
+        let root = TempDir::new().unwrap();
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/vo/en-us/line.fsb"),
+            b"a",
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/vo/fr-fr/line.fsb"),
+            b"b",
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_b/vo/en-us/line.fsb"),
+            b"c",
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/names_en-us.tlk"),
+            b"d",
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_b/names_fr-fr.tlk"),
+            b"e",
+        );
+
+        let report = scan(root.path(), &with_languages());
+
+        assert_eq!(keys(&report.conflicts), ["line.fsb [en-us]"]);
+        assert_eq!(report.conflicts["line.fsb [en-us]"].len(), 2);
+    }
+
+    #[test]
+    fn disabled_files_keep_the_key_they_were_disabled_from() {
+        let root = TempDir::new().unwrap();
+        let disabled = write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/vo/fr-fr/Line.fsb.disabled"),
+            b"a",
+        );
+
+        let report = scan(root.path(), &with_languages());
+
+        assert_eq!(
+            report.disabled,
+            [("line.fsb [fr-fr]".to_string(), disabled)]
+        );
+        assert!(report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn language_suffixed_names_are_kept_as_is() {
+        let options = with_languages();
+        let root = Path::new("game");
+
+        let key = |name: &str, source: &str| conflict_key(name, Path::new(source), root, &options);
+
+        assert_eq!(
+            key("names_en-us.tlk", "game/override/names_en-us.tlk"),
+            "names_en-us.tlk"
+        );
+        assert_ne!(
+            key("names_en-us.tlk", "game/override/names_en-us.tlk"),
+            key("names_fr-fr.tlk", "game/override/names_fr-fr.tlk")
+        );
+        assert_eq!(
+            key("Line.fsb", "game/vo/fr-fr/Line.fsb"),
+            "line.fsb [fr-fr]"
+        );
+    }
+
+    #[test]
+    fn base_name_grouping_merges_types_that_exact_names_keep_apart() {
+        let root = TempDir::new().unwrap();
+        let archive = write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[("Foo.uti", b"item")]),
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/foo.utc"),
+            b"creature",
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_b/foo.utc"),
+            b"creature",
+        );
+
+        let exact = scan(root.path(), &ScanOptions::default());
+        assert_eq!(keys(&exact.conflicts), ["foo.utc"]);
+
+        let base = scan(
+            root.path(),
+            &ScanOptions {
+                grouping: Grouping::BaseName,
+                ..Default::default()
+            },
+        );
+        assert_eq!(keys(&base.conflicts), ["foo"]);
+        assert_eq!(base.conflicts["foo"].len(), 3);
+        assert_eq!(base.conflicts.entry_name("foo", &archive), "Foo.uti");
+        assert_eq!(
+            crate::content::read_source(&archive, base.conflicts.entry_name("foo", &archive))
+                .unwrap(),
+            b"item"
+        );
+    }
+
+    #[derive(Debug)]
+    struct MorphRedirect;
+
+    impl ResolutionRule for MorphRedirect {
+        fn redirect(&self, name: &str) -> Option<String> {
+            name.eq_ignore_ascii_case("chargen_alt.mor")
+                .then(|| "chargen_default.mor".to_string())
+        }
+    }
+
+    #[test]
+    fn resolution_rule_redirects_a_name_into_another_conflict() {
+        let root = TempDir::new().unwrap();
+        let archive = write(
+            root.path(),
+            "packages/core/data/morphs.erf",
+            &erf_v20(&[("Chargen_Alt.mor", b"alt")]),
+        );
+        write(
+            root.path(),
+            &format!("{OVERRIDE}/mod_a/chargen_default.mor"),
+            b"default",
+        );
+
+        let plain = scan(root.path(), &ScanOptions::default());
+        assert!(plain.conflicts.is_empty());
+
+        let report = scan(
+            root.path(),
+            &ScanOptions {
+                resolution: Arc::new(MorphRedirect),
+                ..Default::default()
+            },
+        );
+        assert_eq!(keys(&report.conflicts), ["chargen_default.mor"]);
+        assert_eq!(
+            report.conflicts.entry_name("chargen_default.mor", &archive),
+            "Chargen_Alt.mor"
+        );
+    }
+
+    #[test]
+    fn hardlinks_collapse_into_one_source() {
+        let root = TempDir::new().unwrap();
+        let original = write(root.path(), &format!("{OVERRIDE}/mod_a/armor.uti"), b"a");
+        let link = root.path().join(OVERRIDE).join("mod_b/armor.uti");
+        fs::create_dir_all(link.parent().unwrap()).unwrap();
+        // Some filesystems can't hold hardlinks, nothing to check there
+        if fs::hard_link(&original, &link).is_err() {
+            return;
+        }
+
+        let linked = scan(root.path(), &ScanOptions::default());
+        assert_eq!(keys(&linked.conflicts), ["armor.uti"]);
+
+        let deduped = scan(
+            root.path(),
+            &ScanOptions {
+                dedup_hardlinks: true,
+                ..Default::default()
+            },
+        );
+        assert!(deduped.conflicts.is_empty());
+        assert_eq!(deduped.stats.collapsed_links, 1);
+    }
+
+    #[test]
+    fn retrying_a_now_readable_archive_adds_its_resources() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), &format!("{OVERRIDE}/mod_a/a.uti"), b"1");
+        write(root.path(), &format!("{OVERRIDE}/mod_a/b.gda"), b"2");
+        write(root.path(), &format!("{OVERRIDE}/mod_b/b.gda"), b"3");
+        let archive = write(root.path(), "packages/core/data/patch.erf", b"locked");
+        let options = ScanOptions::default();
+
+        let mut report = scan(root.path(), &options);
+        assert_eq!(keys(&report.conflicts), ["b.gda"]);
+        assert_eq!(report.failed_archives, std::slice::from_ref(&archive));
+
+        write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[("A.uti", b"4"), ("b.gda", b"5"), ("c.gda", b"6")]),
+        );
+        let outcome = retry_archives(
+            &report.failed_archives,
+            root.path(),
+            &options,
+            &mut report.conflicts,
+            &mut report.single_providers,
+            &mut report.resource_index,
+        );
+
+        assert!(outcome.still_failing.is_empty());
+        assert_eq!(outcome.changed, ["a.uti", "b.gda"]);
+        assert_eq!(keys(&report.conflicts), ["a.uti", "b.gda"]);
+        assert!(report.conflicts["a.uti"].contains(&archive));
+        assert_eq!(report.conflicts["b.gda"].len(), 3);
+        assert_eq!(report.resource_index["c.gda"], [archive]);
+    }
+
+    #[test]
+    fn override_only_mode_skips_archives() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), &format!("{OVERRIDE}/mod_a/a.uti"), b"1");
+        write(root.path(), &format!("{OVERRIDE}/mod_b/a.uti"), b"2");
+        write(root.path(), &format!("{OVERRIDE}/mod_a/b.gda"), b"3");
+        write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[("b.gda", b"4"), ("c.gda", b"5")]),
+        );
+        write(
+            root.path(),
+            "packages/core/data/more.erf",
+            &erf_v20(&[("c.gda", b"6")]),
+        );
+
+        let full = scan(root.path(), &ScanOptions::default());
+        assert_eq!(keys(&full.conflicts), ["a.uti", "b.gda", "c.gda"]);
+        assert!(!full.override_only);
+
+        let fast = scan(
+            root.path(),
+            &ScanOptions {
+                override_only: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(keys(&fast.conflicts), ["a.uti"]);
+        assert!(fast.override_only);
+        assert!(fast.archives.is_empty());
+    }
+
+    #[test]
+    fn totals_count_every_resource_and_provider() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), &format!("{OVERRIDE}/mod_a/a.uti"), b"1");
+        write(root.path(), &format!("{OVERRIDE}/mod_b/a.uti"), b"2");
+        write(root.path(), &format!("{OVERRIDE}/mod_a/b.uti"), b"3");
+        write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[("a.uti", b"4"), ("c.gda", b"5")]),
+        );
+
+        let report = scan(root.path(), &ScanOptions::default());
+
+        assert_eq!(keys(&report.conflicts), ["a.uti"]);
+        assert_eq!(report.stats.resources, 3);
+        assert_eq!(report.stats.providers, 5);
+        assert_eq!(report.stats.loose_files, 3);
+        assert_eq!(report.stats.archives, 1);
+    }
+
+    #[test]
+    fn a_name_listed_twice_in_one_archive_counts_once() {
+        let root = TempDir::new().unwrap();
+        let archive = write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[
+                ("a.uti", b"1"),
+                ("A.UTI", b"2"),
+                ("b.gda", b"3"),
+                ("b.gda", b"4"),
+            ]),
+        );
+        let loose = write(root.path(), &format!("{OVERRIDE}/mod_a/a.uti"), b"5");
+
+        let report = scan(root.path(), &ScanOptions::default());
+
+        assert_eq!(keys(&report.conflicts), ["a.uti"]);
+        let mut expected = vec![archive, loose];
+        expected.sort();
+        assert_eq!(report.conflicts["a.uti"], expected);
+    }
+
+    #[test]
+    fn zero_byte_sources_are_skipped_only_when_asked() {
+        let root = TempDir::new().unwrap();
+        write(root.path(), &format!("{OVERRIDE}/mod_a/stub.uti"), b"");
+        write(root.path(), &format!("{OVERRIDE}/mod_b/stub.uti"), b"");
+        write(
+            root.path(),
+            "packages/core/data/patch.erf",
+            &erf_v20(&[("stub.uti", b""), ("real.gda", b"x")]),
+        );
+        write(root.path(), &format!("{OVERRIDE}/mod_a/real.gda"), b"y");
+
+        let kept = scan(root.path(), &ScanOptions::default());
+        assert_eq!(keys(&kept.conflicts), ["real.gda", "stub.uti"]);
+        assert_eq!(kept.conflicts["stub.uti"].len(), 3);
+        assert_eq!(kept.stats.skipped_empty, 0);
+
+        let skipped = scan(
+            root.path(),
+            &ScanOptions {
+                skip_empty: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(keys(&skipped.conflicts), ["real.gda"]);
+        assert_eq!(skipped.stats.skipped_empty, 3);
+    }
 }
@@ -0,0 +1,159 @@
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use anyhow::{Context, Result as AnyhowResult};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::AppConfig, scanner::Conflicts};
+
+/// How many past scans are kept on disk.
+pub const MAX_SNAPSHOTS: usize = 20;
+
+/// The conflicts found by one scan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub timestamp: u64,
+    pub conflicts: Conflicts,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnapshotHistory {
+    snapshots: Vec<Snapshot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderChange {
+    Added,
+    Removed,
+}
+
+/// A source that started or stopped providing a resource at some scan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderEvent {
+    /// 1-based position of the scan in the history.
+    pub scan: usize,
+    pub timestamp: u64,
+    pub path: PathBuf,
+    pub change: ProviderChange,
+}
+
+/// Loads the stored scan history, oldest first.
+pub fn load_snapshots() -> AnyhowResult<Vec<Snapshot>> {
+    let path = snapshots_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path).context("Failed to read scan history")?;
+    let history: SnapshotHistory =
+        toml::from_str(&contents).context("Failed to parse scan history")?;
+    Ok(history.snapshots)
+}
+
+/// Appends a snapshot to the history, dropping the oldest beyond [`MAX_SNAPSHOTS`].
+pub fn record_snapshot(snapshots: &mut Vec<Snapshot>, snapshot: Snapshot) -> AnyhowResult<()> {
+    snapshots.push(snapshot);
+    if snapshots.len() > MAX_SNAPSHOTS {
+        snapshots.drain(..snapshots.len() - MAX_SNAPSHOTS);
+    }
+
+    let path = snapshots_path()?;
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir).context("Failed to create config directory")?;
+    }
+
+    let history = SnapshotHistory {
+        snapshots: snapshots.clone(),
+    };
+    let contents = toml::to_string(&history).context("Failed to serialize scan history")?;
+    fs::write(&path, contents).context("Failed to write scan history")
+}
+
+/// Reconstructs when each source started and stopped providing `key`.
+///
+/// Snapshots only hold conflicts, so a resource missing from a scan had at most
+/// one provider then and its sources show up as removed.
+pub fn provider_timeline(snapshots: &[Snapshot], key: &str) -> Vec<ProviderEvent> {
+    let mut events = Vec::new();
+    let mut previous = BTreeSet::new();
+
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let current: BTreeSet<_> = snapshot
+            .conflicts
+            .get(key)
+            .map(|paths| paths.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let mut push = |path: &PathBuf, change| {
+            events.push(ProviderEvent {
+                scan: index + 1,
+                timestamp: snapshot.timestamp,
+                path: path.clone(),
+                change,
+            })
+        };
+        current
+            .difference(&previous)
+            .for_each(|p| push(p, ProviderChange::Added));
+        previous
+            .difference(&current)
+            .for_each(|p| push(p, ProviderChange::Removed));
+
+        previous = current;
+    }
+
+    events
+}
+
+fn snapshots_path() -> AnyhowResult<PathBuf> {
+    Ok(AppConfig::config_dir()?.join("snapshots.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(timestamp: u64, sources: &[&str]) -> Snapshot {
+        let conflicts = (sources.len() > 1)
+            .then(|| {
+                (
+                    "a.uti".to_string(),
+                    sources.iter().map(PathBuf::from).collect(),
+                )
+            })
+            .into_iter()
+            .collect();
+        Snapshot {
+            timestamp,
+            conflicts,
+        }
+    }
+
+    #[test]
+    fn reconstructs_when_providers_came_and_went() {
+        let snapshots = [
+            snapshot(10, &[]),
+            snapshot(20, &["base/a.uti", "modx/a.uti"]),
+            snapshot(30, &["base/a.uti", "modx/a.uti"]),
+            snapshot(40, &["base/a.uti", "mody/a.uti"]),
+            snapshot(50, &[]),
+        ];
+
+        let event = |scan, timestamp, path: &str, change| ProviderEvent {
+            scan,
+            timestamp,
+            path: PathBuf::from(path),
+            change,
+        };
+        assert_eq!(
+            provider_timeline(&snapshots, "a.uti"),
+            [
+                event(2, 20, "base/a.uti", ProviderChange::Added),
+                event(2, 20, "modx/a.uti", ProviderChange::Added),
+                event(4, 40, "mody/a.uti", ProviderChange::Added),
+                event(4, 40, "modx/a.uti", ProviderChange::Removed),
+                event(5, 50, "base/a.uti", ProviderChange::Removed),
+                event(5, 50, "mody/a.uti", ProviderChange::Removed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    io::Result as IoResult,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    content::Divergence,
+    loadorder::{resolve_winner, shadows_core},
+    scanner::{Conflicts, ScanStats, SourceKind, resource_name},
+    view::is_actionable,
+};
+
+/// Aggregated numbers shown on the post-scan summary screen.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanSummary {
+    pub total: usize,
+    pub unresolved: usize,
+    pub actionable: usize,
+    pub unexpected_winners: usize,
+    /// Conflicts where a loose override replaces base-game content.
+    pub core_shadowing: usize,
+    pub divergent: usize,
+    pub identical: usize,
+    /// Conflicts per resource extension, most common first.
+    pub by_type: Vec<(String, usize)>,
+    /// Bytes held by loose files that lose their conflict and could be removed.
+    pub reclaimable_bytes: u64,
+    pub failed_archives: usize,
+    pub skipped_empty: usize,
+    pub resources: usize,
+    pub providers: usize,
+    pub archives: usize,
+    pub loose_files: usize,
+}
+
+/// Sums up a scan. `is_unresolved` tells which conflicts still need a look,
+/// the same test the status line counts with.
+pub fn summarize(
+    bioware_dir: &Path,
+    conflicts: &Conflicts,
+    is_unresolved: impl Fn(&str, &[PathBuf]) -> bool,
+    divergence: &HashMap<String, Divergence>,
+    unexpected_winners: &HashSet<String>,
+    stats: &ScanStats,
+    file_size: impl Fn(&Path) -> IoResult<u64>,
+) -> ScanSummary {
+    let mut summary = ScanSummary {
+        total: conflicts.len(),
+        unexpected_winners: unexpected_winners.len(),
+        failed_archives: stats.failed_archives,
+        skipped_empty: stats.skipped_empty,
+        resources: stats.resources,
+        providers: stats.providers,
+        archives: stats.archives,
+        loose_files: stats.loose_files,
+        ..Default::default()
+    };
+    let mut by_type = HashMap::<String, usize>::new();
+
+    for (key, paths) in conflicts {
+        if !is_unresolved(key, paths) {
+            continue;
+        }
+
+        summary.unresolved += 1;
+        if is_actionable(paths) {
+            summary.actionable += 1;
+        }
+        if shadows_core(paths, bioware_dir) {
+            summary.core_shadowing += 1;
+        }
+
+        match divergence.get(key) {
+            Some(Divergence::Divergent) => summary.divergent += 1,
+            Some(Divergence::Identical) => summary.identical += 1,
+            None => {}
+        }
+
+        *by_type.entry(resource_type(key)).or_default() += 1;
+
+        let winner = resolve_winner(paths);
+        summary.reclaimable_bytes += paths
+            .iter()
+            .enumerate()
+            .filter(|&(index, path)| {
+                Some(index) != winner && SourceKind::of(path) == SourceKind::Loose
+            })
+            .filter_map(|(_, path)| file_size(path).ok())
+            .sum::<u64>();
+    }
+
+    summary.by_type = by_type.into_iter().collect();
+    summary
+        .by_type
+        .sort_by_key(|(ext, count)| (Reverse(*count), ext.clone()));
+
+    summary
+}
+
+/// The lowercase extension of a conflict's resource, e.g. `gda`.
+pub fn resource_type(key: &str) -> String {
+    Path::new(resource_name(key)).extension().map_or_else(
+        || "(none)".to_string(),
+        |ext| ext.to_string_lossy().to_lowercase(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_a_synthetic_scan() {
+        let bioware_dir = Path::new("/BioWare");
+        let source = |relative: &str| bioware_dir.join(relative);
+        let conflicts: Conflicts = [
+            (
+                "a.uti",
+                vec![
+                    source("packages/core/data/core.erf"),
+                    source("packages/core/override/moda/a.uti"),
+                    source("packages/core/override/modb/a.uti"),
+                ],
+            ),
+            (
+                "b.gda",
+                vec![
+                    source("packages/core/data/core.erf"),
+                    source("AddIns/modx/core/data/modx.erf"),
+                ],
+            ),
+            (
+                "c.uti",
+                vec![
+                    source("packages/core/override/moda/c.uti"),
+                    source("packages/core/override/modb/c.uti"),
+                ],
+            ),
+            (
+                "d.uti",
+                vec![
+                    source("packages/core/override/modc/d.uti"),
+                    source("packages/core/override/modd/d.uti"),
+                ],
+            ),
+        ]
+        .into_iter()
+        .map(|(key, paths)| (key.to_string(), paths))
+        .collect();
+        let ignored: Conflicts = [("c.uti".to_string(), conflicts["c.uti"].clone())]
+            .into_iter()
+            .collect();
+        let divergence = HashMap::from([
+            ("a.uti".to_string(), Divergence::Divergent),
+            ("b.gda".to_string(), Divergence::Identical),
+        ]);
+        let unexpected = HashSet::from(["b.gda".to_string()]);
+        let stats = ScanStats {
+            failed_archives: 1,
+            skipped_empty: 2,
+            loose_files: 6,
+            archives: 2,
+            resources: 10,
+            providers: 14,
+            ..Default::default()
+        };
+
+        let summary = summarize(
+            bioware_dir,
+            &conflicts,
+            |key, paths| ignored.get(key).is_none_or(|p| p != paths),
+            &divergence,
+            &unexpected,
+            &stats,
+            |path: &Path| {
+                Ok(if path.ends_with("moda/a.uti") {
+                    100
+                } else {
+                    40
+                })
+            },
+        );
+
+        assert_eq!(
+            summary,
+            ScanSummary {
+                total: 4,
+                unresolved: 3,
+                actionable: 2,
+                unexpected_winners: 1,
+                core_shadowing: 1,
+                divergent: 1,
+                identical: 1,
+                by_type: vec![("uti".to_string(), 2), ("gda".to_string(), 1)],
+                reclaimable_bytes: 140,
+                failed_archives: 1,
+                skipped_empty: 2,
+                resources: 10,
+                providers: 14,
+                archives: 2,
+                loose_files: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn resolved_conflicts_are_left_out() {
+        let conflicts: Conflicts = ["a.uti", "b.uti"]
+            .into_iter()
+            .map(|key| {
+                let paths = vec![
+                    PathBuf::from(format!("/BioWare/packages/core/override/moda/{key}")),
+                    PathBuf::from(format!("/BioWare/packages/core/override/modb/{key}")),
+                ];
+                (key.to_string(), paths)
+            })
+            .collect();
+
+        let summary = summarize(
+            Path::new("/BioWare"),
+            &conflicts,
+            |key, _| key != "b.uti",
+            &HashMap::new(),
+            &HashSet::new(),
+            &ScanStats::default(),
+            |_: &Path| Ok(10),
+        );
+
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.unresolved, 1);
+        assert_eq!(summary.actionable, 1);
+        assert_eq!(summary.reclaimable_bytes, 10);
+    }
+
+    #[test]
+    fn resource_type_ignores_the_language_suffix() {
+        assert_eq!(resource_type("Line.FSB [fr-fr]"), "fsb");
+        assert_eq!(resource_type("readme"), "(none)");
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use thiserror::Error as ThisError;
+
+/// Most strings read from one table, huge tables are cut off past this.
+pub const MAX_TLK_ENTRIES: usize = 50_000;
+
+const GFF_MAGIC: &[u8; 8] = b"GFF V4.0";
+const TLK_TYPE: &[u8; 4] = b"TLK ";
+const UNCOMPRESSED_VERSION: &[u8; 4] = b"V0.2";
+const HEADER_SIZE: usize = 28;
+const STRUCT_DEF_SIZE: usize = 16;
+const FIELD_DEF_SIZE: usize = 12;
+const NO_OFFSET: u32 = 0xFFFF_FFFF;
+
+const STRING_LIST_LABEL: u32 = 19001;
+const STRING_ID_LABEL: u32 = 19002;
+const STRING_LABEL: u32 = 19003;
+
+#[derive(Debug, ThisError)]
+pub enum TlkError {
+    #[error("Not a GFF V4.0 talk table")]
+    InvalidHeader,
+
+    #[error("Unsupported talk table version {0}, only uncompressed V0.2 tables can be read")]
+    UnsupportedVersion(String),
+
+    #[error("Talk table is truncated or malformed")]
+    Malformed,
+}
+
+pub type TlkResult<T> = Result<T, TlkError>;
+
+/// Strings of a DA:O talk table by string id.
+#[derive(Debug, Default)]
+pub struct TalkTable {
+    pub strings: BTreeMap<u32, String>,
+    /// The table held more than the requested number of entries.
+    pub truncated: bool,
+}
+
+/// A string id whose text differs between two tables, `None` where it's missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringDiff {
+    pub id: u32,
+    pub first: Option<String>,
+    pub second: Option<String>,
+}
+
+impl TalkTable {
+    /// Reads at most `limit` strings from an uncompressed (V0.2) talk table.
+    pub fn parse(data: &[u8], limit: usize) -> TlkResult<Self> {
+        if data.len() < HEADER_SIZE || &data[0..8] != GFF_MAGIC || &data[12..16] != TLK_TYPE {
+            return Err(TlkError::InvalidHeader);
+        }
+        if &data[16..20] != UNCOMPRESSED_VERSION {
+            return Err(TlkError::UnsupportedVersion(
+                String::from_utf8_lossy(&data[16..20]).into_owned(),
+            ));
+        }
+
+        let gff = Gff {
+            data,
+            data_offset: read_u32(data, 24)? as usize,
+        };
+
+        let root = gff.struct_def(0)?;
+        let (list_type, list_offset) = gff.field(&root, 0, STRING_LIST_LABEL)?;
+        let item = gff.struct_def(list_type as usize)?;
+
+        let list_pos = gff.reference(list_offset)?;
+        let Some(list_pos) = list_pos else {
+            return Ok(Self::default());
+        };
+
+        let count = read_u32(data, list_pos)? as usize;
+        let mut table = Self {
+            truncated: count > limit,
+            ..Default::default()
+        };
+
+        for index in 0..count.min(limit) {
+            // Instances are relative to the data block, like field offsets
+            let base = list_offset as usize + 4 + index * item.size;
+            let (_, id) = gff.field(&item, base, STRING_ID_LABEL)?;
+            let (_, text_offset) = gff.field(&item, base, STRING_LABEL)?;
+            let text = match gff.reference(text_offset)? {
+                Some(pos) => read_string(data, pos)?,
+                None => String::new(),
+            };
+
+            table.strings.insert(id, text);
+        }
+
+        Ok(table)
+    }
+}
+
+/// String ids added, removed or reworded between two tables, in id order.
+pub fn diff_tables(first: &TalkTable, second: &TalkTable) -> Vec<StringDiff> {
+    let ids: BTreeSet<u32> = first
+        .strings
+        .keys()
+        .chain(second.strings.keys())
+        .copied()
+        .collect();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let first = first.strings.get(&id);
+            let second = second.strings.get(&id);
+            (first != second).then(|| StringDiff {
+                id,
+                first: first.cloned(),
+                second: second.cloned(),
+            })
+        })
+        .collect()
+}
+
+struct StructDef {
+    field_count: usize,
+    field_offset: usize,
+    size: usize,
+}
+
+/// The minimal subset of the GFF V4.0 layout needed to walk a talk table.
+struct Gff<'a> {
+    data: &'a [u8],
+    data_offset: usize,
+}
+
+impl Gff<'_> {
+    fn struct_def(&self, index: usize) -> TlkResult<StructDef> {
+        let pos = HEADER_SIZE + index * STRUCT_DEF_SIZE;
+
+        Ok(StructDef {
+            field_count: read_u32(self.data, pos + 4)? as usize,
+            field_offset: read_u32(self.data, pos + 8)? as usize,
+            size: read_u32(self.data, pos + 12)? as usize,
+        })
+    }
+
+    /// Finds the field labelled `label` and returns its type id and raw value,
+    /// reading the struct instance at `base` within the data block.
+    fn field(&self, def: &StructDef, base: usize, label: u32) -> TlkResult<(u32, u32)> {
+        for index in 0..def.field_count {
+            let pos = def.field_offset + index * FIELD_DEF_SIZE;
+            if read_u32(self.data, pos)? != label {
+                continue;
+            }
+
+            let type_id = read_u32(self.data, pos + 4)? & 0xFFFF;
+            let offset = read_u32(self.data, pos + 8)? as usize;
+            let value = read_u32(self.data, self.data_offset + base + offset)?;
+            return Ok((type_id, value));
+        }
+
+        Err(TlkError::Malformed)
+    }
+
+    /// Resolves an offset into the data block, `None` for an unset reference.
+    fn reference(&self, offset: u32) -> TlkResult<Option<usize>> {
+        if offset == NO_OFFSET {
+            return Ok(None);
+        }
+
+        let pos = self.data_offset + offset as usize;
+        if pos >= self.data.len() {
+            return Err(TlkError::Malformed);
+        }
+        Ok(Some(pos))
+    }
+}
+
+fn read_u32(data: &[u8], pos: usize) -> TlkResult<u32> {
+    data.get(pos..pos + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or(TlkError::Malformed)
+}
+
+/// Reads a length-prefixed UTF-16 string.
+fn read_string(data: &[u8], pos: usize) -> TlkResult<String> {
+    let len = read_u32(data, pos)? as usize;
+    let bytes = data
+        .get(pos + 4..pos + 4 + len * 2)
+        .ok_or(TlkError::Malformed)?;
+
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect();
+    Ok(String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::tlk;
+
+    fn strings(table: &TalkTable) -> Vec<(u32, &str)> {
+        table
+            .strings
+            .iter()
+            .map(|(id, text)| (*id, text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_the_entries_of_a_tiny_table() {
+        let data = tlk(&[(100, "Alistair"), (7, ""), (250_000, "Ça va, Morrigan?")]);
+
+        let table = TalkTable::parse(&data, MAX_TLK_ENTRIES).unwrap();
+
+        assert_eq!(
+            strings(&table),
+            [(7, ""), (100, "Alistair"), (250_000, "Ça va, Morrigan?")]
+        );
+        assert!(!table.truncated);
+    }
+
+    #[test]
+    fn huge_tables_are_cut_off_at_the_limit() {
+        let data = tlk(&[(1, "one"), (2, "two"), (3, "three")]);
+
+        let table = TalkTable::parse(&data, 2).unwrap();
+
+        assert_eq!(strings(&table), [(1, "one"), (2, "two")]);
+        assert!(table.truncated);
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_reworded_strings() {
+        let first = TalkTable::parse(&tlk(&[(1, "same"), (2, "old"), (3, "gone")]), 10).unwrap();
+        let second = TalkTable::parse(&tlk(&[(1, "same"), (2, "new"), (4, "added")]), 10).unwrap();
+
+        let diff = |id, first: Option<&str>, second: Option<&str>| StringDiff {
+            id,
+            first: first.map(str::to_string),
+            second: second.map(str::to_string),
+        };
+        assert_eq!(
+            diff_tables(&first, &second),
+            [
+                diff(2, Some("old"), Some("new")),
+                diff(3, Some("gone"), None),
+                diff(4, None, Some("added")),
+            ]
+        );
+    }
+
+    #[test]
+    fn compressed_tables_are_reported_as_unsupported() {
+        let mut data = tlk(&[(1, "one")]);
+        data[16..20].copy_from_slice(b"V0.5");
+
+        assert!(matches!(
+            TalkTable::parse(&data, 10),
+            Err(TlkError::UnsupportedVersion(version)) if version == "V0.5"
+        ));
+        assert!(matches!(
+            TalkTable::parse(b"GFF V4.0PC  UTC ", 10),
+            Err(TlkError::InvalidHeader)
+        ));
+    }
+}
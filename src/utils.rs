@@ -1,21 +1,568 @@
-use std::{fs, io::Result as IoResult, path::Path, process::Command};
+use std::{
+    fs::{self, File, FileTimes, Metadata},
+    io::{self, Result as IoResult},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+pub const DISABLED_EXTENSION: &str = "disabled";
+
+/// Sends a file to the OS recycle bin, so a deletion can still be restored.
 pub fn delete(path: &Path) -> IoResult<()> {
-    fs::remove_file(path)
+    trash::delete(path).map_err(trash_to_io)
+}
+
+fn trash_to_io(err: trash::Error) -> io::Error {
+    match err {
+        // Win32 errors wrapped in an HRESULT, keeping sharing violations detectable
+        trash::Error::Os { code, .. } if (code as u32) >> 16 == 0x8007 => {
+            io::Error::from_raw_os_error(code & 0xFFFF)
+        }
+        #[cfg(all(unix, not(target_os = "macos")))]
+        trash::Error::FileSystem { source, .. } => source,
+        trash::Error::CouldNotAccess { .. } => {
+            io::Error::new(io::ErrorKind::NotFound, err.to_string())
+        }
+        err => io::Error::other(err.to_string()),
+    }
+}
+
+/// Whether an I/O error means another process has the file open, e.g. the game.
+pub fn is_in_use(err: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    if cfg!(windows) && matches!(err.raw_os_error(), Some(32 | 33)) {
+        return true;
+    }
+
+    matches!(
+        err.kind(),
+        io::ErrorKind::ResourceBusy | io::ErrorKind::ExecutableFileBusy
+    )
+}
+
+/// Copies a file into `backup_dir`, keeping its path relative to `root`.
+///
+/// Files outside `root` are backed up by file name only.
+pub fn backup_file(path: &Path, root: &Path, backup_dir: &Path) -> IoResult<PathBuf> {
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .map(Path::to_path_buf)
+        .or_else(|| path.file_name().map(PathBuf::from))
+        .unwrap_or_default();
+
+    let target = backup_dir.join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    copy_with_metadata(path, &target)?;
+    Ok(target)
+}
+
+/// Disables a file by appending a `.disabled` extension, keeping its metadata.
+pub fn disable(path: &Path) -> IoResult<PathBuf> {
+    let mut target = path.as_os_str().to_owned();
+    target.push(format!(".{DISABLED_EXTENSION}"));
+
+    let target = PathBuf::from(target);
+    move_with_metadata(path, &target)?;
+    Ok(target)
+}
+
+/// Restores a file disabled by [`disable`], keeping its metadata.
+pub fn enable(path: &Path) -> IoResult<PathBuf> {
+    let target = path.with_extension("");
+    move_with_metadata(path, &target)?;
+    Ok(target)
+}
+
+pub fn is_disabled(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(DISABLED_EXTENSION))
+}
+
+/// Moves a file, falling back to copy + remove when a rename isn't possible.
+///
+/// Both paths keep the modification time and read-only flag of the original, so
+/// mod managers and mtime-based checks don't see the file as changed. Fails with
+/// `AlreadyExists` rather than replacing a file already at `to`, e.g. a
+/// reinstalled copy of a disabled file.
+pub fn move_with_metadata(from: &Path, to: &Path) -> IoResult<()> {
+    if to.try_exists()? {
+        return Err(already_exists(to));
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_new_with_metadata(from, to)?;
+    fs::remove_file(from)
+}
+
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.display()),
+    )
+}
+
+pub fn copy_with_metadata(from: &Path, to: &Path) -> IoResult<()> {
+    let metadata = fs::metadata(from)?;
+    fs::copy(from, to)?;
+    restore_times(to, &metadata)
+}
+
+/// Like [`copy_with_metadata`], but never replaces an existing file at `to`.
+fn copy_new_with_metadata(from: &Path, to: &Path) -> IoResult<()> {
+    let metadata = fs::metadata(from)?;
+    let mut target = File::options()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::AlreadyExists => already_exists(to),
+            _ => err,
+        })?;
+    io::copy(&mut File::open(from)?, &mut target)?;
+    drop(target);
+
+    fs::set_permissions(to, metadata.permissions())?;
+    restore_times(to, &metadata)
+}
+
+fn restore_times(path: &Path, metadata: &Metadata) -> IoResult<()> {
+    let times = FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+
+    open_for_times(path)?.set_times(times)
+}
+
+#[cfg(windows)]
+fn open_for_times(path: &Path) -> IoResult<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // FILE_WRITE_ATTRIBUTES works even when the file is read-only
+    File::options().access_mode(0x100).open(path)
+}
+
+#[cfg(not(windows))]
+fn open_for_times(path: &Path) -> IoResult<File> {
+    File::open(path)
+}
+
+/// Lowers the I/O and scheduling priority of the current thread until dropped.
+///
+/// Only has an effect on Windows, where it keeps scans from competing with a
+/// running game for the disk.
+pub struct BackgroundPriority {
+    active: bool,
+}
+
+impl BackgroundPriority {
+    pub fn enter() -> Self {
+        Self {
+            active: set_background_mode(true),
+        }
+    }
+}
+
+impl Drop for BackgroundPriority {
+    fn drop(&mut self) {
+        if self.active {
+            set_background_mode(false);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn set_background_mode(enabled: bool) -> bool {
+    use windows::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN,
+        THREAD_MODE_BACKGROUND_END,
+    };
+
+    let mode = if enabled {
+        THREAD_MODE_BACKGROUND_BEGIN
+    } else {
+        THREAD_MODE_BACKGROUND_END
+    };
+
+    // SAFETY: the pseudo handle always refers to the calling thread
+    unsafe { SetThreadPriority(GetCurrentThread(), mode) }.is_ok()
+}
+
+#[cfg(not(windows))]
+fn set_background_mode(_enabled: bool) -> bool {
+    false
+}
+
+/// Attaches stdout and stderr to the console the app was started from, which
+/// a GUI subsystem build doesn't get on its own.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+    // SAFETY: fails harmlessly when there is no parent console or one is attached
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Formats a unix timestamp as a `YYYY-MM-DD` UTC date.
+pub fn format_date(timestamp: u64) -> String {
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Opens `url` in the default browser.
+pub fn open_url(url: &str) -> IoResult<()> {
+    if cfg!(windows) {
+        Command::new("rundll32")
+            .args(["url.dll,FileProtocolHandler", url])
+            .spawn()?;
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()?;
+    } else {
+        Command::new("xdg-open").arg(url).spawn()?;
+    }
+
+    Ok(())
+}
+
+/// Fills the `{name}` placeholder of a search URL template with `name`,
+/// percent-encoded.
+pub fn search_url(template: &str, name: &str) -> String {
+    template.replace("{name}", &encode_url_component(name))
+}
+
+fn encode_url_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
 }
 
-pub fn open_in_explorer(path: &Path) -> IoResult<()> {
+/// Opens the system file manager with the file selected, or its folder when
+/// selecting isn't possible.
+pub fn reveal_in_file_manager(path: &Path) -> IoResult<()> {
     let absolute_path = path.canonicalize()?;
-    let path_str = absolute_path
-        .display()
+
+    if spawn_select(&absolute_path).is_ok() {
+        return Ok(());
+    }
+
+    let folder = absolute_path.parent().unwrap_or(&absolute_path);
+    open_folder(folder)
+}
+
+#[cfg(windows)]
+fn spawn_select(path: &Path) -> IoResult<()> {
+    use std::os::windows::process::CommandExt;
+
+    // Explorer parses its own command line, and `arg` would quote the switch
+    // together with the path, which it doesn't understand
+    Command::new("explorer.exe")
+        .raw_arg(select_argument(path))
+        .spawn()?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn spawn_select(path: &Path) -> IoResult<()> {
+    let status = Command::new("open").arg("-R").arg(path).status()?;
+    status
+        .success()
+        .then_some(())
+        .ok_or_else(|| io::Error::other("open -R failed"))
+}
+
+/// Asks the freedesktop file manager service to show the file, which Nautilus,
+/// Dolphin, Nemo and most others implement.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn spawn_select(path: &Path) -> IoResult<()> {
+    use std::process::Stdio;
+
+    let uri = format!("file://{}", encode_path_uri(path));
+    let status = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{uri}"))
+        .arg("string:")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    status
+        .success()
+        .then_some(())
+        .ok_or_else(|| io::Error::other("No file manager answered over D-Bus"))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn encode_path_uri(path: &Path) -> String {
+    path.display()
         .to_string()
-        .replace('/', "\\")
-        .replace(r"\\?\", "");
+        .split('/')
+        .map(encode_url_component)
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-    // FIX: rarely works
+#[cfg(windows)]
+fn open_folder(folder: &Path) -> IoResult<()> {
     Command::new("explorer.exe")
-        .arg(format!("/select,{}", path_str))
+        .arg(strip_extended_prefix(&folder.display().to_string()))
         .spawn()?;
 
     Ok(())
 }
+
+#[cfg(target_os = "macos")]
+fn open_folder(folder: &Path) -> IoResult<()> {
+    Command::new("open").arg(folder).spawn()?;
+
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn open_folder(folder: &Path) -> IoResult<()> {
+    Command::new("xdg-open").arg(folder).spawn()?;
+
+    Ok(())
+}
+
+/// The `/select,"<path>"` argument Explorer expects, with only the path quoted.
+#[cfg(windows)]
+fn select_argument(path: &Path) -> String {
+    let path_str = strip_extended_prefix(&path.display().to_string()).replace('/', "\\");
+    format!("/select,\"{path_str}\"")
+}
+
+/// Turns `\\?\C:\...` and `\\?\UNC\server\...` back into paths Explorer accepts.
+#[cfg(windows)]
+fn strip_extended_prefix(path: &str) -> String {
+    if let Some(unc) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{unc}")
+    } else {
+        path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::write;
+
+    fn age(path: &Path) -> SystemTime {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_300_000_000);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(FileTimes::new().set_modified(modified))
+            .unwrap();
+        modified
+    }
+
+    fn modified(path: &Path) -> SystemTime {
+        fs::metadata(path).unwrap().modified().unwrap()
+    }
+
+    #[test]
+    fn disabled_then_enabled_file_keeps_its_mtime() {
+        let dir = TempDir::new().unwrap();
+        let path = write(dir.path(), "override/armor.uti", b"armor");
+        let original = age(&path);
+
+        let disabled = disable(&path).unwrap();
+        assert!(is_disabled(&disabled));
+        assert_eq!(modified(&disabled), original);
+
+        let enabled = enable(&disabled).unwrap();
+        assert_eq!(enabled, path);
+        assert_eq!(modified(&enabled), original);
+    }
+
+    #[test]
+    fn enabling_onto_an_existing_file_keeps_both() {
+        let dir = TempDir::new().unwrap();
+        let live = write(dir.path(), "override/armor.uti", b"reinstalled");
+        let disabled = write(dir.path(), "override/armor.uti.disabled", b"old");
+
+        let err = enable(&disabled).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&live).unwrap(), b"reinstalled");
+        assert_eq!(fs::read(&disabled).unwrap(), b"old");
+
+        let err = disable(&live).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&live).unwrap(), b"reinstalled");
+        assert_eq!(fs::read(&disabled).unwrap(), b"old");
+    }
+
+    #[test]
+    fn fallback_copy_refuses_an_existing_target() {
+        let dir = TempDir::new().unwrap();
+        let from = write(dir.path(), "a.uti", b"new");
+        let to = write(dir.path(), "b.uti", b"old");
+
+        let err = copy_new_with_metadata(&from, &to).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&to).unwrap(), b"old");
+    }
+
+    #[test]
+    fn copies_keep_the_original_mtime() {
+        let dir = TempDir::new().unwrap();
+        let path = write(dir.path(), "armor.uti", b"armor");
+        let original = age(&path);
+        let copy = dir.path().join("copy.uti");
+
+        copy_with_metadata(&path, &copy).unwrap();
+
+        assert_eq!(modified(&copy), original);
+        assert_eq!(fs::read(&copy).unwrap(), b"armor");
+    }
+
+    #[test]
+    fn search_url_encodes_the_resource_name() {
+        let template = "https://dragonage.fandom.com/wiki/Special:Search?query={name}";
+
+        assert_eq!(
+            search_url(template, "gen00fl_dog.dlg"),
+            "https://dragonage.fandom.com/wiki/Special:Search?query=gen00fl_dog.dlg"
+        );
+        assert_eq!(
+            search_url(template, "a&b c+d/é#1.uti"),
+            "https://dragonage.fandom.com/wiki/Special:Search?query=a%26b%20c%2Bd%2F%C3%A9%231.uti"
+        );
+        assert_eq!(
+            search_url("https://example.com/{name}/{name}", "x y"),
+            "https://example.com/x%20y/x%20y"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn select_argument_quotes_only_the_path() {
+        assert_eq!(
+            select_argument(Path::new(r"\\?\C:\Users\Me\Documents\BioWare\a b.uti")),
+            r#"/select,"C:\Users\Me\Documents\BioWare\a b.uti""#
+        );
+        assert_eq!(
+            select_argument(Path::new(r"\\?\UNC\nas\games/override/a.uti")),
+            r#"/select,"\\nas\games\override\a.uti""#
+        );
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    #[test]
+    fn file_manager_uri_encodes_each_path_component() {
+        assert_eq!(
+            encode_path_uri(Path::new("/home/me/Dragon Age/override/a#1.uti")),
+            "/home/me/Dragon%20Age/override/a%231.uti"
+        );
+    }
+
+    #[test]
+    fn in_use_errors_are_told_apart() {
+        for kind in [
+            io::ErrorKind::ResourceBusy,
+            io::ErrorKind::ExecutableFileBusy,
+        ] {
+            assert!(is_in_use(&io::Error::from(kind)));
+        }
+        for kind in [io::ErrorKind::NotFound, io::ErrorKind::PermissionDenied] {
+            assert!(!is_in_use(&io::Error::from(kind)));
+        }
+        assert_eq!(is_in_use(&io::Error::from_raw_os_error(32)), cfg!(windows));
+    }
+
+    #[test]
+    fn recycle_bin_sharing_violation_keeps_its_os_code() {
+        // HRESULT_FROM_WIN32(ERROR_SHARING_VIOLATION)
+        let err = trash_to_io(trash::Error::Os {
+            code: 0x8007_0020_u32 as i32,
+            description: "The file is in use".to_string(),
+        });
+
+        assert_eq!(err.raw_os_error(), Some(32));
+    }
+
+    // Windows refuses to enter background mode twice or to leave it when not
+    // in it, which tells whether the guard switched the thread and back
+    #[cfg(windows)]
+    #[test]
+    fn background_priority_is_restored_when_dropped() {
+        {
+            let priority = BackgroundPriority::enter();
+            assert!(priority.active);
+            assert!(!set_background_mode(true));
+        }
+        assert!(!set_background_mode(false));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn background_priority_is_inert_off_windows() {
+        let priority = BackgroundPriority::enter();
+        assert!(!priority.active);
+    }
+}
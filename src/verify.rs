@@ -0,0 +1,72 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::erf::ErfFile;
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub checked: usize,
+    /// Problems found, grouped by issue kind, as `(archive, details)` pairs.
+    pub issues: BTreeMap<&'static str, Vec<(PathBuf, String)>>,
+}
+
+impl VerifyReport {
+    pub fn affected_archives(&self) -> usize {
+        let mut archives: Vec<_> = self.issues.values().flatten().map(|(p, _)| p).collect();
+        archives.sort();
+        archives.dedup();
+        archives.len()
+    }
+}
+
+pub fn verify_archives(archives: &[PathBuf]) -> VerifyReport {
+    let mut report = VerifyReport {
+        checked: archives.len(),
+        ..Default::default()
+    };
+
+    for path in archives {
+        for issue in ErfFile::verify(path) {
+            report
+                .issues
+                .entry(issue.kind())
+                .or_default()
+                .push((path.clone(), issue.details()));
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::fixtures::{erf_v20, write};
+
+    #[test]
+    fn batch_report_groups_issues_from_several_archives() {
+        let dir = TempDir::new().unwrap();
+        let healthy = write(dir.path(), "healthy.erf", &erf_v20(&[("a.gda", b"abc")]));
+        let duplicated = write(
+            dir.path(),
+            "duplicated.erf",
+            &erf_v20(&[("b.gda", b"one"), ("B.gda", b"two")]),
+        );
+        let empty = write(dir.path(), "empty.erf", &erf_v20(&[]));
+        let garbage = write(dir.path(), "garbage.erf", b"not an archive at all");
+
+        let report =
+            verify_archives(&[healthy, duplicated.clone(), empty.clone(), garbage.clone()]);
+
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.affected_archives(), 3);
+        let archives_with = |kind: &str| -> Vec<&PathBuf> {
+            report.issues[kind].iter().map(|(path, _)| path).collect()
+        };
+        assert_eq!(archives_with("Duplicate resource name"), [&duplicated]);
+        assert_eq!(archives_with("Empty archive"), [&empty]);
+        assert_eq!(archives_with("Unreadable archive"), [&garbage]);
+        assert_eq!(report.issues.len(), 3);
+    }
+}
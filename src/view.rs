@@ -0,0 +1,571 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use pathdiff::diff_paths;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    loadorder::winner_location,
+    restype::ResourceType,
+    scanner::{
+        Conflicts, SourceKind, addin_root, override_root, resource_name, summarize_conflicts,
+    },
+    summary::resource_type,
+};
+
+pub type ConflictEntry = (String, Vec<PathBuf>);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortMode {
+    #[default]
+    NameAsc,
+    NameDesc,
+    CountDesc,
+    ResolvedAtDesc,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [
+        SortMode::NameAsc,
+        SortMode::NameDesc,
+        SortMode::CountDesc,
+        SortMode::ResolvedAtDesc,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::NameAsc => "Name (A–Z)",
+            SortMode::NameDesc => "Name (Z–A)",
+            SortMode::CountDesc => "Conflict count (high→low)",
+            SortMode::ResolvedAtDesc => "Resolved (newest first)",
+        }
+    }
+}
+
+/// Which conflicts the main list shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListView {
+    #[default]
+    Unresolved,
+    Resolved,
+    All,
+}
+
+impl ListView {
+    pub const ALL: [ListView; 3] = [ListView::Unresolved, ListView::Resolved, ListView::All];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ListView::Unresolved => "Unresolved",
+            ListView::Resolved => "Resolved",
+            ListView::All => "All",
+        }
+    }
+
+    pub fn shows_unresolved(self) -> bool {
+        matches!(self, ListView::Unresolved | ListView::All)
+    }
+
+    pub fn shows_resolved(self) -> bool {
+        matches!(self, ListView::Resolved | ListView::All)
+    }
+}
+
+/// How many of the scanned conflicts are still waiting on a decision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolutionCounts {
+    pub unresolved: usize,
+    pub total: usize,
+}
+
+impl ResolutionCounts {
+    pub fn resolved(self) -> usize {
+        self.total - self.unresolved
+    }
+
+    pub fn describe(self) -> String {
+        format!(
+            "{} unresolved / {} total / {} resolved",
+            self.unresolved,
+            self.total,
+            self.resolved()
+        )
+    }
+}
+
+/// The groups the results list is split into, highest severity first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    CoreShadowing,
+    UnexpectedWinner,
+    UnpairedScript,
+    Other,
+    Resolved,
+}
+
+impl SectionKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            SectionKind::CoreShadowing => "Shadows core content",
+            SectionKind::UnexpectedWinner => "Unexpected winner",
+            SectionKind::UnpairedScript => "Script out of sync",
+            SectionKind::Other => "Conflicts",
+            SectionKind::Resolved => "Resolved",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConflictSection {
+    pub kind: SectionKind,
+    pub entries: Vec<ConflictEntry>,
+}
+
+/// The filter applied to the unresolved conflicts list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultsFilter {
+    pub query: String,
+    /// Lowercase resource extension without the dot, e.g. `dlg`.
+    pub resource_type: Option<String>,
+    /// Resource types to show, every type when empty.
+    pub categories: BTreeSet<ResourceType>,
+}
+
+impl ResultsFilter {
+    pub fn is_empty(&self) -> bool {
+        self.query.trim().is_empty() && self.resource_type.is_none() && self.categories.is_empty()
+    }
+
+    pub fn matches(&self, key: &str, paths: &[PathBuf]) -> bool {
+        matches_filter(key, paths, &self.query)
+            && self
+                .resource_type
+                .as_ref()
+                .is_none_or(|ext| resource_type(key) == *ext)
+            && (self.categories.is_empty()
+                || self
+                    .categories
+                    .contains(&ResourceType::of_name(resource_name(key))))
+    }
+}
+
+/// How the conflicts list is filtered, restored on launch when
+/// remembering the view is enabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub filter: ResultsFilter,
+    pub list_view: ListView,
+    pub only_actionable: bool,
+}
+
+/// The key of the first listed entry matching `filter`, in display order.
+///
+/// Returns `None` for an empty filter, since there is nothing to focus.
+pub fn first_match<'a>(
+    entries: impl IntoIterator<Item = &'a ConflictEntry>,
+    filter: &ResultsFilter,
+) -> Option<&'a str> {
+    if filter.is_empty() {
+        return None;
+    }
+
+    entries
+        .into_iter()
+        .find(|(key, paths)| filter.matches(key, paths))
+        .map(|(key, _)| key.as_str())
+}
+
+/// Case-insensitive match of `query` against the conflict key or any of its paths.
+pub fn matches_filter(key: &str, paths: &[PathBuf], query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return true;
+    }
+
+    key.to_lowercase().contains(&query)
+        || paths
+            .iter()
+            .any(|p| p.to_string_lossy().to_lowercase().contains(&query))
+}
+
+/// Whether at least one source of a conflict is a loose file that can be acted on.
+pub fn is_actionable(paths: &[PathBuf]) -> bool {
+    paths.iter().any(|p| SourceKind::of(p) == SourceKind::Loose)
+}
+
+/// Sorts entries by `mode`, falling back to the name so the order is stable.
+///
+/// `resolved_at` holds the unix timestamps of resolved conflicts; entries without
+/// one sort last under [`SortMode::ResolvedAtDesc`].
+pub fn sort_entries(
+    entries: &mut [ConflictEntry],
+    mode: SortMode,
+    resolved_at: &HashMap<String, u64>,
+) {
+    match mode {
+        SortMode::NameAsc => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+        SortMode::NameDesc => entries.sort_by(|a, b| b.0.cmp(&a.0)),
+        SortMode::CountDesc => {
+            entries.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)))
+        }
+        SortMode::ResolvedAtDesc => entries.sort_by_key(|(key, _)| {
+            (
+                Reverse(resolved_at.get(key).copied().unwrap_or_default()),
+                key.clone(),
+            )
+        }),
+    }
+}
+
+/// Renders sections as a plain-text report, keeping their order and the order of
+/// their entries so the report matches what is shown on screen.
+pub fn render_report(sections: &[ConflictSection], bioware_dir: &Path) -> String {
+    let summary = summarize_conflicts(
+        sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .map(|(key, paths)| (key, paths)),
+    );
+    let mut report = format!("Conflict report\n{}\n", summary.describe());
+
+    for section in sections.iter().filter(|s| !s.entries.is_empty()) {
+        report.push_str(&format!(
+            "\n== {} ({}) ==\n",
+            section.kind.title(),
+            section.entries.len()
+        ));
+
+        for (key, paths) in &section.entries {
+            report.push_str(&format!("{key}\n"));
+            for path in paths {
+                let display_path = diff_paths(path, bioware_dir).unwrap_or_else(|| path.clone());
+                report.push_str(&format!("    {}\n", display_path.display()));
+            }
+        }
+    }
+
+    report
+}
+
+/// Renders sections as CSV with one row per source, paths relative to `bioware_dir`.
+pub fn render_csv(sections: &[ConflictSection], bioware_dir: &Path) -> String {
+    let mut csv = String::from("resource,path,conflict_count\n");
+
+    for (key, paths) in sections.iter().flat_map(|s| &s.entries) {
+        for path in paths {
+            let display_path = diff_paths(path, bioware_dir).unwrap_or_else(|| path.clone());
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                csv_field(key),
+                csv_field(&display_path.display().to_string()),
+                paths.len()
+            ));
+        }
+    }
+
+    csv
+}
+
+/// Quotes a CSV field when it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Serialize)]
+struct ReportEntry<'a> {
+    resource: &'a str,
+    section: &'static str,
+    winner: Option<String>,
+    sources: Vec<PathBuf>,
+}
+
+/// Renders sections as a JSON array of conflicts, paths relative to `bioware_dir`.
+pub fn render_json(
+    sections: &[ConflictSection],
+    conflicts: &Conflicts,
+    bioware_dir: &Path,
+) -> serde_json::Result<String> {
+    let entries: Vec<ReportEntry> = sections
+        .iter()
+        .flat_map(|section| {
+            section.entries.iter().map(|(key, paths)| ReportEntry {
+                resource: key,
+                section: section.kind.title(),
+                winner: winner_location(key, paths, conflicts),
+                sources: paths
+                    .iter()
+                    .map(|path| diff_paths(path, bioware_dir).unwrap_or_else(|| path.clone()))
+                    .collect(),
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&entries)
+}
+
+/// A line in a conflict's source list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceRow {
+    /// The source at this index, shown on its own.
+    Single(usize),
+    /// Adjacent sources sharing an add-in or the override folder.
+    Collapsed {
+        folder: PathBuf,
+        indices: Vec<usize>,
+        /// The subfolder (or file name) of each source within `folder`.
+        labels: Vec<String>,
+    },
+}
+
+impl SourceRow {
+    pub fn summary(&self, bioware_dir: &Path) -> Option<String> {
+        let SourceRow::Collapsed { folder, labels, .. } = self else {
+            return None;
+        };
+        let folder = diff_paths(folder, bioware_dir).unwrap_or_else(|| folder.clone());
+
+        Some(format!(
+            "{} copies in {}: {}",
+            labels.len(),
+            folder.display(),
+            labels.join(", ")
+        ))
+    }
+}
+
+/// Groups runs of adjacent sources from the same add-in or override folder
+/// into one row, leaving sources alone in their folder as single rows.
+pub fn collapse_sources(paths: &[PathBuf], bioware_dir: &Path) -> Vec<SourceRow> {
+    let mut rows = Vec::new();
+    let mut index = 0;
+
+    while index < paths.len() {
+        let folder = source_layer(&paths[index], bioware_dir);
+        let run = paths[index..]
+            .iter()
+            .take_while(|path| source_layer(path, bioware_dir) == folder)
+            .count();
+
+        if run == 1 {
+            rows.push(SourceRow::Single(index));
+        } else {
+            let indices: Vec<usize> = (index..index + run).collect();
+            let labels = indices
+                .iter()
+                .map(|&i| layer_label(&paths[i], &folder))
+                .collect();
+            rows.push(SourceRow::Collapsed {
+                folder,
+                indices,
+                labels,
+            });
+        }
+
+        index += run;
+    }
+
+    rows
+}
+
+/// The add-in or override folder a source belongs to, or its own folder.
+fn source_layer(path: &Path, bioware_dir: &Path) -> PathBuf {
+    if let Some(root) = addin_root(path, bioware_dir) {
+        return root;
+    }
+
+    if let Some(override_dir) = override_root(path, bioware_dir) {
+        return override_dir;
+    }
+
+    path.parent().unwrap_or(path).to_path_buf()
+}
+
+fn layer_label(path: &Path, folder: &Path) -> String {
+    let relative = path.strip_prefix(folder).unwrap_or(path);
+    let first = relative.iter().next().unwrap_or(relative.as_os_str());
+    first.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, paths: &[&str]) -> ConflictEntry {
+        (key.to_string(), paths.iter().map(PathBuf::from).collect())
+    }
+
+    #[test]
+    fn substring_filter_narrows_the_ignored_list() {
+        let ignored = [
+            entry(
+                "armor_plate.uti",
+                &["override/a/armor_plate.uti", "override/b/armor_plate.uti"],
+            ),
+            entry(
+                "gen00fl_dog.dlg",
+                &["override/a/gen00fl_dog.dlg", "packages/core/data/a.erf"],
+            ),
+            entry(
+                "heavy_armor.gda",
+                &["override/c/heavy_armor.gda", "override/d/heavy_armor.gda"],
+            ),
+        ];
+        let filter = ResultsFilter {
+            query: "ARMOR".to_string(),
+            ..Default::default()
+        };
+
+        let mut shown: Vec<_> = ignored
+            .iter()
+            .filter(|(key, paths)| filter.matches(key, paths))
+            .map(|(key, _)| key.as_str())
+            .collect();
+        shown.sort();
+
+        assert_eq!(shown, ["armor_plate.uti", "heavy_armor.gda"]);
+    }
+
+    #[test]
+    fn substring_filter_matches_source_paths() {
+        let ignored = [
+            entry("a.uti", &["override/moda/a.uti", "override/modb/a.uti"]),
+            entry("b.uti", &["override/modc/b.uti", "override/modd/b.uti"]),
+        ];
+        let filter = ResultsFilter {
+            query: " modc ".to_string(),
+            ..Default::default()
+        };
+
+        let shown: Vec<_> = ignored
+            .iter()
+            .filter(|(key, paths)| filter.matches(key, paths))
+            .map(|(key, _)| key.as_str())
+            .collect();
+
+        assert_eq!(shown, ["b.uti"]);
+    }
+
+    #[test]
+    fn export_follows_the_on_screen_sort() {
+        let bioware_dir = Path::new("/BioWare");
+        let mut entries = vec![
+            entry("a.uti", &["/BioWare/x/a.uti", "/BioWare/y/a.uti"]),
+            entry(
+                "b.uti",
+                &["/BioWare/x/b.uti", "/BioWare/y/b.uti", "/BioWare/z/b.uti"],
+            ),
+        ];
+        sort_entries(&mut entries, SortMode::CountDesc, &HashMap::new());
+        let sections = [ConflictSection {
+            kind: SectionKind::Other,
+            entries,
+        }];
+
+        let csv = render_csv(&sections, bioware_dir);
+        let resources: Vec<_> = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap())
+            .collect();
+
+        assert_eq!(resources, ["b.uti", "b.uti", "b.uti", "a.uti", "a.uti"]);
+
+        let report = render_report(&sections, bioware_dir);
+        assert!(report.find("b.uti") < report.find("a.uti"));
+    }
+
+    #[test]
+    fn adjacent_sources_in_one_override_folder_collapse() {
+        let bioware_dir = Path::new("/BioWare");
+        let paths: Vec<_> = [
+            "packages/core/override/01_base/a.uti",
+            "packages/core/override/02_patch/a.uti",
+            "packages/core/override/03_fix/sub/a.uti",
+            "AddIns/modx/core/override/a.uti",
+            "packages/core/data/core.erf",
+        ]
+        .iter()
+        .map(|relative| bioware_dir.join(relative))
+        .collect();
+
+        let rows = collapse_sources(&paths, bioware_dir);
+
+        assert_eq!(
+            rows,
+            [
+                SourceRow::Collapsed {
+                    folder: bioware_dir.join("packages/core/override"),
+                    indices: vec![0, 1, 2],
+                    labels: vec![
+                        "01_base".to_string(),
+                        "02_patch".to_string(),
+                        "03_fix".to_string()
+                    ],
+                },
+                SourceRow::Single(3),
+                SourceRow::Single(4),
+            ]
+        );
+        assert_eq!(
+            rows[0].summary(bioware_dir).as_deref(),
+            Some("3 copies in packages/core/override: 01_base, 02_patch, 03_fix")
+        );
+        assert_eq!(rows[1].summary(bioware_dir), None);
+    }
+
+    #[test]
+    fn first_match_follows_display_order() {
+        let entries = [
+            entry("z_armor.uti", &["override/a/z_armor.uti"]),
+            entry("b.uti", &["override/armor_pack/b.uti"]),
+            entry("armor.uti", &["override/c/armor.uti"]),
+        ];
+        let filter = |query: &str| ResultsFilter {
+            query: query.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(first_match(&entries, &filter("ARMOR")), Some("z_armor.uti"));
+        assert_eq!(first_match(&entries, &filter("armor_pack")), Some("b.uti"));
+        assert_eq!(first_match(&entries, &filter("helmet")), None);
+        assert_eq!(first_match(&entries, &ResultsFilter::default()), None);
+    }
+
+    #[test]
+    fn list_view_selects_which_sets_are_listed() {
+        let shown = |view: ListView| (view.shows_unresolved(), view.shows_resolved());
+
+        assert_eq!(shown(ListView::Unresolved), (true, false));
+        assert_eq!(shown(ListView::Resolved), (false, true));
+        assert_eq!(shown(ListView::All), (true, true));
+        assert_eq!(ListView::default(), ListView::Unresolved);
+    }
+
+    #[test]
+    fn actionable_filter_hides_archive_only_conflicts() {
+        let mut conflicts = vec![
+            entry(
+                "a.uti",
+                &["packages/core/data/a.erf", "modules/x/data/b.rim"],
+            ),
+            entry(
+                "b.uti",
+                &["packages/core/data/a.erf", "override/moda/b.uti"],
+            ),
+        ];
+
+        conflicts.retain(|(_, paths)| is_actionable(paths));
+
+        let shown: Vec<_> = conflicts.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(shown, ["b.uti"]);
+    }
+}